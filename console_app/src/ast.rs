@@ -0,0 +1,90 @@
+#[cfg(test)]
+pub mod tests;
+
+use super::calculator::calculator_parser::expression::{Expr, ExprPrime, Func};
+
+/**
+ * A node of the tree printed for an expression, and the nodes below it
+ */
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Node {
+    label: String,
+    children: Vec<Node>
+}
+
+impl Node {
+    fn leaf(label: String) -> Self {
+        Self {
+            label,
+            children: Vec::new()
+        }
+    }
+
+    fn branch(label: String, children: Vec<Node>) -> Self {
+        Self {
+            label,
+            children
+        }
+    }
+}
+
+/**
+ * Draw the tree a parsed expression evaluates as, with each operator or function above its operands, i.e. for '1 + 2 * 3':
+ *
+ * Operator +
+ * ├── Number 1
+ * └── Operator *
+ *     ├── Number 2
+ *     └── Number 3
+ */
+pub fn tree(expression: &Expr) -> String {
+    let root = match expression {
+        Expr::None => Node::leaf(String::from("Empty")),
+        Expr::ExprPrime(expr_prime) => node(expr_prime)
+    };
+
+    let mut lines = vec![root.label.clone()];
+    draw_children(&root, "", &mut lines);
+
+    lines.join("\n")
+}
+
+fn draw_children(node: &Node, indent: &str, lines: &mut Vec<String>) {
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let (branch, continuation) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+
+        lines.push(format!("{indent}{branch}{}", child.label));
+        draw_children(child, format!("{indent}{continuation}").as_str(), lines);
+    }
+}
+
+fn node(expression: &ExprPrime) -> Node {
+    match expression {
+        ExprPrime::Number(n) => Node::leaf(format!("Number {}", n.value)),
+        ExprPrime::Dice(dice) => Node::leaf(format!("Dice {dice}")),
+        ExprPrime::History(_) => Node::leaf(format!("History {expression}")),
+        ExprPrime::Id(id) => Node::leaf(format!("Variable {}", id.value)),
+        ExprPrime::AccessMem(_) => Node::leaf(format!("Memory {expression}")),
+        ExprPrime::StoreMem(m, subexpr) => Node::branch(format!("Store {}", ExprPrime::AccessMem(*m)), vec![node(subexpr)]),
+        ExprPrime::Func(Func::EmptyFunc(id)) => Node::leaf(format!("Call {}", id.value)),
+        ExprPrime::Func(Func::ConstantFunc(constant)) => Node::leaf(format!("Constant {constant}")),
+        ExprPrime::Func(Func::FuncWithArgs(id, args)) => Node::branch(format!("Call {}", id.value), args.iter().map(node).collect()),
+        ExprPrime::List(elements) => Node::branch(String::from("List"), elements.iter().map(node).collect()),
+        ExprPrime::Range(start, end) => Node::branch(String::from("Range"), vec![node(start), node(end)]),
+        ExprPrime::Index(subexpr, index) => Node::branch(String::from("Index"), vec![node(subexpr), node(index)]),
+        //The tree already shows how the expression is grouped
+        ExprPrime::ParenthesesExpression(subexpr) => node(subexpr),
+        //The prefix nearest the operand is applied first
+        ExprPrime::UnopPrefixesExpression(prefixes, subexpr) => prefixes.iter()
+            .rev()
+            .fold(node(subexpr), |operand, prefix| Node::branch(format!("Prefix {prefix}"), vec![operand])),
+        ExprPrime::UnopSuffixesExpression(subexpr, suffixes) => suffixes.iter()
+            .fold(node(subexpr), |operand, suffix| Node::branch(format!("Suffix {suffix}"), vec![operand])),
+        //Operators of the same precedence are applied from left to right
+        ExprPrime::BinaryInfixExpression(first, siblings) => siblings.iter()
+            .fold(node(first), |left, (operator, right)| Node::branch(format!("Operator {operator}"), vec![left, node(right)])),
+        ExprPrime::BinaryInfixFunctionExpression(first, siblings) => siblings.iter()
+            .fold(node(first), |left, (function, right)| Node::branch(format!("Call {}", function.value), vec![left, node(right)]))
+    }
+}
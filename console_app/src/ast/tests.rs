@@ -0,0 +1,54 @@
+use super::tree;
+use crate::calculator::calculator_parser::parser::Parser;
+
+/**
+ * The input should parse into an expression drawn as the expected lines
+ */
+fn tree_test(input: &str, expected: &[&str]) {
+    match Parser::default().parse(input) {
+        Ok(expression) => assert_eq!(tree(&expression), expected.join("\n"), "Testing '{input}'"),
+        Err(err) => panic!("{err} (testing '{input}')")
+    }
+}
+
+#[test]
+/**
+ * Test that operators are drawn above their operands, grouped by priority rather than by parentheses
+ */
+fn tree_0() {
+    tree_test("", &["Empty"]);
+    tree_test("2", &["Number 2"]);
+    tree_test("1 + 2 * 3", &[
+        "Operator +",
+        "├── Number 1",
+        "└── Operator *",
+        "    ├── Number 2",
+        "    └── Number 3"
+    ]);
+    tree_test("(1 + 2) * 3", &[
+        "Operator *",
+        "├── Operator +",
+        "│   ├── Number 1",
+        "│   └── Number 2",
+        "└── Number 3"
+    ]);
+}
+
+#[test]
+/**
+ * Test that prefixes, suffixes, calls, lists and memory are drawn with their operands below them
+ */
+fn tree_1() {
+    tree_test("-x!", &[
+        "Prefix -",
+        "└── Suffix !",
+        "    └── Variable x"
+    ]);
+    tree_test("$m0: max(1, [2])", &[
+        "Store $m0",
+        "└── Call max",
+        "    ├── Number 1",
+        "    └── List",
+        "        └── Number 2"
+    ]);
+}
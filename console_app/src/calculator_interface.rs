@@ -1,86 +1,522 @@
-use std::io::Write;
+#[cfg(test)]
+pub mod tests;
 
-use super::calculator;
+use colored::Colorize;
+use rustyline::{error::ReadlineError, history::DefaultHistory, Config, Editor};
 
-const EXIT_COMMAND: &str = ":exit";
-const CLEAR_COMMAND: &str = ":clear";
-const LIST_FUNCTIONS_COMMAND: &str = ":list-functions";
-const CLEAR_HISTORY_COMMAND: &str = ":clear-hist";
-const CLEAR_MEMORY_COMMAND: &str = ":clear-mem";
+use std::path::{Path, PathBuf};
+
+use super::{
+    ast,
+    calculator::{self, calculator_interpreter::interpreter::{AngleMode, EvaluateOptions as InterpreterOptions, function::{AngleUse, UserFunction}, value::Value}, calculator_parser::parser::Notation},
+    command::{self, Command},
+    console_err::ConsoleErr,
+    input_helper::InputHelper,
+    output::OutputFormat,
+    plot::{self, PlotRequest},
+    table::{self, TableRequest}
+};
+
+const PRECISION_SETTING: &str = "precision";
+const ANGLE_SETTING: &str = "angle";
+const NOTATION_SETTING: &str = "notation";
+const FORMAT_SETTING: &str = "format";
+
+/**
+ * The most inputs kept in the input history file
+ */
+const MAX_INPUT_HISTORY: usize = 1000;
+
+/**
+ * The most changes to history and memory that can be undone
+ */
+const MAX_UNDO: usize = 100;
 
 #[derive(Debug, Clone, Default)]
 pub struct ConsoleUI {
-    calculator: calculator::Calculator
+    calculator: calculator::Calculator,
+    /**
+     * The states of the calculator before each change, most recent last
+     */
+    undo: Vec<calculator::CalculatorState>,
+    /**
+     * The number of decimal places results are displayed to, or all of them if none
+     */
+    precision: Option<usize>,
+    /**
+     * The format tables are written in
+     */
+    format: OutputFormat,
+    /**
+     * The number of columns and rows of the terminal, if it is one
+     */
+    terminal_size: Option<(usize, usize)>
 }
 
 impl ConsoleUI {
     pub fn new(calculator: calculator::Calculator) -> Self {
         Self {
-            calculator
+            calculator,
+            ..Default::default()
         }
     }
 
     pub fn start(&mut self) -> Result<(), String> {
-        println!("Enter the expression to evaluate, '{CLEAR_COMMAND}' to clear the screen, '{CLEAR_HISTORY_COMMAND}' to clear result history, '{CLEAR_MEMORY_COMMAND}' to clear calculator memory, '{LIST_FUNCTIONS_COMMAND}' to print a list of valid functions, or '{EXIT_COMMAND}' to exit.");
+        println!("Enter the expression to evaluate, '{}' for a list of commands, or '{}' to exit.", command::HELP_COMMAND, command::EXIT_COMMAND);
+
+        //Continue from where the previous session left off
+        let autosave_path = Self::autosave_path();
+
+        if let Some(path) = autosave_path.as_ref().filter(|path| path.exists()) {
+            match self.load_session(path) {
+                Ok(_) => println!("{}", "Restored previous session.".dimmed()),
+                Err(err) => eprintln!("Failed to restore previous session: {err}")
+            }
+        }
+
+        let mut editor = Self::create_editor()?;
+        let history_path = Self::input_history_path();
+
+        if let Some(path) = &history_path {
+            //There is no history file the first time the calculator is run
+            _ = editor.load_history(path);
+        }
 
         loop {
-            print!("> ");
-            
-            match std::io::stdout().flush() {
-                Ok(_) => (),
-                Err(err) => {
-                    eprintln!("Failed to flush stdout: {err}");
-                    continue;
-                }
+            if let Some(helper) = editor.helper_mut() {
+                helper.update(&self.calculator, self.precision);
+            }
+
+            //The terminal may have been resized since the last input
+            self.terminal_size = editor.dimensions();
+
+            let input = match editor.readline("> ") {
+                Ok(line) => String::from(line.trim_end()),
+                //Ctrl-C discards the current input
+                Err(ReadlineError::Interrupted) => continue,
+                //Ctrl-D exits
+                Err(ReadlineError::Eof) => {
+                    println!("Exiting...");
+                    break;
+                },
+                Err(err) => return Err(format!("Failed to read input: {err}"))
+            };
+
+            if input.trim().is_empty() {
+                continue;
             }
 
-            let mut input = String::new();
+            if let Err(err) = editor.add_history_entry(input.as_str()) {
+                eprintln!("Failed to add input to history: {err}");
+            }
 
-            match std::io::stdin().read_line(&mut input) {
-                Ok(_) => (),
-                Err(err) => {
-                    eprintln!("Failed to read input: {err}");
-                    continue;
+            if let Some(path) = &history_path {
+                if let Err(err) = Self::save_input_history(&mut editor, path) {
+                    eprintln!("Failed to save input history: {err}");
                 }
             }
 
-            input = String::from(input.trim_end());
+            match Command::parse(&input) {
+                Some(Ok(Command::Exit)) => {
+                    println!("Exiting...");
+                    break;
+                },
+                Some(Ok(command)) => self.run_command(command, autosave_path.as_deref()),
+                Some(Err(err)) => eprintln!("{err}"),
+                None => self.evaluate(&input, autosave_path.as_deref())
+            }
+        };
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self, input: &str, autosave_path: Option<&Path>) {
+        if self.calculator.notation() == Notation::Postfix {
+            self.evaluate_stack(input, autosave_path);
+            return;
+        }
 
-            if input.eq_ignore_ascii_case(EXIT_COMMAND) {
-                println!("Exiting...");
-                break;
+        let previous = self.calculator.clone_current_state();
+
+        let (evaluated, state) = match self.calculator.evaluate_with_options(input, calculator::EvaluateOptions::default()) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("{e}");
+                return;
             }
-            else if input.eq_ignore_ascii_case(CLEAR_COMMAND) {
-                print!("{esc}c", esc = 27 as char);
-                continue;
+        };
+
+        self.push_undo(previous);
+
+        //Show the individual dice rolls that made up the result
+        for roll in state.rolls {
+            println!("{}", roll.to_string().dimmed());
+        }
+
+        println!("{}", display_value(&evaluated, self.precision));
+
+        self.autosave(autosave_path);
+    }
+
+    /**
+     * Evaluate postfix input against history as a stack, printing each value it pushes
+     */
+    fn evaluate_stack(&mut self, input: &str, autosave_path: Option<&Path>) {
+        let previous = self.calculator.clone_current_state();
+
+        let pushed = match self.calculator.evaluate_postfix_stack(input) {
+            Ok(pushed) => pushed,
+            Err(e) => {
+                eprintln!("{e}");
+                return;
             }
-            else if input.eq_ignore_ascii_case(CLEAR_HISTORY_COMMAND) {
+        };
+
+        self.push_undo(previous);
+
+        for value in pushed {
+            println!("{}", display_value(&value, self.precision));
+        }
+
+        self.autosave(autosave_path);
+    }
+
+    fn run_command(&mut self, command: Command, autosave_path: Option<&Path>) {
+        match command {
+            //Handled by the loop, which it breaks out of
+            Command::Exit => {},
+            Command::Clear => print!("{esc}c", esc = 27 as char),
+            Command::ClearHistory => {
+                self.push_undo(self.calculator.clone_current_state());
                 self.calculator.clear_stack();
                 println!("Cleared calculator history.");
-                continue;
-            }
-            else if input.eq_ignore_ascii_case(CLEAR_MEMORY_COMMAND) {
+                self.autosave(autosave_path);
+            },
+            Command::ClearMemory => {
+                self.push_undo(self.calculator.clone_current_state());
                 self.calculator.clear_mem();
                 println!("Cleared calculator memory.");
-                continue;
+                self.autosave(autosave_path);
+            },
+            Command::Save(path) => match self.save_session(Path::new(&path)) {
+                Ok(_) => println!("Saved session to '{path}'."),
+                Err(err) => eprintln!("Failed to save session to '{path}': {err}")
+            },
+            Command::Load(path) => {
+                let previous = self.calculator.clone_current_state();
+
+                match self.load_session(Path::new(&path)) {
+                    Ok(_) => {
+                        self.push_undo(previous);
+                        println!("Loaded session from '{path}'.");
+                        self.autosave(autosave_path);
+                    },
+                    Err(err) => eprintln!("Failed to load session from '{path}': {err}")
+                }
+            },
+            Command::ListFunctions(search) => self.print_functions(search.as_deref().unwrap_or_default()),
+            Command::History(count) => self.print_history(count),
+            Command::Memory => self.print_memory(),
+            Command::Help(None) => {
+                for (usage, description) in command::COMMAND_HELP {
+                    println!("{usage:<32}{}", description.dimmed());
+                }
+            },
+            Command::Help(Some(name)) => self.print_function_help(&name),
+            Command::Ast(expression) => match self.calculator.parse(&expression) {
+                Ok(parsed) => println!("{}", ast::tree(&parsed)),
+                Err(err) => eprintln!("{err}")
+            },
+            Command::Undo => match self.undo.pop() {
+                Some(state) => {
+                    self.calculator.restore_state(state);
+                    println!("Undid the last change to history and memory.");
+                    self.autosave(autosave_path);
+                },
+                None => eprintln!("Nothing to undo.")
+            },
+            Command::Set(None, _) => {
+                println!("{PRECISION_SETTING} = {}", self.precision.map(|precision| precision.to_string()).unwrap_or(String::from("off")));
+                println!("{ANGLE_SETTING} = {}", self.calculator.angle_mode());
+                println!("{NOTATION_SETTING} = {}", self.calculator.notation());
+                println!("{FORMAT_SETTING} = {}", self.format);
+            },
+            Command::Set(Some(setting), value) => {
+                if let Err(err) = self.set(&setting, value.as_deref()) {
+                    eprintln!("{err}");
+                }
+            },
+            Command::Plot(argument) => match PlotRequest::parse(&self.calculator, &argument) {
+                Ok(request) => {
+                    for line in plot::render(&self.calculator, &request, self.terminal_size.unwrap_or(plot::DEFAULT_SIZE)) {
+                        println!("{line}");
+                    }
+                },
+                Err(err) => eprintln!("{err}")
+            },
+            Command::Table(argument) => {
+                let lines = TableRequest::parse(&self.calculator, &argument)
+                    .and_then(|request| table::render(&self.calculator, &request, self.format, |value| round_value(value, self.precision)));
+
+                match lines {
+                    Ok(lines) => {
+                        for line in lines {
+                            println!("{line}");
+                        }
+                    },
+                    Err(err) => eprintln!("{err}")
+                }
             }
-            else if input.eq_ignore_ascii_case(LIST_FUNCTIONS_COMMAND) {
-                self.calculator.interpreter().get_funcs().iter()
-                    .for_each(|(name, func)| println!("{name}{}", func.args));
-                continue;
+        }
+    }
+
+    /**
+     * Remember the state of the calculator before a change, so that the change can be undone
+     */
+    fn push_undo(&mut self, state: calculator::CalculatorState) {
+        if self.undo.len() >= MAX_UNDO {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(state);
+    }
+
+    /**
+     * Print history, oldest first, with the reference to each result
+     */
+    fn print_history(&self, count: Option<usize>) {
+        let history = self.calculator.clone_current_state().history;
+
+        if history.is_empty() {
+            println!("History is empty.");
+            return;
+        }
+
+        let count = count.unwrap_or(history.len()).min(history.len());
+
+        for (i, value) in history.iter().rev().enumerate().take(count).rev() {
+            println!("${i} = {}", display_value(value, self.precision).replace('\n', ""));
+        }
+    }
+
+    /**
+     * Print the memory that has been assigned to
+     */
+    fn print_memory(&self) {
+        let memory = self.calculator.clone_current_state().memory;
+
+        let assigned: Vec<(usize, &Value)> = memory.iter()
+            .enumerate()
+            .filter(|(_, value)| **value != Value::default())
+            .collect();
+
+        if assigned.is_empty() {
+            println!("Memory is empty.");
+        }
+
+        for (i, value) in assigned {
+            println!("$m{i} = {}", display_value(value, self.precision).replace('\n', ""));
+        }
+    }
+
+    /**
+     * Print the built-in functions matching the search by category, and then the user defined functions
+     */
+    fn print_functions(&self, search: &str) {
+        let interpreter = self.calculator.interpreter();
+        let mut category = None;
+
+        for (name, function) in interpreter.search_funcs(search) {
+            if category != Some(function.info.category) {
+                category = Some(function.info.category);
+                println!("{}", function.info.category.to_string().bold());
             }
 
-            let evaluated = match self.calculator.evaluate(&input) {
-                Ok(value) => value,
-                Err(e) => {
-                    eprintln!("{e}");
-                    continue;
-                }
-            };
+            println!("  {:<32}{}", format!("{name}{function}"), function.info.description.dimmed());
+        }
 
-            println!("{evaluated}");
-        };
+        let mut user_functions: Vec<(&String, &UserFunction)> = interpreter.get_user_funcs().into_iter()
+            .filter(|(name, _)| name.to_lowercase().contains(&search.to_lowercase()))
+            .collect();
+        user_functions.sort_by_key(|(name, _)| *name);
+
+        if !user_functions.is_empty() {
+            println!("{}", "User defined".bold());
+        }
+
+        for (name, function) in user_functions {
+            println!("  {name}{function}");
+        }
+    }
+
+    fn print_function_help(&self, name: &str) {
+        let interpreter = self.calculator.interpreter();
+
+        if let Some(function) = interpreter.get_func_by_name(name) {
+            let info = function.info;
+
+            println!("{}{function}", name.to_ascii_uppercase());
+            println!("{}", info.description);
+            println!("{} {}", "Category:".dimmed(), info.category);
+
+            if let Some(domain) = info.domain {
+                println!("{} {domain}", "Domain:".dimmed());
+            }
+
+            match function.angle {
+                AngleUse::Arguments => println!("{} Takes an angle in {}.", "Angles:".dimmed(), self.calculator.angle_mode()),
+                AngleUse::Result => println!("{} Returns an angle in {}.", "Angles:".dimmed(), self.calculator.angle_mode()),
+                AngleUse::None => {}
+            }
+
+            //Preview the example, so that it doesn't change history
+            let options = calculator::EvaluateOptions::new(InterpreterOptions::new(true));
+
+            match self.calculator.evaluate_with_options(info.example, options) {
+                Ok((value, _)) => println!("{} {} = {}", "Example:".dimmed(), info.example, display_value(&value, self.precision).replace('\n', "")),
+                Err(_) => println!("{} {}", "Example:".dimmed(), info.example)
+            }
+        }
+        else if let Some(function) = interpreter.get_user_func_by_name(name) {
+            println!("{name}{function}");
+            println!("{}", "User defined function.".dimmed());
+        }
+        else {
+            eprintln!("No such function '{name}'.");
+        }
+    }
+
+    fn set(&mut self, setting: &str, value: Option<&str>) -> Result<(), String> {
+        match (setting.to_ascii_lowercase().as_str(), value) {
+            (PRECISION_SETTING, None) => println!("{PRECISION_SETTING} = {}", self.precision.map(|precision| precision.to_string()).unwrap_or(String::from("off"))),
+            (PRECISION_SETTING, Some(value)) if value.eq_ignore_ascii_case("off") => self.precision = None,
+            (PRECISION_SETTING, Some(value)) => {
+                let precision = value.parse::<usize>()
+                    .map_err(|_| format!("Expected a number of decimal places or 'off' for '{PRECISION_SETTING}'; got '{value}'."))?;
+
+                self.precision = Some(precision);
+            },
+            (ANGLE_SETTING, None) => println!("{ANGLE_SETTING} = {}", self.calculator.angle_mode()),
+            (ANGLE_SETTING, Some(value)) => {
+                let angle_mode = match value.to_ascii_lowercase().as_str() {
+                    "deg" | "degrees" => AngleMode::Degrees,
+                    "rad" | "radians" => AngleMode::Radians,
+                    _ => Err(format!("Expected 'deg' or 'rad' for '{ANGLE_SETTING}'; got '{value}'."))?
+                };
+
+                self.calculator.set_angle_mode(angle_mode);
+            },
+            (NOTATION_SETTING, None) => println!("{NOTATION_SETTING} = {}", self.calculator.notation()),
+            (NOTATION_SETTING, Some(value)) => {
+                let notation = match value.to_ascii_lowercase().as_str() {
+                    "infix" => Notation::Infix,
+                    "rpn" | "postfix" => Notation::Postfix,
+                    _ => Err(format!("Expected 'infix' or 'rpn' for '{NOTATION_SETTING}'; got '{value}'."))?
+                };
 
+                self.calculator.set_notation(notation);
+            },
+            (FORMAT_SETTING, None) => println!("{FORMAT_SETTING} = {}", self.format),
+            (FORMAT_SETTING, Some(value)) => self.format = value.parse().map_err(|err: ConsoleErr| err.to_string())?,
+            _ => Err(format!("Unknown setting '{setting}'; expected '{PRECISION_SETTING}', '{ANGLE_SETTING}', '{NOTATION_SETTING}' or '{FORMAT_SETTING}'."))?
+        }
+
+        Ok(())
+    }
+
+    fn create_editor() -> Result<Editor<InputHelper, DefaultHistory>, String> {
+        let config = Config::builder()
+            .max_history_size(MAX_INPUT_HISTORY)
+            .map_err(|err| format!("Failed to configure input history: {err}"))?
+            .history_ignore_dups(true)
+            .map_err(|err| format!("Failed to configure input history: {err}"))?
+            .history_ignore_space(true)
+            .completion_type(rustyline::CompletionType::List)
+            .auto_add_history(false)
+            .build();
+
+        let mut editor = Editor::with_config(config)
+            .map_err(|err| format!("Failed to create line editor: {err}"))?;
+
+        editor.set_helper(Some(InputHelper::new(&command::COMMANDS)));
+
+        Ok(editor)
+    }
+
+    /**
+     * The path of the file previous inputs are saved to, in the user's config directory
+     */
+    fn input_history_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("calculator").join("history.txt"))
+    }
+
+    fn save_input_history(editor: &mut Editor<InputHelper, DefaultHistory>, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+
+        editor.save_history(path).map_err(|err| err.to_string())
+    }
+
+    /**
+     * The path of the file the session is automatically saved to, in the user's config directory
+     */
+    fn autosave_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("calculator").join("session.txt"))
+    }
+
+    fn autosave(&self, path: Option<&Path>) {
+        if let Some(path) = path {
+            if let Err(err) = self.save_session(path) {
+                eprintln!("Failed to save session: {err}");
+            }
+        }
+    }
+
+    /**
+     * Write the calculator's history, memory, variables and functions to a file
+     */
+    fn save_session(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+
+        std::fs::write(path, self.calculator.clone_current_state().to_session_string())
+            .map_err(|err| err.to_string())
+    }
+
+    /**
+     * Replace the calculator's history, memory, variables and functions with those saved to a file
+     */
+    fn load_session(&mut self, path: &Path) -> Result<(), String> {
+        let session = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let state = calculator::CalculatorState::from_session_str(&session).map_err(|err| err.to_string())?;
+
+        self.calculator.restore_state(state);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/**
+ * Display a value, rounding it to the given number of decimal places, if any
+ */
+pub fn display_value(value: &Value, precision: Option<usize>) -> String {
+    round_value(value.clone(), precision).to_string()
+}
+
+/**
+ * Round a value to the given number of decimal places, if any
+ */
+pub fn round_value(value: Value, precision: Option<usize>) -> Value {
+    match precision {
+        Some(precision) => {
+            let scale = 10_f64.powi(precision.min(i32::MAX as usize) as i32);
+
+            //Numbers too large to scale have no decimal places to round anyway
+            value.clone()
+                .map(|n| Ok(if (n * scale).is_finite() { (n * scale).round() / scale } else { n }))
+                .unwrap_or(value)
+        },
+        None => value
+    }
+}
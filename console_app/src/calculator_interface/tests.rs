@@ -0,0 +1,31 @@
+use super::{display_value, round_value};
+use crate::calculator::calculator_interpreter::interpreter::value::Value;
+
+#[test]
+/**
+ * Test that values are rounded to the number of decimal places, or displayed in full without a precision
+ */
+fn display_value_0() {
+    assert_eq!(display_value(&Value::Number(1.23456_f64), Some(2)), "1.23");
+    assert_eq!(display_value(&Value::Number(1.23456_f64), Some(0)), "1");
+    assert_eq!(display_value(&Value::Number(1.23456_f64), None), "1.23456");
+    assert_eq!(display_value(&Value::List(vec![0.125_f64, 2_f64 / 3_f64]), Some(2)), Value::List(vec![0.13_f64, 0.67_f64]).to_string());
+}
+
+#[test]
+/**
+ * Test that values too large to scale by the precision, and precisions too large to scale by, leave the value as it is
+ */
+fn display_value_1() {
+    let extremes = [1e300_f64, -1e300_f64, f64::MAX, 0_f64, f64::INFINITY, f64::NEG_INFINITY];
+
+    for n in extremes {
+        for precision in [20, 400, usize::MAX] {
+            assert_eq!(display_value(&Value::Number(n), Some(precision)), Value::Number(n).to_string(), "Testing {n} to {precision} places");
+        }
+    }
+
+    assert!(round_value(Value::Number(f64::NAN), Some(20)).into_number().unwrap().is_nan());
+    assert_eq!(round_value(Value::Number(1.5e-300_f64), Some(3)), Value::Number(0_f64));
+    assert_eq!(round_value(Value::Number(f64::MIN_POSITIVE), Some(20)), Value::Number(0_f64));
+}
@@ -0,0 +1,158 @@
+#[cfg(test)]
+pub mod tests;
+
+/**
+ * Inputs starting with this are commands to the console, rather than expressions
+ */
+pub const COMMAND_PREFIX: char = ':';
+
+pub const EXIT_COMMAND: &str = ":exit";
+pub const CLEAR_COMMAND: &str = ":clear";
+pub const LIST_FUNCTIONS_COMMAND: &str = ":list-functions";
+pub const CLEAR_HISTORY_COMMAND: &str = ":clear-hist";
+pub const CLEAR_MEMORY_COMMAND: &str = ":clear-mem";
+pub const SAVE_COMMAND: &str = ":save";
+pub const LOAD_COMMAND: &str = ":load";
+pub const HISTORY_COMMAND: &str = ":hist";
+pub const MEMORY_COMMAND: &str = ":mem";
+pub const HELP_COMMAND: &str = ":help";
+pub const AST_COMMAND: &str = ":ast";
+pub const UNDO_COMMAND: &str = ":undo";
+pub const SET_COMMAND: &str = ":set";
+pub const PLOT_COMMAND: &str = ":plot";
+pub const TABLE_COMMAND: &str = ":table";
+
+pub const COMMANDS: [&str; 15] = [
+    EXIT_COMMAND, CLEAR_COMMAND, LIST_FUNCTIONS_COMMAND, CLEAR_HISTORY_COMMAND, CLEAR_MEMORY_COMMAND, SAVE_COMMAND, LOAD_COMMAND,
+    HISTORY_COMMAND, MEMORY_COMMAND, HELP_COMMAND, AST_COMMAND, UNDO_COMMAND, SET_COMMAND, PLOT_COMMAND, TABLE_COMMAND
+];
+
+/**
+ * The commands, and what they do, as listed by ':help'
+ */
+pub const COMMAND_HELP: [(&str, &str); 15] = [
+    (":hist [n]", "List the last n results of history, or all of it"),
+    (":mem", "List the memory that has been assigned to"),
+    (":help [function]", "Show how to call a function, or list these commands"),
+    (":ast <expression>", "Print the tree an expression is parsed into"),
+    (":undo", "Undo the last change to history and memory"),
+    (":set [setting] [value]", "Change a setting, or list the settings; 'precision <digits|off>', 'angle <deg|rad>', 'notation <infix|rpn>' or 'format <plain|csv|tsv|json>'"),
+    (":plot <expression> [from] [to]", "Plot expressions of one variable, separated by ';', from -10 to 10 unless given"),
+    (":table <expression> [from] [to] [step]", "Tabulate expressions of one variable, separated by ';', from -10 to 10 in steps of 1 unless given"),
+    (":list-functions [search]", "List the calculator's functions, or those matching the search"),
+    (":clear-hist", "Clear history"),
+    (":clear-mem", "Clear memory"),
+    (":save <file>", "Save history, memory, variables and functions to a file"),
+    (":load <file>", "Load history, memory, variables and functions from a file"),
+    (":clear", "Clear the screen"),
+    (":exit", "Exit")
+];
+
+/**
+ * A command entered in interactive mode
+ */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    Exit,
+    Clear,
+    /**
+     * List the functions, optionally only those whose name, description or category match a search
+     */
+    ListFunctions(Option<String>),
+    ClearHistory,
+    ClearMemory,
+    Save(String),
+    Load(String),
+    /**
+     * List history, optionally only the most recent results
+     */
+    History(Option<usize>),
+    Memory,
+    /**
+     * Describe a function, or list the commands if there is no function
+     */
+    Help(Option<String>),
+    Ast(String),
+    Undo,
+    /**
+     * Change a setting, or show its value if there is no value, or list every setting if there is no setting
+     */
+    Set(Option<String>, Option<String>),
+    /**
+     * Plot expressions, which are followed by the range to plot them across if it's given
+     */
+    Plot(String),
+    /**
+     * Tabulate expressions, which are followed by the values to tabulate them for if they're given
+     */
+    Table(String)
+}
+
+impl Command {
+    /**
+     * Read the input as a command, if it starts with ':'. Command names are case insensitive.
+     */
+    pub fn parse(input: &str) -> Option<Result<Self, String>> {
+        let input = input.trim();
+
+        if !input.starts_with(COMMAND_PREFIX) {
+            return None;
+        }
+
+        let (name, argument) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let argument = argument.trim();
+        let name = name.to_ascii_lowercase();
+
+        let command = match name.as_str() {
+            EXIT_COMMAND => no_argument(Self::Exit, EXIT_COMMAND, argument),
+            CLEAR_COMMAND => no_argument(Self::Clear, CLEAR_COMMAND, argument),
+            LIST_FUNCTIONS_COMMAND => Ok(Self::ListFunctions(optional_argument(argument))),
+            CLEAR_HISTORY_COMMAND => no_argument(Self::ClearHistory, CLEAR_HISTORY_COMMAND, argument),
+            CLEAR_MEMORY_COMMAND => no_argument(Self::ClearMemory, CLEAR_MEMORY_COMMAND, argument),
+            MEMORY_COMMAND => no_argument(Self::Memory, MEMORY_COMMAND, argument),
+            UNDO_COMMAND => no_argument(Self::Undo, UNDO_COMMAND, argument),
+            SAVE_COMMAND => required_argument(SAVE_COMMAND, "file", argument).map(Self::Save),
+            LOAD_COMMAND => required_argument(LOAD_COMMAND, "file", argument).map(Self::Load),
+            AST_COMMAND => required_argument(AST_COMMAND, "expression", argument).map(Self::Ast),
+            PLOT_COMMAND => required_argument(PLOT_COMMAND, "expression", argument).map(Self::Plot),
+            TABLE_COMMAND => required_argument(TABLE_COMMAND, "expression", argument).map(Self::Table),
+            HELP_COMMAND => Ok(Self::Help(optional_argument(argument))),
+            HISTORY_COMMAND => match optional_argument(argument) {
+                Some(count) => count.parse::<usize>()
+                    .map(|count| Self::History(Some(count)))
+                    .map_err(|_| format!("Expected a number of results after '{HISTORY_COMMAND}'; got '{count}'.")),
+                None => Ok(Self::History(None))
+            },
+            SET_COMMAND => {
+                let (setting, value) = argument.split_once(char::is_whitespace).unwrap_or((argument, ""));
+                Ok(Self::Set(optional_argument(setting), optional_argument(value.trim())))
+            },
+            _ => Err(format!("Unknown command '{name}'; enter '{HELP_COMMAND}' for a list of commands."))
+        };
+
+        Some(command)
+    }
+}
+
+fn no_argument(command: Command, name: &str, argument: &str) -> Result<Command, String> {
+    if argument.is_empty() {
+        Ok(command)
+    }
+    else {
+        Err(format!("'{name}' does not take an argument."))
+    }
+}
+
+fn required_argument(name: &str, expected: &str, argument: &str) -> Result<String, String> {
+    optional_argument(argument).ok_or_else(|| format!("Expected {} {expected} after '{name}'.", article(expected)))
+}
+
+fn optional_argument(argument: &str) -> Option<String> {
+    Some(argument)
+        .filter(|argument| !argument.is_empty())
+        .map(String::from)
+}
+
+fn article(noun: &str) -> &'static str {
+    if noun.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" }
+}
@@ -0,0 +1,75 @@
+use super::Command;
+
+/**
+ * The input should be read as the command
+ */
+fn command_test(input: &str, expected: Command) {
+    match Command::parse(input) {
+        Some(Ok(command)) => assert_eq!(command, expected, "Testing '{input}'"),
+        Some(Err(err)) => panic!("{err} (testing '{input}')"),
+        None => panic!("'{input}' was not read as a command")
+    }
+}
+
+/**
+ * The input should be read as a command, but fail to parse
+ */
+fn command_err_test(input: &str) {
+    assert!(matches!(Command::parse(input), Some(Err(_))), "Testing '{input}'");
+}
+
+#[test]
+/**
+ * Test that commands are read with or without arguments, ignoring case and surrounding whitespace
+ */
+fn parse_0() {
+    let inputs = vec![
+        (":exit", Command::Exit),
+        ("  :EXIT  ", Command::Exit),
+        (":clear", Command::Clear),
+        (":list-functions", Command::ListFunctions(None)),
+        (":list-functions  trig", Command::ListFunctions(Some(String::from("trig")))),
+        (":clear-hist", Command::ClearHistory),
+        (":clear-mem", Command::ClearMemory),
+        (":save session.txt", Command::Save(String::from("session.txt"))),
+        (":Load  my session.txt", Command::Load(String::from("my session.txt"))),
+        (":hist", Command::History(None)),
+        (":hist 3", Command::History(Some(3))),
+        (":mem", Command::Memory),
+        (":help", Command::Help(None)),
+        (":help sqrt", Command::Help(Some(String::from("sqrt")))),
+        (":ast 1 + 2", Command::Ast(String::from("1 + 2"))),
+        (":undo", Command::Undo),
+        (":set", Command::Set(None, None)),
+        (":set precision", Command::Set(Some(String::from("precision")), None)),
+        (":set precision  4", Command::Set(Some(String::from("precision")), Some(String::from("4")))),
+        (":plot sin(x) -pi pi", Command::Plot(String::from("sin(x) -pi pi"))),
+        (":table x^2 0 4", Command::Table(String::from("x^2 0 4")))
+    ];
+
+    for (input, expected) in inputs {
+        command_test(input, expected);
+    }
+}
+
+#[test]
+/**
+ * Test that commands with missing, unexpected or invalid arguments, and unknown commands, fail to parse
+ */
+fn parse_1() {
+    let inputs = vec![":exit now", ":clear 1", ":undo 2", ":save", ":load ", ":ast", ":plot", ":table", ":hist -1", ":hist x", ":unknown"];
+
+    for input in inputs {
+        command_err_test(input);
+    }
+}
+
+#[test]
+/**
+ * Test that input which doesn't start with ':' is not a command
+ */
+fn parse_2() {
+    for input in ["1 + 2", "", "exit", "$m0: 2"] {
+        assert_eq!(Command::parse(input), None, "Testing '{input}'");
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use super::calculator::calculator_err::CalculatorErr;
+
+/**
+ * Exit codes reported when the console fails, so that scripts calling it can tell why it failed
+ */
+pub const EXIT_ARGUMENT_ERR: u8 = 1;
+pub const EXIT_PARSE_ERR: u8 = 2;
+pub const EXIT_EVALUATE_ERR: u8 = 3;
+pub const EXIT_IO_ERR: u8 = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ConsoleErr {
+    Argument { message: String },
+    Parse { message: String },
+    Evaluate { message: String },
+    Io { message: String }
+}
+
+impl ConsoleErr {
+    pub fn argument_err(message: &str) -> Self {
+        Self::Argument { message: String::from(message) }
+    }
+
+    pub fn parse_err(message: &str) -> Self {
+        Self::Parse { message: String::from(message) }
+    }
+
+    pub fn eval_err(message: &str) -> Self {
+        Self::Evaluate { message: String::from(message) }
+    }
+
+    pub fn io_err(message: &str) -> Self {
+        Self::Io { message: String::from(message) }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Argument { message } => message,
+            Self::Parse { message } => message,
+            Self::Evaluate { message } => message,
+            Self::Io { message } => message
+        }
+    }
+
+    /**
+     * Create an error of the same kind, with the message changed, such as to add where the error occurred
+     */
+    pub fn map_message<F>(self, f: F) -> Self
+        where F : FnOnce(&str) -> String
+    {
+        let message = f(self.message());
+
+        match self {
+            Self::Argument { .. } => Self::Argument { message },
+            Self::Parse { .. } => Self::Parse { message },
+            Self::Evaluate { .. } => Self::Evaluate { message },
+            Self::Io { .. } => Self::Io { message }
+        }
+    }
+
+    /**
+     * The name of the kind of error, as written in machine readable output
+     */
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Argument { .. } => "argument",
+            Self::Parse { .. } => "parse",
+            Self::Evaluate { .. } => "evaluate",
+            Self::Io { .. } => "io"
+        }
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Argument { .. } => EXIT_ARGUMENT_ERR,
+            Self::Parse { .. } => EXIT_PARSE_ERR,
+            Self::Evaluate { .. } => EXIT_EVALUATE_ERR,
+            Self::Io { .. } => EXIT_IO_ERR
+        }
+    }
+}
+
+impl From<CalculatorErr> for ConsoleErr {
+    fn from(value: CalculatorErr) -> Self {
+        match value {
+            CalculatorErr::ParseErr { .. } => Self::parse_err(value.to_string().as_str()),
+            CalculatorErr::SessionErr { .. } => Self::io_err(value.to_string().as_str()),
+            _ => Self::eval_err(value.to_string().as_str())
+        }
+    }
+}
+
+impl Display for ConsoleErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
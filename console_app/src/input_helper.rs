@@ -0,0 +1,182 @@
+use std::borrow::Cow;
+
+use colored::Colorize;
+use rustyline::{completion::{Completer, Pair}, hint::Hinter, highlight::Highlighter, validate::Validator, Context, Helper};
+
+use super::{calculator::{self, calculator_interpreter::interpreter::{EvaluateOptions as InterpreterOptions, value::Value}}, calculator_interface, syntax_highlighting};
+
+/**
+ * The most history and memory references offered as completions at once
+ */
+const MAX_REFERENCE_COMPLETIONS: usize = 50;
+
+/**
+ * Provides tab completion to the line editor, for function names, commands,
+ * and references to the calculator's current history and memory,
+ * as well as syntax highlighting and a preview of the result while typing
+ */
+#[derive(Debug, Clone, Default)]
+pub struct InputHelper {
+    calculator: calculator::Calculator,
+    commands: Vec<String>,
+    functions: Vec<String>,
+    variables: Vec<String>,
+    references: Vec<(String, String)>,
+    precision: Option<usize>
+}
+
+impl InputHelper {
+    pub fn new(commands: &[&str]) -> Self {
+        Self {
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Refresh the completions from the calculator's functions, history and memory, the variables highlighted, and the precision results are previewed to
+     */
+    pub fn update(&mut self, calculator: &calculator::Calculator, precision: Option<usize>) {
+        let mut functions: Vec<String> = calculator.interpreter().get_funcs().into_iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        functions.extend(calculator.interpreter().get_user_funcs().into_iter().map(|(name, _)| name.clone()));
+        functions.sort();
+        self.functions = functions;
+
+        self.variables = calculator.interpreter().clone_variables().into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        let state = calculator.clone_current_state();
+
+        //History is referenced from the most recent result
+        let history = state.history.iter().rev()
+            .enumerate()
+            .map(|(i, value)| reference(format!("${i}"), value));
+
+        //Only memory which has been assigned to is worth completing
+        let memory = state.memory.iter()
+            .enumerate()
+            .filter(|(_, value)| **value != Value::default())
+            .map(|(i, value)| reference(format!("$m{i}"), value));
+
+        self.references = history.take(MAX_REFERENCE_COMPLETIONS)
+            .chain(memory.take(MAX_REFERENCE_COMPLETIONS))
+            .collect();
+
+        self.calculator = calculator.clone();
+        self.precision = precision;
+    }
+}
+
+/**
+ * A completion for a history or memory reference, and how it is displayed with its current value
+ */
+fn reference(replacement: String, value: &Value) -> (String, String) {
+    //Matrices span several lines, so only show them on a single line
+    let value = value.to_string().replace('\n', "");
+    let display = format!("{replacement} = {value}");
+
+    (replacement, display)
+}
+
+impl Completer for InputHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+
+        //Commands are only valid as the whole input
+        let trimmed = before.trim_start();
+
+        if trimmed.starts_with(':') {
+            let candidates = self.commands.iter()
+                .filter(|command| command.starts_with(trimmed))
+                .map(|command| Pair { display: command.clone(), replacement: command.clone() })
+                .collect();
+
+            return Ok((pos - trimmed.len(), candidates));
+        }
+
+        //Find the word being typed
+        let start = before.char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_ascii_alphanumeric() || *c == '_' || *c == '$')
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(pos);
+
+        let word = &before[start..];
+
+        let candidates: Vec<Pair> = if word.starts_with('$') {
+            self.references.iter()
+                .filter(|(replacement, _)| replacement.starts_with(word))
+                .map(|(replacement, display)| Pair { display: display.clone(), replacement: replacement.clone() })
+                .collect()
+        }
+        else if word.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            //Complete in the same case the user is typing in
+            let lowercase = !word.chars().any(|c| c.is_ascii_uppercase());
+
+            self.functions.iter()
+                .filter(|function| function.len() >= word.len() && function[..word.len()].eq_ignore_ascii_case(word))
+                .map(|function| {
+                    let replacement = if lowercase { function.to_ascii_lowercase() } else { function.clone() };
+                    Pair { display: replacement.clone(), replacement }
+                })
+                .collect()
+        }
+        else {
+            Vec::new()
+        };
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for InputHelper {
+    type Hint = String;
+
+    /**
+     * Preview the result of the input on the line below, without changing the calculator's history or memory
+     */
+    fn hint(&self, line: &str, _pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        let input = line.trim();
+
+        if input.is_empty() || input.starts_with(':') {
+            return None;
+        }
+
+        let options = calculator::EvaluateOptions::new(InterpreterOptions::new(true));
+
+        match self.calculator.evaluate_with_options(input, options) {
+            //Previewing a number as itself adds nothing
+            Ok((value, _)) => Some(calculator_interface::display_value(&value, self.precision))
+                .filter(|preview| preview != input)
+                .map(|preview| format!("\n= {preview}")),
+            _ => None
+        }
+    }
+}
+
+impl Highlighter for InputHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        Cow::Owned(syntax_highlighting::highlight(line, &self.functions, &self.variables))
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+
+    /**
+     * Highlight again after every change, since a single character can change the meaning of the tokens around it
+     */
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        true
+    }
+}
+
+impl Validator for InputHelper {}
+
+impl Helper for InputHelper {}
@@ -1,72 +1,331 @@
-use std::io::BufRead;
+use std::{io::BufRead, process::ExitCode};
 
 pub use calculator::calculator;
 
+use console_err::ConsoleErr;
+use output::{Emit, OutputFormat, Record};
+
+mod ast;
 mod calculator_interface;
+mod command;
+mod console_err;
+mod input_helper;
+mod map;
+mod output;
+mod plot;
+mod script;
+mod syntax_highlighting;
+mod table;
+
+const SEED_OPTION: &str = "--seed";
+const RPN_OPTION: &str = "--rpn";
+const CONTINUE_ON_ERROR_OPTION: &str = "--continue-on-error";
+const FORMAT_OPTION: &str = "--format";
+const MEMORY_OPTION: &str = "--memory";
+const EMIT_OPTION: &str = "--emit";
+
+const INPUT_OPTION: &str = "--input";
+const EXPR_OPTION: &str = "--expr";
+const OUTPUT_COLUMN_OPTION: &str = "--output-column";
+const NO_HEADER_OPTION: &str = "--no-header";
 
-fn main() -> Result<(), String> {
+const WRITE_OPTION: &str = "--write";
+
+const RUN_COMMAND: &str = "run";
+const MAP_COMMAND: &str = "map";
+const FUNCTIONS_COMMAND: &str = "functions";
+const FMT_COMMAND: &str = "fmt";
+
+fn main() -> ExitCode {
     //Set to use virtual terminal so that control characters work on windows
-    _ = colored::control::set_virtual_terminal(true);
+    #[cfg(windows)]
+    {
+        _ = colored::control::set_virtual_terminal(true);
+    }
+
+    match run() {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+/**
+ * What the console was asked to do, chosen by its first argument
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Command {
+    /**
+     * Evaluate expressions from the arguments or stdin, or start interactive mode if there are none
+     */
+    Evaluate,
+    /**
+     * Run a script file
+     */
+    Run,
+    /**
+     * Evaluate an expression for every row of a data file
+     */
+    Map,
+    /**
+     * Print the list of functions as markdown, as in the README
+     */
+    Functions,
+    /**
+     * Write a script file in canonical form
+     */
+    Fmt
+}
+
+fn run() -> Result<(), ConsoleErr> {
+    //Get arguments
+    let args: Vec<String> = std::env::args().collect();
+
+    let command = match args.get(1) {
+        Some(arg) if arg.eq_ignore_ascii_case(RUN_COMMAND) => Command::Run,
+        Some(arg) if arg.eq_ignore_ascii_case(MAP_COMMAND) => Command::Map,
+        Some(arg) if arg.eq_ignore_ascii_case(FUNCTIONS_COMMAND) => Command::Functions,
+        Some(arg) if arg.eq_ignore_ascii_case(FMT_COMMAND) => Command::Fmt,
+        _ => Command::Evaluate
+    };
+
+    let mut seed: Option<u64> = None;
+    let mut notation = calculator::calculator_parser::parser::Notation::Infix;
+    let mut continue_on_error = false;
+    let mut write = false;
+    let mut format = OutputFormat::default();
+    let mut include_memory = false;
+    let mut emit: Option<Emit> = None;
+    let mut map_options = map::MapOptions::default();
+    let mut map_expression: Option<String> = None;
+    let mut arguments: Vec<String> = Vec::new();
+
+    //Collect all arguments (except for path, command and options)
+    let mut arg_iter = args.iter().skip(if command == Command::Evaluate { 1 } else { 2 });
+
+    while let Some(arg) = arg_iter.next() {
+        if let Some(value) = option_value(arg, SEED_OPTION, &mut arg_iter)? {
+            seed = Some(parse_seed(&value)?);
+        }
+        else if arg.eq_ignore_ascii_case(RPN_OPTION) {
+            notation = calculator::calculator_parser::parser::Notation::Postfix;
+        }
+        else if command == Command::Evaluate {
+            if let Some(value) = option_value(arg, FORMAT_OPTION, &mut arg_iter)? {
+                format = value.parse()?;
+            }
+            else if arg.eq_ignore_ascii_case(MEMORY_OPTION) {
+                include_memory = true;
+            }
+            else if let Some(value) = option_value(arg, EMIT_OPTION, &mut arg_iter)? {
+                emit = Some(value.parse()?);
+            }
+            else {
+                arguments.push(arg.clone());
+            }
+        }
+        else if command == Command::Run && arg.eq_ignore_ascii_case(CONTINUE_ON_ERROR_OPTION) {
+            continue_on_error = true;
+        }
+        else if command == Command::Fmt && arg.eq_ignore_ascii_case(WRITE_OPTION) {
+            write = true;
+        }
+        else if command == Command::Map {
+            if let Some(value) = option_value(arg, INPUT_OPTION, &mut arg_iter)? {
+                map_options.input = Some(std::path::PathBuf::from(value)).filter(|path| path.as_os_str() != "-");
+            }
+            else if let Some(value) = option_value(arg, EXPR_OPTION, &mut arg_iter)? {
+                map_expression = Some(value);
+            }
+            else if let Some(value) = option_value(arg, OUTPUT_COLUMN_OPTION, &mut arg_iter)? {
+                map_options.output_column = value;
+            }
+            else if arg.eq_ignore_ascii_case(NO_HEADER_OPTION) {
+                map_options.has_header = false;
+            }
+            else {
+                return Err(ConsoleErr::argument_err(format!("Unexpected argument '{arg}' for '{MAP_COMMAND}'.").as_str()));
+            }
+        }
+        else {
+            arguments.push(arg.clone());
+        }
+    }
 
     //Check if piped input
 
-    //Input not piped; don't read from stdin
-    let mut inputs: Vec<String> = if atty::is(atty::Stream::Stdin) {
+    //Input not piped, or running a command which reads its own input; don't read from stdin
+    let mut inputs: Vec<String> = if command != Command::Evaluate || atty::is(atty::Stream::Stdin) {
         Vec::new()
     }
     //Input was piped; read content from stdin
     else {
-        std::io::stdin()
-        .lock()
-        .lines()
-        .filter_map(|l| {
-            if let Ok(line) = l {
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    None
-                }
-                else {
-                    Some(String::from(trimmed))
-                }
+        let lines = std::io::stdin()
+            .lock()
+            .lines()
+            .collect::<Result<Vec<String>, std::io::Error>>()
+            .map_err(|err| ConsoleErr::io_err(format!("Failed to read input: {err}").as_str()))?;
+
+        lines.iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    //Create the calculator
+    let mut calculator = calculator::Calculator::default();
+
+    //Seed the calculator, so that random functions are reproducible
+    if let Some(seed) = seed {
+        calculator.seed(seed);
+    }
+
+    //Read expressions in reverse polish notation
+    calculator.set_notation(notation);
+
+    match command {
+        Command::Run => {
+            let path = match arguments.as_slice() {
+                [path] => path,
+                _ => return Err(ConsoleErr::argument_err(format!("Expected a single script file after '{RUN_COMMAND}'.").as_str()))
+            };
+
+            return script::run(&mut calculator, std::path::Path::new(path), continue_on_error);
+        },
+        Command::Map => {
+            map_options.expression = map_expression
+                .ok_or_else(|| ConsoleErr::argument_err(format!("Expected an expression to evaluate for each row, using '{EXPR_OPTION}'.").as_str()))?;
+
+            return map::run(&mut calculator, &map_options);
+        },
+        Command::Functions => {
+            if let Some(arg) = arguments.first() {
+                return Err(ConsoleErr::argument_err(format!("Unexpected argument '{arg}' for '{FUNCTIONS_COMMAND}'.").as_str()));
             }
-            else {
-                None
+
+            println!("{}", calculator.interpreter().function_list_markdown());
+            return Ok(());
+        },
+        Command::Fmt => {
+            if notation != calculator::calculator_parser::parser::Notation::Infix {
+                return Err(ConsoleErr::argument_err(format!("Scripts are always written in infix notation; '{RPN_OPTION}' can't be used with '{FMT_COMMAND}'.").as_str()));
             }
-        })
-        .collect()
-    };
 
-    //Get arguments
-    let args: Vec<String> = std::env::args().collect();
+            let path = match arguments.as_slice() {
+                [path] => path,
+                _ => return Err(ConsoleErr::argument_err(format!("Expected a single script file after '{FMT_COMMAND}'.").as_str()))
+            };
 
-    //Push all arguments (except for path) to input
-    if args.len() > 1 {
-        for arg in args.iter().skip(1) {
-            inputs.push(arg.clone());
-        }
+            return script::format(std::path::Path::new(path), write);
+        },
+        Command::Evaluate => {}
     }
 
-    //Create the calculator
-    let calculator = calculator::Calculator::default();
+    inputs.extend(arguments);
 
-    if !inputs.is_empty() {
-        for expression in inputs {
-            match calculator.evaluate(&expression) {
-                Ok(result) => {
-                    println!("{result}");
-                    Ok(())
-                },
-                Err(e) => Err(e.to_string())
-            }?;
+    if let Some(emit) = emit {
+        if format != OutputFormat::Plain || include_memory {
+            return Err(ConsoleErr::argument_err(format!("'{EMIT_OPTION}' can't be combined with '{FORMAT_OPTION}' or '{MEMORY_OPTION}'.").as_str()));
         }
 
-        Ok(())
+        if inputs.is_empty() {
+            return Err(ConsoleErr::argument_err(format!("Expected expressions to write after '{EMIT_OPTION}'.").as_str()));
+        }
+
+        emit_inputs(&calculator, &inputs, emit)
+    }
+    else if !inputs.is_empty() {
+        evaluate_inputs(&calculator, &inputs, format, include_memory)
     }
     else {
         //Create the UI instance
         let mut ui = calculator_interface::ConsoleUI::new(calculator);
 
         //Start the UI
-        ui.start()
+        ui.start().map_err(|err| ConsoleErr::io_err(err.as_str()))
+    }
+}
+
+/**
+ * Evaluate each input in order, writing a record of each in the given format.
+ *
+ * Plain output stops at the first error; other formats write a record of each error and continue,
+ * failing afterwards with the first error's kind.
+ */
+fn evaluate_inputs(calculator: &calculator::Calculator, inputs: &[String], format: OutputFormat, include_memory: bool) -> Result<(), ConsoleErr> {
+    if format == OutputFormat::Plain {
+        for expression in inputs {
+            let result = calculator.evaluate(expression)?;
+            println!("{result}");
+        }
+
+        return Ok(());
     }
-}
\ No newline at end of file
+
+    if let Some(header) = output::header(format, include_memory) {
+        println!("{header}");
+    }
+
+    let mut first_err: Option<ConsoleErr> = None;
+    let mut failed = 0_usize;
+
+    for expression in inputs {
+        let record = match calculator.evaluate_with_options(expression, calculator::EvaluateOptions::default()) {
+            Ok((value, state)) => Record { expression, result: Ok(value), memory: include_memory.then_some(state.memory) },
+            Err(err) => {
+                first_err.get_or_insert(ConsoleErr::from(err.clone()));
+                failed += 1;
+
+                Record { expression, result: Err(err), memory: include_memory.then(|| calculator.clone_current_state().memory) }
+            }
+        };
+
+        println!("{}", output::format_record(format, &record));
+    }
+
+    match first_err {
+        Some(err) => Err(err.map_message(|_| format!("{failed} of {} expression(s) failed.", inputs.len()))),
+        None => Ok(())
+    }
+}
+
+/**
+ * Write each input typeset in the given markup without evaluating it, stopping at the first error
+ */
+fn emit_inputs(calculator: &calculator::Calculator, inputs: &[String], emit: Emit) -> Result<(), ConsoleErr> {
+    for expression in inputs {
+        let expr = calculator.parse(expression)?;
+
+        match emit {
+            Emit::Latex => println!("{}", expr.to_latex()),
+            Emit::MathMl => println!("{}", expr.to_mathml())
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * If the argument is the given option, get its value, either following '=' or as the next argument
+ */
+fn option_value<'a, I>(arg: &str, option: &str, arg_iter: &mut I) -> Result<Option<String>, ConsoleErr>
+    where I : Iterator<Item = &'a String>
+{
+    if arg.eq_ignore_ascii_case(option) {
+        match arg_iter.next() {
+            Some(value) => Ok(Some(value.clone())),
+            None => Err(ConsoleErr::argument_err(format!("Expected a value after '{option}'.").as_str()))
+        }
+    }
+    else {
+        Ok(arg.strip_prefix(format!("{option}=").as_str()).map(String::from))
+    }
+}
+
+fn parse_seed(value: &str) -> Result<u64, ConsoleErr> {
+    value.trim().parse::<u64>()
+        .map_err(|_| ConsoleErr::argument_err(format!("Invalid seed '{value}'; expected a non-negative integer.").as_str()))
+}
@@ -0,0 +1,195 @@
+use std::{fs::File, io::Read, path::{Path, PathBuf}};
+
+use super::{calculator::{self, calculator_interpreter::interpreter::value::Value}, console_err::ConsoleErr};
+
+#[cfg(test)]
+pub mod tests;
+
+/**
+ * The function that reads a column by name, i.e. 'col(price)', which is the same as 'price'
+ */
+const COLUMN_FUNCTION: &str = "col";
+
+/**
+ * The prefix of the names columns can always be read by, followed by their position, i.e. 'col1'
+ */
+const POSITIONAL_PREFIX: &str = "col";
+
+const DEFAULT_OUTPUT_COLUMN: &str = "result";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MapOptions {
+    /**
+     * The data file to read, or stdin if there is none
+     */
+    pub input: Option<PathBuf>,
+    pub expression: String,
+    pub output_column: String,
+    pub has_header: bool
+}
+
+impl Default for MapOptions {
+    fn default() -> Self {
+        Self {
+            input: None,
+            expression: String::new(),
+            output_column: String::from(DEFAULT_OUTPUT_COLUMN),
+            has_header: true
+        }
+    }
+}
+
+/**
+ * Evaluate the expression for every row of a CSV (or TSV) file, writing each row with the result added as a new column.
+ *
+ * Each column is bound to a variable named by its position, i.e. 'col1', and, if the file has a header, by its name,
+ * with any characters that can't be part of a name replaced by '_'. Cells that aren't numbers leave their variables unset.
+ *
+ * Rows are read and written one at a time, so files don't need to fit in memory. Rows which fail are reported with their line,
+ * and written with an empty result.
+ */
+pub fn run(calculator: &mut calculator::Calculator, options: &MapOptions) -> Result<(), ConsoleErr> {
+    let source = match &options.input {
+        Some(path) => path.display().to_string(),
+        None => String::from("stdin")
+    };
+
+    let input: Box<dyn Read> = match &options.input {
+        Some(path) => Box::new(File::open(path).map_err(|err| ConsoleErr::io_err(format!("Failed to open '{source}': {err}").as_str()))?),
+        None => Box::new(std::io::stdin())
+    };
+
+    let delimiter = match options.input.as_deref().and_then(Path::extension) {
+        Some(extension) if extension.eq_ignore_ascii_case("tsv") => b'\t',
+        _ => b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(options.has_header)
+        .flexible(true)
+        .from_reader(input);
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_writer(std::io::stdout().lock());
+
+    let read_err = |err: csv::Error| ConsoleErr::io_err(format!("Failed to read '{source}': {err}").as_str());
+    let write_err = |err: &dyn std::fmt::Display| ConsoleErr::io_err(format!("Failed to write output: {err}").as_str());
+
+    let mut names: Vec<Vec<String>> = Vec::new();
+    let mut header_len: Option<usize> = None;
+
+    if options.has_header {
+        let header = reader.headers().map_err(read_err)?.clone();
+        header_len = Some(header.len());
+        names = header_names(calculator, &header);
+
+        writer.write_record(header.iter().chain([options.output_column.as_str()]))
+            .map_err(|err| write_err(&err))?;
+    }
+
+    calculator.define_function(COLUMN_FUNCTION, &["column"], "column")?;
+
+    let mut first_err: Option<ConsoleErr> = None;
+    let mut failed = 0_usize;
+
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(read_err)?;
+        let line = record.position().map(|position| position.line()).unwrap_or(i as u64 + 1);
+
+        bind_columns(calculator, &mut names, &record)?;
+
+        //Rows are independent, so history doesn't need to be kept; memory is kept, such as to sum a column
+        calculator.clear_stack();
+
+        //The result wouldn't be in the result column of rows which don't match the header
+        let evaluated = match header_len {
+            Some(len) if len != record.len() => Err(ConsoleErr::parse_err(format!("Expected {len} columns; got {}.", record.len()).as_str())),
+            _ => calculator.evaluate(&options.expression).map_err(ConsoleErr::from)
+        };
+
+        let result = match evaluated {
+            Ok(value) => value.to_string().replace('\n', ""),
+            Err(err) => {
+                let report = err.map_message(|message| format!("{source}:{line}: {message}"));
+                eprintln!("{report}");
+
+                first_err.get_or_insert(report);
+                failed += 1;
+                String::new()
+            }
+        };
+
+        writer.write_record(record.iter().chain([result.as_str()]))
+            .map_err(|err| write_err(&err))?;
+    }
+
+    writer.flush().map_err(|err| write_err(&err))?;
+
+    match first_err {
+        Some(err) => Err(err.map_message(|_| format!("{failed} row(s) of '{source}' failed."))),
+        None => Ok(())
+    }
+}
+
+/**
+ * The names each column of the header can be read by: its position, and its name if that can be used as a variable
+ */
+fn header_names(calculator: &calculator::Calculator, header: &csv::StringRecord) -> Vec<Vec<String>> {
+    header.iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let mut column_names = vec![positional_name(i)];
+            let name = sanitize(column);
+
+            if !calculator.is_valid_name(&name) {
+                eprintln!("Column '{column}' can only be used as '{}'.", positional_name(i));
+            }
+            else if !column_names.contains(&name) {
+                column_names.push(name);
+            }
+
+            column_names
+        })
+        .collect()
+}
+
+/**
+ * Set the variables of each column to its cell in the row, or unset them if the cell isn't a number.
+ * Columns beyond those named so far can be read by their position.
+ */
+fn bind_columns(calculator: &mut calculator::Calculator, names: &mut Vec<Vec<String>>, record: &csv::StringRecord) -> Result<(), ConsoleErr> {
+    //Rows may have more columns than the header
+    while names.len() < record.len() {
+        names.push(vec![positional_name(names.len())]);
+    }
+
+    for (j, column_names) in names.iter().enumerate() {
+        let cell = record.get(j).and_then(|cell| cell.trim().parse::<f64>().ok());
+
+        for name in column_names {
+            match cell {
+                Some(n) => calculator.set_variable(name, Value::Number(n))?,
+                None => _ = calculator.remove_variable(name)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn positional_name(index: usize) -> String {
+    format!("{POSITIONAL_PREFIX}{}", index + 1)
+}
+
+/**
+ * Replace characters of a column's name that can't be part of a variable's name
+ */
+fn sanitize(column: &str) -> String {
+    column.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,50 @@
+use csv::StringRecord;
+
+use super::{bind_columns, header_names, sanitize};
+use crate::calculator::{Calculator, calculator_interpreter::interpreter::value::Value};
+
+#[test]
+/**
+ * Test that characters of a column's name that can't be part of a variable's name are replaced
+ */
+fn sanitize_0() {
+    assert_eq!(sanitize("price"), "price");
+    assert_eq!(sanitize("  unit price "), "unit_price");
+    assert_eq!(sanitize("cost ($)"), "cost____");
+    assert_eq!(sanitize("naïve"), "na_ve");
+}
+
+#[test]
+/**
+ * Test that columns are named by position, and by their name unless it can't be used as a variable
+ */
+fn header_names_0() {
+    let calculator = Calculator::default();
+    let header = StringRecord::from(vec!["price", "unit price", "pi", "col2", "3"]);
+
+    assert_eq!(header_names(&calculator, &header), vec![
+        vec!["col1".to_string(), "price".to_string()],
+        vec!["col2".to_string(), "unit_price".to_string()],
+        vec!["col3".to_string()],
+        vec!["col4".to_string(), "col2".to_string()],
+        vec!["col5".to_string()]
+    ]);
+}
+
+#[test]
+/**
+ * Test that each row binds its cells to the column's variables, unsetting those of cells that aren't numbers
+ */
+fn bind_columns_0() {
+    let mut calculator = Calculator::default();
+    let mut names = header_names(&calculator, &StringRecord::from(vec!["price", "qty"]));
+
+    bind_columns(&mut calculator, &mut names, &StringRecord::from(vec!["2.5", " 4 "])).unwrap();
+    assert_eq!(calculator.evaluate("price * qty").unwrap(), Value::Number(10_f64));
+    assert_eq!(calculator.evaluate("col1 * col2").unwrap(), Value::Number(10_f64));
+
+    bind_columns(&mut calculator, &mut names, &StringRecord::from(vec!["n/a", "3", "7"])).unwrap();
+    assert!(calculator.evaluate("price").is_err());
+    assert!(calculator.evaluate("col1").is_err());
+    assert_eq!(calculator.evaluate("qty + col3").unwrap(), Value::Number(10_f64));
+}
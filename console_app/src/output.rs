@@ -0,0 +1,314 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde_json::json;
+
+use super::{calculator::{calculator_err::CalculatorErr, calculator_interpreter::interpreter::value::Value}, console_err::ConsoleErr};
+
+#[cfg(test)]
+pub mod tests;
+
+const EXPRESSION_FIELD: &str = "expression";
+const RESULT_FIELD: &str = "result";
+const ERROR_FIELD: &str = "error";
+const ERROR_CODE_FIELD: &str = "error_code";
+const ERROR_MESSAGE_FIELD: &str = "error_message";
+const SPAN_START_FIELD: &str = "span_start";
+const SPAN_END_FIELD: &str = "span_end";
+const MEMORY_FIELD: &str = "memory";
+
+/**
+ * How the results of expressions are written
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OutputFormat {
+    /**
+     * Only the result of each expression, stopping at the first error
+     */
+    #[default] Plain,
+    /**
+     * A JSON object on each line
+     */
+    Json,
+    /**
+     * Comma separated values, with a header row
+     */
+    Csv,
+    /**
+     * Tab separated values, with a header row
+     */
+    Tsv
+}
+
+impl FromStr for OutputFormat {
+    type Err = ConsoleErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(ConsoleErr::argument_err(format!("Invalid format '{s}'; expected 'plain', 'json', 'csv' or 'tsv'.").as_str()))
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Plain => f.write_str("plain"),
+            Self::Json => f.write_str("json"),
+            Self::Csv => f.write_str("csv"),
+            Self::Tsv => f.write_str("tsv")
+        }
+    }
+}
+
+/**
+ * How expressions are written instead of being evaluated
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Emit {
+    /**
+     * LaTeX math, without the delimiters of math mode
+     */
+    Latex,
+    /**
+     * A MathML 'math' element
+     */
+    MathMl
+}
+
+impl FromStr for Emit {
+    type Err = ConsoleErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "latex" => Ok(Self::Latex),
+            "mathml" => Ok(Self::MathMl),
+            _ => Err(ConsoleErr::argument_err(format!("Invalid emit '{s}'; expected 'latex' or 'mathml'.").as_str()))
+        }
+    }
+}
+
+/**
+ * The outcome of evaluating a single input, and optionally the calculator's memory afterwards
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Record<'a> {
+    pub expression: &'a str,
+    pub result: Result<Value, CalculatorErr>,
+    pub memory: Option<Vec<Value>>
+}
+
+/**
+ * The header row written before any records, if the format has one
+ */
+pub fn header(format: OutputFormat, include_memory: bool) -> Option<String> {
+    let mut fields = vec![EXPRESSION_FIELD, RESULT_FIELD, ERROR_CODE_FIELD, ERROR_MESSAGE_FIELD, SPAN_START_FIELD, SPAN_END_FIELD];
+
+    if include_memory {
+        fields.push(MEMORY_FIELD);
+    }
+
+    match format {
+        OutputFormat::Csv => Some(delimited(b',', &fields)),
+        OutputFormat::Tsv => Some(delimited(b'\t', &fields)),
+        OutputFormat::Plain | OutputFormat::Json => None
+    }
+}
+
+/**
+ * Write the record as a single line in the given format
+ */
+pub fn format_record(format: OutputFormat, record: &Record) -> String {
+    let error = record.result.as_ref().err();
+    let span = error.and_then(|err| span(record.expression, err));
+
+    match format {
+        OutputFormat::Plain => match &record.result {
+            Ok(value) => value.to_string(),
+            Err(err) => err.to_string()
+        },
+        OutputFormat::Json => {
+            let mut object = json!({
+                EXPRESSION_FIELD: record.expression,
+                RESULT_FIELD: record.result.as_ref().ok().map(value_json),
+                ERROR_FIELD: error.map(|err| json!({
+                    "code": ConsoleErr::from(err.clone()).code(),
+                    "message": err.to_string(),
+                    "span": span.map(|(start, end)| json!({ "start": start, "end": end }))
+                }))
+            });
+
+            if let Some(memory) = &record.memory {
+                let assigned: serde_json::Map<String, serde_json::Value> = assigned_memory(memory)
+                    .map(|(i, value)| (i.to_string(), value_json(value)))
+                    .collect();
+
+                object[MEMORY_FIELD] = serde_json::Value::Object(assigned);
+            }
+
+            object.to_string()
+        },
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let (start, end) = match span {
+                Some((start, end)) => (start.to_string(), end.to_string()),
+                None => (String::new(), String::new())
+            };
+
+            let mut fields = vec![
+                record.expression.to_string(),
+                record.result.as_ref().map(single_line).unwrap_or_default(),
+                error.map(|err| ConsoleErr::from(err.clone()).code().to_string()).unwrap_or_default(),
+                error.map(|err| err.to_string()).unwrap_or_default(),
+                start,
+                end
+            ];
+
+            if let Some(memory) = &record.memory {
+                let assigned: Vec<String> = assigned_memory(memory)
+                    .map(|(i, value)| format!("$m{i} = {}", single_line(value)))
+                    .collect();
+
+                fields.push(assigned.join("; "));
+            }
+
+            delimited(if format == OutputFormat::Csv { b',' } else { b'\t' }, &fields)
+        }
+    }
+}
+
+/**
+ * Write a table as lines in the given format, each row having a cell for each column.
+ *
+ * Plain tables have their columns aligned, with the error in place of a cell which failed;
+ * other formats leave the cell empty, or null in JSON, where each row is an object keyed by the column names.
+ */
+pub fn format_table(format: OutputFormat, columns: &[String], rows: &[Vec<Result<Value, CalculatorErr>>]) -> Vec<String> {
+    match format {
+        OutputFormat::Plain => {
+            let cells: Vec<Vec<String>> = rows.iter()
+                .map(|row| row.iter().map(|cell| match cell {
+                    Ok(value) => single_line(value),
+                    Err(err) => err.to_string()
+                }).collect())
+                .collect();
+
+            let widths: Vec<usize> = columns.iter()
+                .enumerate()
+                .map(|(i, column)| cells.iter()
+                    .filter_map(|row| row.get(i))
+                    .chain(std::iter::once(column))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or_default())
+                .collect();
+
+            std::iter::once(columns.to_vec())
+                .chain(cells)
+                .map(|row| row.iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:>width$}"))
+                    .collect::<Vec<String>>()
+                    .join("  "))
+                .collect()
+        },
+        //Objects are written field by field, since serde_json sorts the keys of its maps, and the columns should stay in order
+        OutputFormat::Json => rows.iter()
+            .map(|row| {
+                let fields: Vec<String> = columns.iter()
+                    .zip(row)
+                    .map(|(column, cell)| format!("{}:{}", json!(column), cell.as_ref().map(value_json).unwrap_or(serde_json::Value::Null)))
+                    .collect();
+
+                format!("{{{}}}", fields.join(","))
+            })
+            .collect(),
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let delimiter = if format == OutputFormat::Csv { b',' } else { b'\t' };
+
+            std::iter::once(delimited(delimiter, columns))
+                .chain(rows.iter().map(|row| {
+                    let fields: Vec<String> = row.iter().map(|cell| cell.as_ref().map(single_line).unwrap_or_default()).collect();
+                    delimited(delimiter, &fields)
+                }))
+                .collect()
+        }
+    }
+}
+
+/**
+ * Where in the expression parsing failed, as the start and end character of the unexpected input
+ */
+fn span(expression: &str, err: &CalculatorErr) -> Option<(usize, usize)> {
+    if let CalculatorErr::ParseErr { lah, .. } = err {
+        let len = expression.trim().chars().count();
+        Some((*lah, (*lah + 1).min(len).max(*lah)))
+    }
+    else {
+        None
+    }
+}
+
+/**
+ * Memory which has been assigned to, and its index
+ */
+fn assigned_memory(memory: &[Value]) -> impl Iterator<Item = (usize, &Value)> {
+    memory.iter()
+        .enumerate()
+        .filter(|(_, value)| **value != Value::default())
+}
+
+/**
+ * Numbers are written as JSON numbers, lists as arrays, and matrices as arrays of rows
+ */
+fn value_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Number(n) => number_json(*n),
+        Value::List(list) => serde_json::Value::Array(list.iter().copied().map(number_json).collect()),
+        Value::Matrix(matrix) => serde_json::Value::Array((0..matrix.rows())
+            .map(|row| serde_json::Value::Array(matrix.row(row).into_iter().map(number_json).collect()))
+            .collect())
+    }
+}
+
+/**
+ * Numbers that JSON can't represent are written by name, the same as when values are serialized: 'NaN', 'inf' or '-inf'
+ */
+fn number_json(n: f64) -> serde_json::Value {
+    if n.is_nan() {
+        json!("NaN")
+    }
+    else if n == f64::INFINITY {
+        json!("inf")
+    }
+    else if n == f64::NEG_INFINITY {
+        json!("-inf")
+    }
+    else {
+        json!(n)
+    }
+}
+
+/**
+ * Matrices span several lines, so write them on a single line
+ */
+fn single_line(value: &Value) -> String {
+    value.to_string().replace('\n', "")
+}
+
+/**
+ * Join the fields with the delimiter, quoting any that contain the delimiter, quotes or line breaks
+ */
+fn delimited<S: AsRef<str>>(delimiter: u8, fields: &[S]) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+
+    //Writing to memory can't fail
+    _ = writer.write_record(fields.iter().map(|field| field.as_ref()));
+
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).trim_end_matches(['\r', '\n']).to_string()
+}
@@ -0,0 +1,68 @@
+use super::{OutputFormat, Record, delimited, format_record, header};
+use crate::calculator::calculator_interpreter::interpreter::value::{Value, matrix::Matrix};
+
+/**
+ * The record of an expression which evaluated to the value, without memory
+ */
+fn result_record(expression: &str, value: Value) -> Record<'_> {
+    Record { expression, result: Ok(value), memory: None }
+}
+
+#[test]
+/**
+ * Test that numbers JSON can't represent are written by name, so that they can be told apart from having no result
+ */
+fn format_record_0() {
+    let record = result_record("1/0", Value::Number(f64::INFINITY));
+    assert_eq!(format_record(OutputFormat::Json, &record), r#"{"error":null,"expression":"1/0","result":"inf"}"#);
+
+    let record = result_record("[-1/0, sqrt(-1), 2]", Value::List(vec![f64::NEG_INFINITY, f64::NAN, 2_f64]));
+    assert_eq!(format_record(OutputFormat::Json, &record), r#"{"error":null,"expression":"[-1/0, sqrt(-1), 2]","result":["-inf","NaN",2.0]}"#);
+
+    let record = result_record("[[1/0, 1]]", Value::Matrix(Matrix::new(1, 2, vec![f64::INFINITY, 1_f64]).unwrap()));
+    assert_eq!(format_record(OutputFormat::Json, &record), r#"{"error":null,"expression":"[[1/0, 1]]","result":[["inf",1.0]]}"#);
+}
+
+#[test]
+/**
+ * Test that errors are written with their code, message and where parsing failed, and that only assigned memory is written
+ */
+fn format_record_1() {
+    use crate::calculator::calculator_err::CalculatorErr;
+
+    let err = CalculatorErr::parse_err("Unexpected token.", false, 2);
+    let record = Record { expression: "1 +", result: Err(err), memory: None };
+
+    assert_eq!(format_record(OutputFormat::Plain, &record), "Unexpected token.");
+    assert_eq!(format_record(OutputFormat::Csv, &record), "1 +,,parse,Unexpected token.,2,3");
+    assert_eq!(format_record(OutputFormat::Json, &record), r#"{"error":{"code":"parse","message":"Unexpected token.","span":{"end":3,"start":2}},"expression":"1 +","result":null}"#);
+
+    let mut memory = vec![Value::default(); 4];
+    memory[2] = Value::Number(5_f64);
+    let record = Record { expression: "$m2: 5", result: Ok(Value::Number(5_f64)), memory: Some(memory) };
+
+    assert_eq!(format_record(OutputFormat::Plain, &record), "5");
+    assert_eq!(format_record(OutputFormat::Tsv, &record), "$m2: 5\t5\t\t\t\t\t$m2 = 5");
+    assert_eq!(format_record(OutputFormat::Json, &record), r#"{"error":null,"expression":"$m2: 5","memory":{"2":5.0},"result":5.0}"#);
+}
+
+#[test]
+/**
+ * Test that fields are quoted only when they contain the delimiter, quotes or line breaks
+ */
+fn delimited_0() {
+    assert_eq!(delimited(b',', &["max(1, 2)", "2", ""]), r#""max(1, 2)",2,"#);
+    assert_eq!(delimited(b'\t', &["max(1, 2)", "a\tb"]), "max(1, 2)\t\"a\tb\"");
+    assert_eq!(delimited(b',', &["say \"hi\"", "line\nbreak"]), "\"say \"\"hi\"\"\",\"line\nbreak\"");
+}
+
+#[test]
+/**
+ * Test that only delimited formats have a header, which includes memory if it is written
+ */
+fn header_0() {
+    assert_eq!(header(OutputFormat::Csv, false).unwrap(), "expression,result,error_code,error_message,span_start,span_end");
+    assert_eq!(header(OutputFormat::Tsv, true).unwrap(), "expression\tresult\terror_code\terror_message\tspan_start\tspan_end\tmemory");
+    assert_eq!(header(OutputFormat::Json, true), None);
+    assert_eq!(header(OutputFormat::Plain, false), None);
+}
@@ -0,0 +1,335 @@
+use colored::{Color, Colorize};
+
+#[cfg(test)]
+pub mod tests;
+
+use super::calculator::{Calculator, EvaluateOptions, calculator_interpreter::interpreter::{EvaluateOptions as InterpreterOptions, value::Value}, calculator_plot::{self, Curve, PlotFunction, Point, Viewport}};
+
+/**
+ * Separates the expressions of a plot with more than one series, i.e. 'sin(x); cos(x)'
+ */
+pub const SERIES_SEPARATOR: char = ';';
+
+/**
+ * The colors the series are drawn in, repeating once they run out
+ */
+const COLORS: [Color; 6] = [Color::Blue, Color::Red, Color::Green, Color::Magenta, Color::Yellow, Color::Cyan];
+
+/**
+ * The size the plot is drawn at when the size of the terminal isn't known
+ */
+pub const DEFAULT_SIZE: (usize, usize) = (80, 24);
+
+/**
+ * The largest size the plot is drawn at; larger sizes, such as those reported for a terminal without a size, are treated as unknown
+ */
+const MAX_SIZE: (usize, usize) = (1000, 500);
+
+/**
+ * The lines of the terminal left for the axis labels, legend and prompt around the plot
+ */
+const RESERVED_ROWS: usize = 5;
+
+/**
+ * Each character of the plot is a braille pattern of 2 by 4 dots
+ */
+const DOTS_ACROSS: usize = 2;
+const DOTS_DOWN: usize = 4;
+
+/**
+ * The bit of a braille pattern for each dot, by row and then by column
+ */
+const BRAILLE_DOTS: [[u8; DOTS_ACROSS]; DOTS_DOWN] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/**
+ * The first of the braille patterns, with no dots
+ */
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/**
+ * The expressions to plot, and the range of x to plot them across
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlotRequest {
+    pub functions: Vec<(String, PlotFunction)>,
+    pub from: f64,
+    pub to: f64
+}
+
+impl PlotRequest {
+    /**
+     * Read the argument of ':plot', which is the expressions to plot separated by ';', optionally followed by the range of x to plot across.
+     * Trailing words are only read as the range if they evaluate to numbers, and what comes before them still parses, i.e. 'x - 5' has no range.
+     */
+    pub fn parse(calculator: &Calculator, argument: &str) -> Result<Self, String> {
+        let default = Viewport::default();
+        let mut first_err = None;
+
+        for (expressions, bounds) in trailing_numbers(calculator, argument, 2) {
+            let (from, to) = match bounds[..] {
+                [from, to] => (from, to),
+                [from] => (from, default.x_max),
+                _ => (default.x_min, default.x_max)
+            };
+
+            match parse_functions(calculator, expressions, "plot") {
+                Ok(functions) if from < to => return Ok(Self { functions, from, to }),
+                Ok(_) => return Err(format!("Expected the start of the range to be before its end; got {from} to {to}.")),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+
+        Err(first_err.unwrap_or_default())
+    }
+}
+
+/**
+ * Draw the series of a plot in braille to fit in a terminal of the given number of columns and rows, with the y axis labelled on the left,
+ * the x axis labelled underneath, and a legend with the color of each series
+ */
+pub fn render(calculator: &Calculator, request: &PlotRequest, size: (usize, usize)) -> Vec<String> {
+    let (columns, rows) = match size {
+        (columns, rows) if columns == 0 || rows == 0 || columns > MAX_SIZE.0 || rows > MAX_SIZE.1 => DEFAULT_SIZE,
+        size => size
+    };
+
+    let functions: Vec<PlotFunction> = request.functions.iter().map(|(_, function)| function.clone()).collect();
+
+    //Fit the y range to the values at each column, before knowing how wide the labels make the plot
+    let viewport = PlotFunction::fit(&functions, calculator.interpreter(), request.from, request.to, columns.max(1) * DOTS_ACROSS);
+
+    let height = rows.saturating_sub(RESERVED_ROWS).max(4);
+    let y_ticks = calculator_plot::ticks(viewport.y_min, viewport.y_max, (height / 3).max(2));
+    let label_width = y_ticks.iter().map(|tick| tick.label.chars().count()).max().unwrap_or_default();
+    let width = columns.saturating_sub(label_width + 2).max(10);
+
+    let mut canvas = Canvas::new(width, height, viewport);
+    canvas.draw_axes();
+
+    for (series, function) in functions.iter().enumerate() {
+        let curve = function.sample(calculator.interpreter(), &viewport, width * DOTS_ACROSS);
+        canvas.draw_curve(&curve, series);
+    }
+
+    //Each tick of the y axis is labelled on the row it falls in
+    let mut lines: Vec<String> = (0..height).map(|row| {
+        let tick = y_ticks.iter().find(|tick| canvas.row_of(tick.value) == Some(row));
+
+        match tick {
+            Some(tick) => format!("{:>label_width$} ┤{}", tick.label, canvas.row(row)),
+            None => format!("{:label_width$} │{}", "", canvas.row(row))
+        }
+    }).collect();
+
+    //Each tick of the x axis is marked on the bottom edge, and labelled underneath as long as it doesn't overlap the previous label
+    let mut edge: Vec<char> = vec!['─'; width];
+    let mut labels: Vec<char> = vec![' '; width + label_width + 2];
+    let mut labels_end = 0;
+
+    for tick in calculator_plot::ticks(viewport.x_min, viewport.x_max, (width / 10).max(2)) {
+        let column = match canvas.column_of(tick.value) {
+            Some(column) => column,
+            None => continue
+        };
+
+        edge[column] = '┬';
+
+        let label: Vec<char> = tick.label.chars().collect();
+        let start = (column + label_width + 2).saturating_sub(label.len() / 2).min(labels.len().saturating_sub(label.len()));
+
+        if start >= labels_end && start + label.len() <= labels.len() {
+            labels[start..start + label.len()].copy_from_slice(&label);
+            labels_end = start + label.len() + 1;
+        }
+    }
+
+    lines.push(format!("{:label_width$} └{}", "", edge.into_iter().collect::<String>()));
+    lines.push(labels.into_iter().collect::<String>().trim_end().to_string());
+
+    for (series, (expression, function)) in request.functions.iter().enumerate() {
+        let color = COLORS[series % COLORS.len()];
+        lines.push(format!("{} {}", "━━".color(color), format!("f{}({}) = {expression}", series + 1, function.variable()).dimmed()));
+    }
+
+    lines
+}
+
+/**
+ * A grid of braille characters, each made up of dots which are drawn on individually, and colored by the last series drawn on them
+ */
+struct Canvas {
+    width: usize,
+    height: usize,
+    viewport: Viewport,
+    dots: Vec<u8>,
+    /**
+     * The series drawn on each character last, if any
+     */
+    series: Vec<Option<usize>>
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, viewport: Viewport) -> Self {
+        Self {
+            width,
+            height,
+            viewport,
+            dots: vec![0; width * height],
+            series: vec![None; width * height]
+        }
+    }
+
+    /**
+     * The position of a point in dots, from the left and from the top, which may be off the canvas
+     */
+    fn to_dots(&self, point: Point) -> (f64, f64) {
+        let (dots_across, dots_down) = ((self.width * DOTS_ACROSS) as f64, (self.height * DOTS_DOWN) as f64);
+
+        (
+            (point.x - self.viewport.x_min) / self.viewport.width() * dots_across,
+            //Points far off the canvas are moved to just off its edge, so that steep curves are still drawn towards it
+            ((self.viewport.y_max - point.y) / self.viewport.height() * dots_down).clamp(-dots_down, dots_down * 2.0)
+        )
+    }
+
+    /**
+     * The row of characters a value of y falls in, if it's on the canvas
+     */
+    fn row_of(&self, y: f64) -> Option<usize> {
+        let (_, down) = self.to_dots(Point::new(self.viewport.x_min, y));
+
+        //A value on the bottom edge is in the last row
+        (down >= 0.0 && down <= (self.height * DOTS_DOWN) as f64).then(|| (down as usize / DOTS_DOWN).min(self.height - 1))
+    }
+
+    /**
+     * The column of characters a value of x falls in, if it's on the canvas
+     */
+    fn column_of(&self, x: f64) -> Option<usize> {
+        let (across, _) = self.to_dots(Point::new(x, self.viewport.y_max));
+
+        //A value on the right edge is in the last column
+        (across >= 0.0 && across <= (self.width * DOTS_ACROSS) as f64).then(|| (across as usize / DOTS_ACROSS).min(self.width - 1))
+    }
+
+    fn set(&mut self, across: i64, down: i64, series: Option<usize>) {
+        if across < 0 || down < 0 || across >= (self.width * DOTS_ACROSS) as i64 || down >= (self.height * DOTS_DOWN) as i64 {
+            return;
+        }
+
+        let (across, down) = (across as usize, down as usize);
+        let cell = down / DOTS_DOWN * self.width + across / DOTS_ACROSS;
+
+        self.dots[cell] |= BRAILLE_DOTS[down % DOTS_DOWN][across % DOTS_ACROSS];
+
+        if series.is_some() {
+            self.series[cell] = series;
+        }
+    }
+
+    /**
+     * Draw a line of dots between two positions, stepping by one dot along whichever direction it changes most in
+     */
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), series: Option<usize>) {
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as usize;
+
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            self.set((from.0 + (to.0 - from.0) * t).floor() as i64, (from.1 + (to.1 - from.1) * t).floor() as i64, series);
+        }
+    }
+
+    /**
+     * Draw the axes through zero, where they can be seen
+     */
+    fn draw_axes(&mut self) {
+        let (across, down) = self.to_dots(Point::new(0.0, 0.0));
+        let (dots_across, dots_down) = ((self.width * DOTS_ACROSS) as f64, (self.height * DOTS_DOWN) as f64);
+
+        self.line((0.0, down), (dots_across - 1.0, down), None);
+        self.line((across, 0.0), (across, dots_down - 1.0), None);
+    }
+
+    fn draw_curve(&mut self, curve: &Curve, series: usize) {
+        for segment in &curve.segments {
+            let dots: Vec<(f64, f64)> = segment.iter().map(|point| self.to_dots(*point)).collect();
+
+            match dots.as_slice() {
+                [single] => self.line(*single, *single, Some(series)),
+                dots => dots.windows(2).for_each(|pair| self.line(pair[0], pair[1], Some(series)))
+            }
+        }
+    }
+
+    /**
+     * A row of characters, with each series in its color and the axes dimmed
+     */
+    fn row(&self, row: usize) -> String {
+        (row * self.width..(row + 1) * self.width)
+            .map(|cell| {
+                let character = char::from_u32(BRAILLE_BLANK + self.dots[cell] as u32).unwrap_or(' ').to_string();
+
+                match self.series[cell] {
+                    Some(series) => character.color(COLORS[series % COLORS.len()]).to_string(),
+                    None if self.dots[cell] != 0 => character.dimmed().to_string(),
+                    None => character
+                }
+            })
+            .collect()
+    }
+}
+
+/**
+ * The ways of reading up to count trailing words of the argument as numbers, and what comes before them,
+ * preferring as many numbers as possible; the numbers are in the order they were written
+ */
+pub fn trailing_numbers<'a>(calculator: &Calculator, argument: &'a str, count: usize) -> Vec<(&'a str, Vec<f64>)> {
+    let mut splits = vec![(argument, Vec::new())];
+
+    while splits.len() <= count {
+        let (rest, numbers) = &splits[splits.len() - 1];
+
+        match split_number(calculator, rest) {
+            (Some(n), before) => {
+                let numbers = std::iter::once(n).chain(numbers.iter().copied()).collect();
+                splits.push((before, numbers));
+            },
+            (None, _) => break
+        }
+    }
+
+    splits.reverse();
+    splits
+}
+
+/**
+ * Split the last word off the argument, if it evaluates to a number, i.e. '-pi' or '2*pi'
+ */
+fn split_number<'a>(calculator: &Calculator, argument: &'a str) -> (Option<f64>, &'a str) {
+    let (rest, last) = match argument.trim_end().rsplit_once(char::is_whitespace) {
+        Some(split) => split,
+        None => return (None, argument)
+    };
+
+    //Preview the number, so that it doesn't change history
+    let options = EvaluateOptions::new(InterpreterOptions::new(true));
+
+    match calculator.evaluate_with_options(last, options) {
+        Ok((Value::Number(n), _)) if n.is_finite() => (Some(n), rest.trim_end()),
+        _ => (None, argument)
+    }
+}
+
+/**
+ * Parse each of the expressions separated by ';' as a function of one variable, naming the verb which failed in errors
+ */
+pub fn parse_functions(calculator: &Calculator, expressions: &str, verb: &str) -> Result<Vec<(String, PlotFunction)>, String> {
+    expressions.split(SERIES_SEPARATOR)
+        .map(str::trim)
+        .map(|expression| PlotFunction::parse(calculator, expression)
+            .map(|function| (String::from(expression), function))
+            .map_err(|err| format!("Failed to {verb} '{expression}': {err}")))
+        .collect()
+}
@@ -0,0 +1,69 @@
+use crate::calculator::Calculator;
+
+use super::{PlotRequest, DEFAULT_SIZE, render, trailing_numbers};
+
+/**
+ * The expressions and range the argument of ':plot' is read as
+ */
+fn plot_request(calculator: &Calculator, argument: &str) -> (Vec<String>, f64, f64) {
+    let request = PlotRequest::parse(calculator, argument).unwrap();
+    let expressions = request.functions.iter().map(|(expression, _)| expression.clone()).collect();
+
+    (expressions, request.from, request.to)
+}
+
+#[test]
+/**
+ * Test that the words at the end of the argument are read as numbers, as long as what comes before them still parses
+ */
+fn trailing_numbers_0() {
+    let calculator = Calculator::default();
+
+    assert_eq!(trailing_numbers(&calculator, "sin(x) -pi 2*pi", 2), vec![
+        ("sin(x)", vec![-std::f64::consts::PI, 2_f64 * std::f64::consts::PI]),
+        ("sin(x) -pi", vec![2_f64 * std::f64::consts::PI]),
+        ("sin(x) -pi 2*pi", vec![])
+    ]);
+    assert_eq!(trailing_numbers(&calculator, "x 1 2 3", 2), vec![("x 1", vec![2_f64, 3_f64]), ("x 1 2", vec![3_f64]), ("x 1 2 3", vec![])]);
+    assert_eq!(trailing_numbers(&calculator, "x", 2), vec![("x", vec![])]);
+    assert_eq!(trailing_numbers(&calculator, "x 1/0", 2), vec![("x 1/0", vec![])]);
+}
+
+#[test]
+/**
+ * Test that the argument of ':plot' is read as expressions separated by ';', optionally followed by the range of x
+ */
+fn plot_request_0() {
+    let calculator = Calculator::default();
+
+    assert_eq!(plot_request(&calculator, "sin(x)"), (vec!["sin(x)".to_string()], -10_f64, 10_f64));
+    assert_eq!(plot_request(&calculator, "sin(x); cos(x) 0 5"), (vec!["sin(x)".to_string(), "cos(x)".to_string()], 0_f64, 5_f64));
+    assert_eq!(plot_request(&calculator, "x^2 -3"), (vec!["x^2".to_string()], -3_f64, 10_f64));
+    assert_eq!(plot_request(&calculator, "x - 5"), (vec!["x - 5".to_string()], -10_f64, 10_f64));
+}
+
+#[test]
+/**
+ * Test that ':plot' fails for expressions that don't parse, and for ranges that end before they start
+ */
+fn plot_request_1() {
+    let calculator = Calculator::default();
+
+    for argument in ["", "sin(", "x + y", "x 5 1"] {
+        assert!(PlotRequest::parse(&calculator, argument).is_err(), "Testing '{argument}'");
+    }
+}
+
+#[test]
+/**
+ * Test that a plot is drawn at the default size when the terminal reports no usable size
+ */
+fn render_0() {
+    let calculator = Calculator::default();
+    let request = PlotRequest::parse(&calculator, "sin(x)").unwrap();
+    let expected = render(&calculator, &request, DEFAULT_SIZE);
+
+    for size in [(usize::MAX, usize::MAX), (0, 0), (80, usize::MAX)] {
+        assert_eq!(render(&calculator, &request, size), expected, "Testing {size:?}");
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::Path;
+
+use super::{calculator::{self, calculator_interpreter::interpreter::value::Value}, console_err::ConsoleErr};
+
+#[cfg(test)]
+pub mod tests;
+
+const COMMENT: char = '#';
+const LINE_CONTINUATION: char = '\\';
+
+const LET_KEYWORD: &str = "let";
+const FN_KEYWORD: &str = "fn";
+
+/*
+ * A script is a file of statements, run in order by the same calculator, so that history, memory,
+ * variables and functions carry over from one statement to the next.
+ *
+ * Everything following '#' on a line is a comment. A statement continues onto the next line
+ * while it has unclosed brackets, or if its line ends with '\'.
+ *
+ * Statements:
+ *  - 'let {name} = {expr}': evaluate the expression and assign it to a variable
+ *  - 'fn {name}({params}) = {expr}': define a function of the given parameters
+ *  - '{expr}': evaluate the expression and print the result
+ */
+
+/**
+ * A statement read from a script, and the line it starts on
+ */
+#[derive(Debug, PartialEq, Clone)]
+struct Statement {
+    line: usize,
+    /**
+     * The line the statement ends on, which is the same as the line it starts on unless it spans several
+     */
+    end_line: usize,
+    text: String
+}
+
+/**
+ * Run each statement of the script in the file, printing the results of expressions.
+ *
+ * Errors are reported with the line of the statement that caused them; unless continuing on errors, the script stops at the first error.
+ */
+pub fn run(calculator: &mut calculator::Calculator, path: &Path, continue_on_error: bool) -> Result<(), ConsoleErr> {
+    let script = std::fs::read_to_string(path)
+        .map_err(|err| ConsoleErr::io_err(format!("Failed to read script '{}': {err}", path.display()).as_str()))?;
+
+    let statements = read_statements(&script)
+        .map_err(|(line, err)| ConsoleErr::parse_err(format!("{}:{line}: {err}", path.display()).as_str()))?;
+
+    //Report the first failure, so that the exit code reflects why the script failed
+    let mut first_err: Option<ConsoleErr> = None;
+    let mut failed = 0_usize;
+
+    for statement in statements {
+        match execute(calculator, &statement.text) {
+            Ok(Some(result)) => println!("{result}"),
+            Ok(None) => {},
+            Err(err) => {
+                let report = err.map_message(|message| format!("{}:{}: {message}", path.display(), statement.line));
+
+                if !continue_on_error {
+                    return Err(report);
+                }
+
+                eprintln!("{report}");
+                first_err.get_or_insert(report);
+                failed += 1;
+            }
+        }
+    }
+
+    match first_err {
+        Some(err) => Err(err.map_message(|_| format!("{failed} statement(s) in '{}' failed.", path.display()))),
+        None => Ok(())
+    }
+}
+
+/**
+ * Write each statement of the script in the file in canonical form, so that scripts are laid out the same way.
+ *
+ * Blank lines and comments are kept, with the comments of a statement that spans several lines following it on one line.
+ * Unless writing the file in place, the formatted script is printed.
+ */
+pub fn format(path: &Path, write: bool) -> Result<(), ConsoleErr> {
+    let script = std::fs::read_to_string(path)
+        .map_err(|err| ConsoleErr::io_err(format!("Failed to read script '{}': {err}", path.display()).as_str()))?;
+
+    let statements = read_statements(&script)
+        .map_err(|(line, err)| ConsoleErr::parse_err(format!("{}:{line}: {err}", path.display()).as_str()))?;
+
+    //Scripts are always read in infix notation
+    let calculator = calculator::Calculator::default();
+
+    let lines: Vec<&str> = script.lines().collect();
+    let mut statements = statements.into_iter().peekable();
+    let mut formatted: Vec<String> = Vec::new();
+    let mut i = 0_usize;
+
+    while i < lines.len() {
+        let statement = match statements.next_if(|statement| statement.line == i + 1) {
+            Some(statement) => statement,
+            //Lines between statements only have comments, if anything
+            None => {
+                formatted.push(String::from(lines[i].trim()));
+                i += 1;
+                continue;
+            }
+        };
+
+        let mut line = format_statement(&calculator, &statement.text)
+            .map_err(|err| err.map_message(|message| format!("{}:{}: {message}", path.display(), statement.line)))?;
+
+        let comments: Vec<&str> = lines[i..statement.end_line].iter()
+            .filter_map(|line| line.split_once(COMMENT))
+            .map(|(_, comment)| comment.trim())
+            .filter(|comment| !comment.is_empty())
+            .collect();
+
+        if !comments.is_empty() {
+            line.push_str(&format!(" {COMMENT} {}", comments.join(" ")));
+        }
+
+        formatted.push(line);
+        i = statement.end_line;
+    }
+
+    let formatted = formatted.iter()
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+
+    if write {
+        std::fs::write(path, formatted)
+            .map_err(|err| ConsoleErr::io_err(format!("Failed to write script '{}': {err}", path.display()).as_str()))
+    }
+    else {
+        print!("{formatted}");
+        Ok(())
+    }
+}
+
+/**
+ * Split a script into statements, removing comments and joining statements that span several lines
+ */
+fn read_statements(script: &str) -> Result<Vec<Statement>, (usize, String)> {
+    let mut statements: Vec<Statement> = Vec::new();
+    let mut current: Option<Statement> = None;
+    let mut depth = 0_i32;
+
+    for (i, line) in script.lines().enumerate() {
+        let code = match line.split_once(COMMENT) {
+            Some((code, _)) => code,
+            None => line
+        }.trim();
+
+        let (code, continued) = match code.strip_suffix(LINE_CONTINUATION) {
+            Some(code) => (code.trim_end(), true),
+            None => (code, false)
+        };
+
+        if code.is_empty() && current.is_none() {
+            continue;
+        }
+
+        for c in code.chars() {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        let statement = current.get_or_insert_with(|| Statement { line: i + 1, end_line: i + 1, text: String::new() });
+        statement.end_line = i + 1;
+
+        if !statement.text.is_empty() && !code.is_empty() {
+            statement.text.push(' ');
+        }
+
+        statement.text.push_str(code);
+
+        if depth < 0 {
+            return Err((statement.line, String::from("Unexpected closing bracket.")));
+        }
+
+        if depth == 0 && !continued {
+            statements.extend(current.take().filter(|statement| !statement.text.is_empty()));
+        }
+    }
+
+    match current {
+        Some(statement) if depth > 0 => Err((statement.line, String::from("Expected closing bracket before the end of the script."))),
+        Some(statement) => {
+            statements.extend(Some(statement).filter(|statement| !statement.text.is_empty()));
+            Ok(statements)
+        },
+        None => Ok(statements)
+    }
+}
+
+/**
+ * What a statement does, with its parts
+ */
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum StatementKind<'a> {
+    Let { name: &'a str, expression: &'a str },
+    Fn { name: &'a str, params: Vec<&'a str>, body: &'a str },
+    Expression(&'a str)
+}
+
+/**
+ * Split a statement into its parts
+ */
+fn statement_kind(statement: &str) -> Result<StatementKind<'_>, ConsoleErr> {
+    if let Some(definition) = keyword_argument(statement, LET_KEYWORD) {
+        let (name, expression) = definition.split_once('=')
+            .ok_or_else(|| ConsoleErr::parse_err(format!("Expected '{LET_KEYWORD} <name> = <expression>'.").as_str()))?;
+
+        Ok(StatementKind::Let { name: name.trim(), expression })
+    }
+    else if let Some(definition) = keyword_argument(statement, FN_KEYWORD) {
+        let expected = || ConsoleErr::parse_err(format!("Expected '{FN_KEYWORD} <name>(<parameters>) = <expression>'.").as_str());
+
+        let (signature, body) = definition.split_once('=').ok_or_else(expected)?;
+        let (name, params) = signature.trim().strip_suffix(')')
+            .and_then(|signature| signature.split_once('('))
+            .ok_or_else(expected)?;
+
+        let params: Vec<&str> = params.split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .collect();
+
+        Ok(StatementKind::Fn { name: name.trim(), params, body })
+    }
+    else {
+        Ok(StatementKind::Expression(statement))
+    }
+}
+
+/**
+ * Run a single statement, returning the result if it is an expression
+ */
+fn execute(calculator: &mut calculator::Calculator, statement: &str) -> Result<Option<Value>, ConsoleErr> {
+    match statement_kind(statement)? {
+        StatementKind::Let { name, expression } => {
+            let value = calculator.evaluate(expression)?;
+
+            calculator.set_variable(name, value)?;
+            Ok(None)
+        },
+        StatementKind::Fn { name, params, body } => {
+            calculator.define_function(name, &params, body)?;
+            Ok(None)
+        },
+        StatementKind::Expression(expression) => Ok(Some(calculator.evaluate(expression)?))
+    }
+}
+
+/**
+ * Write a single statement in canonical form
+ */
+fn format_statement(calculator: &calculator::Calculator, statement: &str) -> Result<String, ConsoleErr> {
+    match statement_kind(statement)? {
+        StatementKind::Let { name, expression } => Ok(format!("{LET_KEYWORD} {name} = {}", calculator.parse(expression)?.to_canonical_string())),
+        StatementKind::Fn { name, params, body } => Ok(format!("{FN_KEYWORD} {name}({}) = {}", params.join(", "), calculator.parse(body)?.to_canonical_string())),
+        StatementKind::Expression(expression) => Ok(calculator.parse(expression)?.to_canonical_string())
+    }
+}
+
+/**
+ * If the statement starts with the given keyword, get the rest of the statement
+ */
+fn keyword_argument<'a>(statement: &'a str, keyword: &str) -> Option<&'a str> {
+    statement.split_once(char::is_whitespace)
+        .filter(|(first, _)| *first == keyword)
+        .map(|(_, rest)| rest)
+}
@@ -0,0 +1,65 @@
+use super::{Statement, StatementKind, read_statements, statement_kind};
+
+/**
+ * A statement with its text, and the lines it starts and ends on
+ */
+fn statement(line: usize, end_line: usize, text: &str) -> Statement {
+    Statement { line, end_line, text: text.to_string() }
+}
+
+#[test]
+/**
+ * Test that statements are read one per line, leaving out blank lines and comments
+ */
+fn read_statements_0() {
+    let script = "# A comment\n\nlet a = 2 # Trailing comment\n   \na * 3\n#\n";
+
+    assert_eq!(read_statements(script), Ok(vec![
+        statement(3, 3, "let a = 2"),
+        statement(5, 5, "a * 3")
+    ]));
+}
+
+#[test]
+/**
+ * Test that statements continue onto the next line while they have unclosed brackets, or if their line ends with '\'
+ */
+fn read_statements_1() {
+    let script = "max(1,\n  2, # The second\n  3)\n1 + \\\n2\nsum([1,\n\n2])";
+
+    assert_eq!(read_statements(script), Ok(vec![
+        statement(1, 3, "max(1, 2, 3)"),
+        statement(4, 5, "1 + 2"),
+        statement(6, 8, "sum([1, 2])")
+    ]));
+}
+
+#[test]
+/**
+ * Test that unbalanced brackets are reported on the line of the statement they're in
+ */
+fn read_statements_2() {
+    assert_eq!(read_statements("1\n2)\n3").map_err(|(line, _)| line), Err(2));
+    assert_eq!(read_statements("1\n(2 +\n3").map_err(|(line, _)| line), Err(2));
+}
+
+#[test]
+/**
+ * Test that statements are split into variable assignments, function definitions and expressions
+ */
+fn statement_kind_0() {
+    assert_eq!(statement_kind("let rate = 0.2 * 3").unwrap(), StatementKind::Let { name: "rate", expression: " 0.2 * 3" });
+    assert_eq!(statement_kind("fn hyp(a, b) = sqrt(a^2 + b^2)").unwrap(), StatementKind::Fn { name: "hyp", params: vec!["a", "b"], body: " sqrt(a^2 + b^2)" });
+    assert_eq!(statement_kind("fn seven() = 7").unwrap(), StatementKind::Fn { name: "seven", params: vec![], body: " 7" });
+    assert_eq!(statement_kind("letter + 1").unwrap(), StatementKind::Expression("letter + 1"));
+}
+
+#[test]
+/**
+ * Test that assignments and definitions missing their parts fail
+ */
+fn statement_kind_1() {
+    for statement in ["let rate", "fn hyp(a, b)", "fn hyp = 1", "fn hyp(a = a"] {
+        assert!(statement_kind(statement).is_err(), "Testing '{statement}'");
+    }
+}
@@ -0,0 +1,187 @@
+use colored::Colorize;
+
+#[cfg(test)]
+pub mod tests;
+
+/**
+ * The kinds of token that are colored differently as the user types
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum TokenKind {
+    Number,
+    Dice,
+    Operator,
+    Bracket,
+    Function,
+    Variable,
+    Reference,
+    Unknown,
+    Whitespace
+}
+
+/**
+ * Color the tokens of an expression, given the names of the functions and variables currently defined; commands are shown in bold
+ */
+pub fn highlight(line: &str, functions: &[String], variables: &[String]) -> String {
+    if line.trim_start().starts_with(':') {
+        return line.bold().to_string();
+    }
+
+    tokenize(line, functions, variables).into_iter()
+        .map(|(kind, token)| match kind {
+            TokenKind::Number => token.cyan().to_string(),
+            TokenKind::Dice => token.bright_magenta().to_string(),
+            TokenKind::Operator => token.yellow().to_string(),
+            TokenKind::Bracket => token.bold().to_string(),
+            TokenKind::Function => token.green().to_string(),
+            TokenKind::Variable => token.blue().to_string(),
+            TokenKind::Reference => token.magenta().to_string(),
+            TokenKind::Unknown => token.red().to_string(),
+            TokenKind::Whitespace => token.to_string()
+        })
+        .collect()
+}
+
+/**
+ * Split a line into tokens, without validating the expression as a whole
+ */
+fn tokenize<'l>(line: &'l str, functions: &[String], variables: &[String]) -> Vec<(TokenKind, &'l str)> {
+    let bytes = line.as_bytes();
+    let mut tokens: Vec<(TokenKind, &str)> = Vec::new();
+    let mut i = 0_usize;
+
+    let take_while = |from: usize, f: &dyn Fn(u8) -> bool| -> usize {
+        let mut end = from;
+
+        while end < bytes.len() && f(bytes[end]) {
+            end += 1;
+        }
+
+        end
+    };
+
+    while i < bytes.len() {
+        let c = bytes[i];
+
+        let (kind, end) = if let Some(end) = dice_end(bytes, i) {
+            (TokenKind::Dice, end)
+        }
+        //The end of a range, i.e. '..4', isn't a decimal point
+        else if c.is_ascii_digit() || (c == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) && (i == 0 || bytes[i - 1] != b'.')) {
+            let mut end = take_while(i, &|b| b.is_ascii_digit());
+
+            //Don't treat the start of a range as a decimal point
+            if bytes.get(end) == Some(&b'.') && bytes.get(end + 1) != Some(&b'.') {
+                end = take_while(end + 1, &|b| b.is_ascii_digit());
+            }
+
+            (TokenKind::Number, end)
+        }
+        else if c == b'$' {
+            let mut end = i + 1;
+
+            if bytes.get(end) == Some(&b'm') {
+                end += 1;
+            }
+
+            end = take_while(end, &|b| b.is_ascii_digit());
+
+            //Include the colon of a memory assignment
+            if bytes.get(end) == Some(&b':') {
+                end += 1;
+            }
+
+            (TokenKind::Reference, end)
+        }
+        else if c.is_ascii_alphabetic() || c == b'_' {
+            let end = take_while(i, &|b| b.is_ascii_alphanumeric() || b == b'_');
+            let id = &line[i..end];
+
+            //Function names ignore case, whereas variable names don't
+            if functions.iter().any(|function| function.eq_ignore_ascii_case(id)) {
+                (TokenKind::Function, end)
+            }
+            else if variables.iter().any(|variable| variable == id) {
+                (TokenKind::Variable, end)
+            }
+            else {
+                (TokenKind::Unknown, end)
+            }
+        }
+        else if c.is_ascii_whitespace() {
+            (TokenKind::Whitespace, take_while(i, &|b| b.is_ascii_whitespace()))
+        }
+        else if b"()[]".contains(&c) {
+            (TokenKind::Bracket, i + 1)
+        }
+        else if b"+-*/^%!,.".contains(&c) {
+            (TokenKind::Operator, i + 1)
+        }
+        else {
+            //Keep multi-byte characters whole
+            let len = line[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+            (TokenKind::Unknown, i + len)
+        };
+
+        tokens.push((kind, &line[i..end]));
+        i = end;
+    }
+
+    tokens
+}
+
+/**
+ * If dice notation, such as '4d6!kh3', starts at the given index, find where it ends
+ */
+fn dice_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let digits_end = |from: usize| -> usize {
+        let mut end = from;
+
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+
+        end
+    };
+
+    //Dice can't be part of an identifier
+    if start > 0 && (bytes[start - 1].is_ascii_alphanumeric() || bytes[start - 1] == b'_') {
+        return None;
+    }
+
+    //The letters of dice notation ignore case, the same as when it is parsed
+    let letter = |i: usize| bytes.get(i).map(u8::to_ascii_lowercase);
+
+    let mut end = digits_end(start);
+
+    if letter(end) != Some(b'd') {
+        return None;
+    }
+
+    let sides_end = digits_end(end + 1);
+
+    if sides_end == end + 1 {
+        return None;
+    }
+
+    end = sides_end;
+
+    if bytes.get(end) == Some(&b'!') {
+        end += 1;
+    }
+
+    if let (Some(b'k' | b'd'), Some(b'h' | b'l')) = (letter(end), letter(end + 1)) {
+        let count_end = digits_end(end + 2);
+
+        if count_end > end + 2 {
+            end = count_end;
+        }
+    }
+
+    //Dice followed by letters are part of an identifier instead
+    if bytes.get(end).is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_') {
+        return None;
+    }
+
+    Some(end)
+}
@@ -0,0 +1,87 @@
+use super::{TokenKind, dice_end, tokenize};
+
+/**
+ * The kinds of the tokens of the line, with whitespace left out
+ */
+fn kinds<'l>(line: &'l str, functions: &[&str], variables: &[&str]) -> Vec<(TokenKind, &'l str)> {
+    let functions: Vec<String> = functions.iter().map(|function| function.to_string()).collect();
+    let variables: Vec<String> = variables.iter().map(|variable| variable.to_string()).collect();
+
+    tokenize(line, &functions, &variables).into_iter()
+        .filter(|(kind, _)| *kind != TokenKind::Whitespace)
+        .collect()
+}
+
+#[test]
+/**
+ * Test that numbers, operators, brackets and references are told apart, keeping ranges and decimals whole
+ */
+fn tokenize_0() {
+    assert_eq!(kinds("(1.5 + $2) * $m3: 1..4", &[], &[]), vec![
+        (TokenKind::Bracket, "("),
+        (TokenKind::Number, "1.5"),
+        (TokenKind::Operator, "+"),
+        (TokenKind::Reference, "$2"),
+        (TokenKind::Bracket, ")"),
+        (TokenKind::Operator, "*"),
+        (TokenKind::Reference, "$m3:"),
+        (TokenKind::Number, "1"),
+        (TokenKind::Operator, "."),
+        (TokenKind::Operator, "."),
+        (TokenKind::Number, "4")
+    ]);
+}
+
+#[test]
+/**
+ * Test that names are functions ignoring case, variables matching case, or otherwise unknown
+ */
+fn tokenize_1() {
+    assert_eq!(kinds("SQRT(rate) + Rate + hyp", &["sqrt", "hyp"], &["rate"]), vec![
+        (TokenKind::Function, "SQRT"),
+        (TokenKind::Bracket, "("),
+        (TokenKind::Variable, "rate"),
+        (TokenKind::Bracket, ")"),
+        (TokenKind::Operator, "+"),
+        (TokenKind::Unknown, "Rate"),
+        (TokenKind::Operator, "+"),
+        (TokenKind::Function, "hyp")
+    ]);
+}
+
+#[test]
+/**
+ * Test that dice are a single token in either case, and that characters outside of ascii are kept whole
+ */
+fn tokenize_2() {
+    assert_eq!(kinds("4d6!kh3 + 2D20DL1 - é", &[], &[]), vec![
+        (TokenKind::Dice, "4d6!kh3"),
+        (TokenKind::Operator, "+"),
+        (TokenKind::Dice, "2D20DL1"),
+        (TokenKind::Operator, "-"),
+        (TokenKind::Unknown, "é")
+    ]);
+}
+
+#[test]
+/**
+ * Test where dice notation ends, and that dice which are part of an identifier aren't dice
+ */
+fn dice_end_0() {
+    let inputs = vec![
+        ("d6", 0, Some(2)),
+        ("3d6 + 1", 0, Some(3)),
+        ("10D8!", 0, Some(5)),
+        ("4d6kh3", 0, Some(6)),
+        ("4D6KL2", 0, Some(6)),
+        ("4d", 0, None),
+        ("4x6", 0, None),
+        ("4d6x", 0, None),
+        ("a4d6", 1, None),
+        ("d6a", 0, None)
+    ];
+
+    for (input, start, expected) in inputs {
+        assert_eq!(dice_end(input.as_bytes(), start), expected, "Testing '{input}'");
+    }
+}
@@ -0,0 +1,91 @@
+use super::{
+    calculator::{Calculator, calculator_err::CalculatorErr, calculator_interpreter::interpreter::{TableRow, value::Value}, calculator_plot::{PlotFunction, Viewport}},
+    output::{self, OutputFormat},
+    plot
+};
+
+#[cfg(test)]
+pub mod tests;
+
+/**
+ * The step between values of the variable when it isn't given
+ */
+const DEFAULT_STEP: f64 = 1.0;
+
+/**
+ * The expressions to tabulate, and the values of their variable to evaluate them for
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableRequest {
+    pub functions: Vec<(String, PlotFunction)>,
+    pub from: f64,
+    pub to: f64,
+    pub step: f64
+}
+
+impl TableRequest {
+    /**
+     * Read the argument of ':table', which is the expressions to tabulate separated by ';', optionally followed by the first and last value of the variable,
+     * and the step between values. As with ':plot', trailing words are only read as numbers if what comes before them still parses.
+     */
+    pub fn parse(calculator: &Calculator, argument: &str) -> Result<Self, String> {
+        let default = Viewport::default();
+        let mut first_err = None;
+
+        for (expressions, numbers) in plot::trailing_numbers(calculator, argument, 3) {
+            let (from, to, step) = match numbers[..] {
+                [from, to, step] => (from, to, step),
+                [from, to] => (from, to, DEFAULT_STEP),
+                [from] => (from, default.x_max, DEFAULT_STEP),
+                _ => (default.x_min, default.x_max, DEFAULT_STEP)
+            };
+
+            match plot::parse_functions(calculator, expressions, "tabulate") {
+                Ok(functions) => return Ok(Self { functions, from, to, step }),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+
+        Err(first_err.unwrap_or_default())
+    }
+}
+
+/**
+ * Evaluate each expression for each value of its variable, writing a row for each value in the given format.
+ * The first column is the value of the variable, named after the first expression's variable, followed by a column for each expression.
+ */
+pub fn render<F>(calculator: &Calculator, request: &TableRequest, format: OutputFormat, round: F) -> Result<Vec<String>, String>
+    where F : Fn(Value) -> Value
+{
+    let interpreter = calculator.interpreter();
+
+    //Tabulating is like plotting, so leaves memory and the source of randomness as they were
+    let series = interpreter.with_scratch_state(|| request.functions.iter()
+        .map(|(_, function)| interpreter.evaluate_table(function.expression(), function.variable(), request.from, request.to, request.step))
+        .collect::<Result<Vec<Vec<TableRow>>, CalculatorErr>>())
+        .map_err(|err| err.to_string())?;
+
+    let variable = request.functions.first().map(|(_, function)| function.variable()).unwrap_or_default();
+
+    let columns: Vec<String> = std::iter::once(variable.to_string())
+        .chain(request.functions.iter().map(|(expression, _)| expression.clone()))
+        .collect();
+
+    //Every series has the same values of the variable, so take them from the first
+    let xs: Vec<f64> = series.first().map(|rows| rows.iter().map(|(x, _)| *x).collect()).unwrap_or_default();
+
+    let rows: Vec<Vec<Result<Value, CalculatorErr>>> = xs.iter()
+        .enumerate()
+        .map(|(i, x)| std::iter::once(Ok(round(Value::Number(*x))))
+            .chain(series.iter().map(|rows| rows[i].1.clone().map(&round)))
+            .collect())
+        .collect();
+
+    if rows.is_empty() {
+        return Err(format!("There are no values from {} to {} in steps of {}.", request.from, request.to, request.step));
+    }
+
+    Ok(output::format_table(format, &columns, &rows))
+}
@@ -0,0 +1,58 @@
+use crate::{calculator::Calculator, output::OutputFormat};
+
+use super::{TableRequest, render};
+
+/**
+ * The expressions and values of the variable the argument of ':table' is read as
+ */
+fn table_request(calculator: &Calculator, argument: &str) -> (Vec<String>, f64, f64, f64) {
+    let request = TableRequest::parse(calculator, argument).unwrap();
+    let expressions = request.functions.iter().map(|(expression, _)| expression.clone()).collect();
+
+    (expressions, request.from, request.to, request.step)
+}
+
+#[test]
+/**
+ * Test that the argument of ':table' is read as expressions separated by ';', optionally followed by the first and last value and the step
+ */
+fn table_request_0() {
+    let calculator = Calculator::default();
+
+    assert_eq!(table_request(&calculator, "x^2"), (vec!["x^2".to_string()], -10_f64, 10_f64, 1_f64));
+    assert_eq!(table_request(&calculator, "x^2; 2*x 0 1 0.25"), (vec!["x^2".to_string(), "2*x".to_string()], 0_f64, 1_f64, 0.25_f64));
+    assert_eq!(table_request(&calculator, "x^2 1 3"), (vec!["x^2".to_string()], 1_f64, 3_f64, 1_f64));
+    assert_eq!(table_request(&calculator, "x^2 5"), (vec!["x^2".to_string()], 5_f64, 10_f64, 1_f64));
+    assert_eq!(table_request(&calculator, "x - 5"), (vec!["x - 5".to_string()], -10_f64, 10_f64, 1_f64));
+}
+
+#[test]
+/**
+ * Test that ':table' fails for expressions that don't parse
+ */
+fn table_request_1() {
+    let calculator = Calculator::default();
+
+    for argument in ["", "sin(", "x + y 0 1"] {
+        assert!(TableRequest::parse(&calculator, argument).is_err(), "Testing '{argument}'");
+    }
+}
+
+#[test]
+/**
+ * Test that a table has a column for the variable and each expression, and fails when there are no values to tabulate
+ */
+fn render_0() {
+    let calculator = Calculator::default();
+
+    let request = TableRequest::parse(&calculator, "x^2; 2*x 0 2").unwrap();
+    assert_eq!(render(&calculator, &request, OutputFormat::Csv, |value| value), Ok(vec![
+        "x,x^2,2*x".to_string(),
+        "0,0,0".to_string(),
+        "1,1,2".to_string(),
+        "2,4,4".to_string()
+    ]));
+
+    let request = TableRequest::parse(&calculator, "x 2 0").unwrap();
+    assert!(render(&calculator, &request, OutputFormat::Csv, |value| value).is_err());
+}
@@ -1,14 +1,17 @@
 pub mod calculator_parser;
 pub mod calculator_interpreter;
 pub mod calculator_err;
+pub mod calculator_session;
+pub mod calculator_plot;
 
 use std::fmt::Debug;
 
-use calculator_interpreter::interpreter::{Interpreter, EvaluateOptions as InterpreterOptions};
-use calculator_parser::parser::Parser;
+use calculator_interpreter::interpreter::{Interpreter, AngleMode, EvaluateOptions as InterpreterOptions, function::UserFunction, random_source::Roll, value::Value};
+use calculator_parser::{expression::{Expr, ExprPrime}, parser::{Parser, Notation}};
 use calculator_err::CalculatorErr;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EvaluateOptions {
     pub interpreter_options: Option<InterpreterOptions>
 }
@@ -29,16 +32,43 @@ impl EvaluateOptions {
 }
 
 #[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CalculatorState {
-    pub memory: Vec<f64>,
-    pub history: Vec<f64>
+    pub memory: Vec<Value>,
+    pub history: Vec<Value>,
+    pub rolls: Vec<Roll>,
+    /**
+     * Variables and user defined functions, ordered by name
+     */
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub variables: Vec<(String, Value)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub functions: Vec<(String, UserFunction)>
 }
 
 impl CalculatorState {
-    pub fn new(memory: Vec<f64>, history: Vec<f64>) -> Self {
+    pub fn new(memory: Vec<Value>, history: Vec<Value>) -> Self {
+        Self::with_rolls(memory, history, Vec::new())
+    }
+
+    pub fn with_rolls(memory: Vec<Value>, history: Vec<Value>, rolls: Vec<Roll>) -> Self {
         Self {
             memory,
-            history
+            history,
+            rolls,
+            variables: Vec::new(),
+            functions: Vec::new()
+        }
+    }
+
+    /**
+     * The state with the given variables and user defined functions
+     */
+    pub fn with_definitions(self, variables: Vec<(String, Value)>, functions: Vec<(String, UserFunction)>) -> Self {
+        Self {
+            variables,
+            functions,
+            ..self
         }
     }
 }
@@ -58,33 +88,107 @@ impl Calculator {
         }
     }
 
+    /**
+     * Create a calculator with the memory, history and definitions of a previous state
+     */
+    pub fn from_state(state: CalculatorState) -> Self {
+        let mut calculator = Self::default();
+        calculator.restore_state(state);
+        calculator
+    }
+
+    /**
+     * Replace the calculator's memory, history, variables and user defined functions with those of a previous state
+     */
+    pub fn restore_state(&mut self, state: CalculatorState) {
+        self.interpreter.restore_state(state.memory, state.history);
+        self.interpreter.restore_definitions(state.variables, state.functions)
+    }
+
     pub fn clone_current_state(&self) -> CalculatorState {
-        CalculatorState::new(self.interpreter.clone_mem(), self.interpreter.clone_stack())
+        CalculatorState::with_rolls(self.interpreter.clone_mem(), self.interpreter.clone_stack(), self.interpreter.clone_rolls())
+            .with_definitions(self.interpreter.clone_variables(), self.interpreter.clone_user_funcs())
     }
 
-    pub fn evaluate_with_options(&self, expression: &str, options: EvaluateOptions) -> Result<(f64, CalculatorState), CalculatorErr> {
+    pub fn evaluate_with_options(&self, expression: &str, options: EvaluateOptions) -> Result<(Value, CalculatorState), CalculatorErr> {
         let prepared_expression = Calculator::prepare_string(expression);
-
-        let parsed = match self.parser.parse(prepared_expression) {
-            Ok(value) => value,
-            Err(e) => Err(CalculatorErr::parse_err(format!("An error occurred while parsing expression '{prepared_expression}'. At {}: {e}", e.lah()).as_str(), false, e.lah()))?
-        };
+        let parsed = self.parse(prepared_expression)?;
 
         let (evaluated, mem) = match self.interpreter.evaluate_with_options(parsed, options.interpreter()) {
             Ok(value) => value,
             Err(e) => Err(CalculatorErr::eval_err(format!("An error occurred while evaluating expression '{prepared_expression}': {e}").as_str()))?
         };
 
-        Ok((evaluated, CalculatorState::new(mem.unwrap_or_else(|| self.interpreter.clone_mem()), self.interpreter.clone_stack())))
+        let state = CalculatorState::with_rolls(mem.unwrap_or_else(|| self.interpreter.clone_mem()), self.interpreter.clone_stack(), self.interpreter.clone_rolls())
+            .with_definitions(self.interpreter.clone_variables(), self.interpreter.clone_user_funcs());
+
+        Ok((evaluated, state))
     }
 
-    pub fn evaluate(&self, expression: &str) -> Result<f64, CalculatorErr> {
+    /**
+     * Parse the expression without evaluating it
+     */
+    pub fn parse(&self, expression: &str) -> Result<Expr, CalculatorErr> {
+        let prepared_expression = Calculator::prepare_string(expression);
+
+        match self.parser.parse(prepared_expression) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(CalculatorErr::parse_err(format!("An error occurred while parsing expression '{prepared_expression}'. At {}: {e}", e.lah()).as_str(), false, e.lah()))
+        }
+    }
+
+    pub fn evaluate(&self, expression: &str) -> Result<Value, CalculatorErr> {
         match self.evaluate_with_options(expression, EvaluateOptions::default()) {
             Err(e) => Err(e),
             Ok((value, _)) => Ok(value)
         }
     }
 
+    /**
+     * Evaluate postfix input as a step of a stack calculator, with history as the stack: each value left by the input is pushed onto history,
+     * after removing the results of history it took as operands, such that '3 4' followed by '+' leaves 7 in place of 3 and 4.
+     * Returns the values pushed, from the bottom of the stack to the top.
+     */
+    pub fn evaluate_postfix_stack(&mut self, expression: &str) -> Result<Vec<Value>, CalculatorErr> {
+        let prepared_expression = Calculator::prepare_string(expression);
+        let parser = Parser::new(self.parser.settings().with_notation(Notation::Postfix));
+
+        let stack = match parser.parse_postfix_stack(prepared_expression) {
+            Ok(stack) => stack,
+            Err(e) => Err(CalculatorErr::parse_err(format!("An error occurred while parsing expression '{prepared_expression}'. At {}: {e}", e.lah()).as_str(), false, e.lah()))?
+        };
+
+        let initial_state = self.clone_current_state();
+        let history_len = initial_state.history.len();
+
+        if stack.history_operands > history_len {
+            Err(CalculatorErr::eval_err(format!("Expected {} values on the stack for '{prepared_expression}'; history has {history_len}.", stack.history_operands).as_str()))?
+        }
+
+        let mut results: Vec<Value> = Vec::new();
+
+        for value in stack.values {
+            //Every value refers to history as it was before the input, including the results of history it takes as operands
+            self.interpreter.restore_state(self.interpreter.clone_mem(), initial_state.history.clone());
+
+            match self.interpreter.evaluate(Expr::ExprPrime(Box::new(value))) {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    self.restore_state(initial_state);
+                    return Err(CalculatorErr::eval_err(format!("An error occurred while evaluating expression '{prepared_expression}': {e}").as_str()));
+                }
+            }
+        }
+
+        let mut history = initial_state.history;
+        history.truncate(history_len - stack.history_operands);
+        history.extend(results.iter().cloned());
+
+        self.interpreter.restore_state(self.interpreter.clone_mem(), history);
+
+        Ok(results)
+    }
+
     pub fn clear_stack(&mut self) {
         self.interpreter.clear_stack()
     }
@@ -93,6 +197,79 @@ impl Calculator {
         self.interpreter.clear_mem()
     }
 
+    /**
+     * Assign a value to a variable, which can then be used by name in expressions
+     */
+    pub fn set_variable(&mut self, name: &str, value: Value) -> Result<(), CalculatorErr> {
+        self.validate_name(name)?;
+        self.interpreter.set_variable(name, value);
+        Ok(())
+    }
+
+    pub fn remove_variable(&mut self, name: &str) -> Option<Value> {
+        self.interpreter.remove_variable(name)
+    }
+
+    /**
+     * Remove all variables and user defined functions
+     */
+    pub fn clear_variables(&mut self) {
+        self.interpreter.clear_variables()
+    }
+
+    /**
+     * Define a function whose body is an expression of its parameters, i.e. 'hyp' with parameters 'a' and 'b' and body 'sqrt(a^2 + b^2)'.
+     * The body is parsed when the function is defined, but is only evaluated when the function is called.
+     */
+    pub fn define_function(&mut self, name: &str, params: &[&str], body: &str) -> Result<(), CalculatorErr> {
+        self.validate_name(name)?;
+
+        for param in params {
+            self.validate_name(param)?;
+        }
+
+        let prepared_body = Calculator::prepare_string(body);
+
+        let parsed = match self.infix_parser().parse(prepared_body) {
+            Ok(Expr::ExprPrime(expr_prime)) => *expr_prime,
+            Ok(Expr::None) => Err(CalculatorErr::eval_err(format!("Expected a body for function '{name}'.").as_str()))?,
+            Err(e) => Err(CalculatorErr::parse_err(format!("An error occurred while parsing the body of function '{name}' '{prepared_body}'. At {}: {e}", e.lah()).as_str(), false, e.lah()))?
+        };
+
+        let params = params.iter()
+            .map(|param| param.to_string())
+            .collect();
+
+        self.interpreter.define_function(name, UserFunction::new(params, parsed))
+    }
+
+    /**
+     * Set whether trigonometric functions take, and their inverses return, radians or degrees
+     */
+    pub fn set_angle_mode(&mut self, angle_mode: AngleMode) {
+        self.interpreter.set_angle_mode(angle_mode)
+    }
+
+    pub fn angle_mode(&self) -> AngleMode {
+        self.interpreter.angle_mode()
+    }
+
+    /**
+     * Set whether expressions are read as infix, i.e. '(3 + 4) * 2', or postfix, i.e. '3 4 + 2 *'.
+     * Variable names and the bodies of user defined functions are always infix.
+     */
+    pub fn set_notation(&mut self, notation: Notation) {
+        self.parser = Parser::new(self.parser.settings().with_notation(notation))
+    }
+
+    pub fn notation(&self) -> Notation {
+        self.parser.settings().notation
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.interpreter.seed(seed)
+    }
+
     pub fn has_history(&self) -> bool {
         self.interpreter.has_history()
     }
@@ -105,6 +282,26 @@ impl Calculator {
         &self.interpreter
     }
 
+    /**
+     * Check that the name would be read as a variable in an expression, rather than as a number, dice, constant or function call
+     */
+    pub fn is_valid_name(&self, name: &str) -> bool {
+        matches!(self.infix_parser().parse(name), Ok(Expr::ExprPrime(expr_prime)) if matches!(&*expr_prime, ExprPrime::Id(id) if id.value == name))
+    }
+
+    fn validate_name(&self, name: &str) -> Result<(), CalculatorErr> {
+        if self.is_valid_name(name) {
+            Ok(())
+        }
+        else {
+            Err(CalculatorErr::eval_err(format!("'{name}' is not a valid variable or function name.").as_str()))
+        }
+    }
+
+    fn infix_parser(&self) -> Parser {
+        Parser::new(self.parser.settings().with_notation(Notation::Infix))
+    }
+
     fn prepare_string(expression: &str) -> &str {
         expression.trim()
     }
@@ -1,11 +1,14 @@
 use std::fmt::Display;
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum CalculatorErr {
     #[default] General,
     EvaluateErr { message: String },
     ParseErr { message: String, propagate: bool, lah: usize },
-    InterpretErr { message: String }
+    InterpretErr { message: String },
+    SessionErr { message: String }
 }
 
 impl CalculatorErr {
@@ -14,7 +17,8 @@ impl CalculatorErr {
             Self::General => None,
             Self::EvaluateErr { message } => Some(message.clone()),
             Self::ParseErr { message, ..} => Some(message.clone()),
-            Self::InterpretErr { message } => Some(message.clone())
+            Self::InterpretErr { message } => Some(message.clone()),
+            Self::SessionErr { message } => Some(message.clone())
         }
     } 
 
@@ -48,6 +52,10 @@ impl CalculatorErr {
         Self::ParseErr { message: String::from(message), propagate, lah }
     }
 
+    pub fn session_err(message: &str) -> Self {
+        Self::SessionErr { message: String::from(message) }
+    }
+
 }
 
 impl Display for CalculatorErr {
@@ -56,7 +64,8 @@ impl Display for CalculatorErr {
             Self::General => "An error occurred.",
             Self::EvaluateErr { message } => message,
             Self::ParseErr { message, .. } => message,
-            Self::InterpretErr { message } => message
+            Self::InterpretErr { message } => message,
+            Self::SessionErr { message } => message
         };
 
         f.write_str(message)
@@ -1,11 +1,26 @@
 pub mod function;
+pub mod random_source;
+pub mod value;
 
 use function::{*, function_impl::*, function_lazy_static::*};
+use random_source::{RandomSource, Roll};
+use value::{Value, matrix::Matrix};
 use super::super::calculator_parser::expression;
-use std::{collections::HashMap, cell::RefCell};
+use std::{collections::HashMap, cell::{Cell, RefCell}, fmt::Display};
 use crate::calculator::CalculatorErr;
 
+/**
+ * The most dice that can be rolled in a single dice expression, including dice added by exploding
+ */
+pub const MAX_DICE: usize = 10_000;
+
+/**
+ * The most user defined function calls that can be nested, such as by a function calling itself
+ */
+pub const MAX_CALL_DEPTH: usize = 64;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EvaluateOptions {
     pub preview: bool
 }
@@ -24,11 +39,40 @@ impl Default for EvaluateOptions {
     }
 }
 
+/**
+ * The unit of angles taken by trigonometric functions, and returned by their inverses
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AngleMode {
+    #[default] Radians,
+    Degrees
+}
+
+impl Display for AngleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Radians => f.write_str("radians"),
+            Self::Degrees => f.write_str("degrees")
+        }
+    }
+}
+
+/**
+ * A value of the variable of a table, and the result of the expression for it
+ */
+pub type TableRow = (f64, Result<Value, CalculatorErr>);
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Interpreter {
     functions: HashMap<String, Function>,
-    history: RefCell<Vec<f64>>,
-    memory: RefCell<Vec<f64>>
+    history: RefCell<Vec<Value>>,
+    memory: RefCell<Vec<Value>>,
+    random: RefCell<RandomSource>,
+    variables: RefCell<HashMap<String, Value>>,
+    user_functions: HashMap<String, UserFunction>,
+    call_depth: Cell<usize>,
+    angle_mode: AngleMode
 }
 
 impl Default for Interpreter {
@@ -44,6 +88,9 @@ impl Default for Interpreter {
                 ("FAC".to_string(), FAC.clone()),
                 ("MAX".to_string(), MAX.clone()),
                 ("MIN".to_string(), MIN.clone()),
+                ("SUM".to_string(), SUM.clone()),
+                ("MEAN".to_string(), MEAN.clone()),
+                ("RANGE".to_string(), RANGE.clone()),
                 ("MOD".to_string(), MOD.clone()),
                 ("ABS".to_string(), ABS.clone()),
                 ("CEIL".to_string(), CEIL.clone()),
@@ -82,14 +129,49 @@ impl Default for Interpreter {
                 ("RAND".to_string(), RAND.clone()),
                 ("RRAND".to_string(), RRAND.clone()),
                 ("RRANDI".to_string(), RRANDI.clone()),
+                ("SEED".to_string(), SEED.clone()),
+                ("CHOOSE".to_string(), CHOOSE.clone()),
+                ("SHUFFLE".to_string(), SHUFFLE.clone()),
+                ("NORMPDF".to_string(), NORMPDF.clone()),
+                ("NORMCDF".to_string(), NORMCDF.clone()),
+                ("NORMINV".to_string(), NORMINV.clone()),
+                ("BINOMPDF".to_string(), BINOMPDF.clone()),
+                ("BINOMCDF".to_string(), BINOMCDF.clone()),
+                ("POISSONPDF".to_string(), POISSONPDF.clone()),
+                ("POISSONCDF".to_string(), POISSONCDF.clone()),
+                ("TCDF".to_string(), TCDF.clone()),
+                ("CHISQCDF".to_string(), CHISQCDF.clone()),
+                ("NORMRAND".to_string(), NORMRAND.clone()),
+                ("BINOMRAND".to_string(), BINOMRAND.clone()),
+                ("POISSONRAND".to_string(), POISSONRAND.clone()),
+                ("TRAND".to_string(), TRAND.clone()),
+                ("CHISQRAND".to_string(), CHISQRAND.clone()),
+                ("TRANSPOSE".to_string(), TRANSPOSE.clone()),
+                ("TABLE".to_string(), TABLE.clone()),
+                ("DET".to_string(), DET.clone()),
+                ("INV".to_string(), INV.clone()),
+                ("RANK".to_string(), RANK.clone()),
+                ("SOLVE_LINEAR".to_string(), SOLVE_LINEAR.clone()),
+                ("EIGVALS".to_string(), EIGVALS.clone()),
                 ("SIGN".to_string(), SIGN.clone()),
                 ("COND".to_string(), COND.clone()),
+                ("AND".to_string(), AND.clone()),
+                ("OR".to_string(), OR.clone()),
+                ("XOR".to_string(), XOR.clone()),
+                ("NOT".to_string(), NOT.clone()),
+                ("SHL".to_string(), SHL.clone()),
+                ("SHR".to_string(), SHR.clone()),
                 ("E".to_string(), E.clone()),
                 ("PI".to_string(), PI.clone()),
             ].into_iter()
             .collect(),
             history: RefCell::new(Vec::new()),
-            memory: RefCell::new(vec![0_f64; u8::MAX as usize])
+            memory: RefCell::new(vec![Value::default(); u8::MAX as usize]),
+            random: RefCell::new(RandomSource::default()),
+            variables: RefCell::new(HashMap::new()),
+            user_functions: HashMap::new(),
+            call_depth: Cell::new(0),
+            angle_mode: AngleMode::default()
         }
     }
 }
@@ -101,7 +183,7 @@ impl Interpreter {
     pub fn clear_mem(&mut self) {
         let mut memory = self.memory.borrow_mut();
         memory.clear();
-        memory.resize(u8::MAX as usize, 0_f64);
+        memory.resize(u8::MAX as usize, Value::default());
     }
 
     /**
@@ -112,6 +194,122 @@ impl Interpreter {
         history.clear();
     }
 
+    /**
+     * Replace the calculator's memory and history, such as with a previously saved session.
+     * Memory is padded or truncated to its usual size.
+     */
+    pub fn restore_state(&mut self, memory: Vec<Value>, history: Vec<Value>) {
+        let mut restored_memory = memory;
+        restored_memory.resize(u8::MAX as usize, Value::default());
+
+        *self.memory.borrow_mut() = restored_memory;
+        *self.history.borrow_mut() = history;
+    }
+
+    /**
+     * Assign a value to the variable with the given name, replacing any previous value
+     */
+    pub fn set_variable(&mut self, name: &str, value: Value) {
+        self.variables.borrow_mut().insert(name.to_string(), value);
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<Value> {
+        self.variables.borrow().get(name).cloned()
+    }
+
+    /**
+     * Remove the variable with the given name, returning its value if it was set
+     */
+    pub fn remove_variable(&mut self, name: &str) -> Option<Value> {
+        self.variables.borrow_mut().remove(name)
+    }
+
+    /**
+     * Remove all variables and user defined functions
+     */
+    pub fn clear_variables(&mut self) {
+        self.variables.borrow_mut().clear();
+        self.user_functions.clear();
+    }
+
+    /**
+     * Get the names and values of all variables, ordered by name
+     */
+    pub fn clone_variables(&self) -> Vec<(String, Value)> {
+        let mut variables: Vec<(String, Value)> = self.variables.borrow().iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        variables
+    }
+
+    /**
+     * Define a function, replacing any user defined function of the same name.
+     * Built in functions cannot be replaced.
+     */
+    pub fn define_function(&mut self, name: &str, function: UserFunction) -> Result<(), CalculatorErr> {
+        if self.get_func_by_name(name).is_some() {
+            return Err(CalculatorErr::interp_err(format!("Cannot redefine built in function '{name}'.").as_str()));
+        }
+
+        for (i, param) in function.params.iter().enumerate() {
+            if function.params[..i].contains(param) {
+                return Err(CalculatorErr::interp_err(format!("Function '{name}' has more than one parameter named '{param}'.").as_str()));
+            }
+        }
+
+        self.user_functions.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+        self.user_functions.insert(name.to_string(), function);
+        Ok(())
+    }
+
+    pub fn get_user_func_by_name(&self, function: &str) -> Option<UserFunction> {
+        self.user_functions.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(function))
+            .map(|(_, func)| func.clone())
+    }
+
+    pub fn get_user_funcs(&self) -> Vec<(&String, &UserFunction)> {
+        self.user_functions.iter()
+        .collect()
+    }
+
+    /**
+     * Get the names and definitions of all user defined functions, ordered by name
+     */
+    pub fn clone_user_funcs(&self) -> Vec<(String, UserFunction)> {
+        let mut functions: Vec<(String, UserFunction)> = self.user_functions.iter()
+            .map(|(name, function)| (name.clone(), function.clone()))
+            .collect();
+
+        functions.sort_by(|a, b| a.0.cmp(&b.0));
+        functions
+    }
+
+    /**
+     * Replace all variables and user defined functions, such as with a previously saved session
+     */
+    pub fn restore_definitions(&mut self, variables: Vec<(String, Value)>, functions: Vec<(String, UserFunction)>) {
+        *self.variables.borrow_mut() = variables.into_iter().collect();
+        self.user_functions = functions.into_iter().collect();
+    }
+
+    pub fn set_angle_mode(&mut self, angle_mode: AngleMode) {
+        self.angle_mode = angle_mode;
+    }
+
+    pub fn angle_mode(&self) -> AngleMode {
+        self.angle_mode
+    }
+
+    /**
+     * Reset the source of randomness to the start of the sequence for the given seed
+     */
+    pub fn seed(&mut self, seed: u64) {
+        self.random.borrow_mut().reseed(seed);
+    }
+
     pub fn has_history(&self) -> bool {
         !self.history.borrow().is_empty()
     }
@@ -119,22 +317,29 @@ impl Interpreter {
     /**
      * Create a clone of the calculator's current memory
      */
-    pub fn clone_mem(&self) -> Vec<f64> {
+    pub fn clone_mem(&self) -> Vec<Value> {
         self.memory.borrow().clone()
     }
 
     /**
      * Create a clone of the calculator's current history
      */
-    pub fn clone_stack(&self) -> Vec<f64> {
+    pub fn clone_stack(&self) -> Vec<Value> {
         self.history.borrow().clone()
     }
 
-    pub fn borrow_mem(&self) -> std::cell::Ref<Vec<f64>> {
+    /**
+     * Create a clone of the rolls made while evaluating the most recent expression
+     */
+    pub fn clone_rolls(&self) -> Vec<Roll> {
+        self.random.borrow().rolls().to_vec()
+    }
+
+    pub fn borrow_mem(&self) -> std::cell::Ref<Vec<Value>> {
         self.memory.borrow()
     }
 
-    pub fn borrow_stack(&self) -> std::cell::Ref<Vec<f64>> {
+    pub fn borrow_stack(&self) -> std::cell::Ref<Vec<Value>> {
         self.history.borrow()
     }
 
@@ -151,13 +356,61 @@ impl Interpreter {
         .collect()
     }
 
+    /**
+     * Get the built-in functions whose name, description or category contain the query, ignoring case,
+     * ordered by category and then by name. An empty query matches every function.
+     */
+    pub fn search_funcs(&self, query: &str) -> Vec<(&String, &Function)> {
+        let mut matching: Vec<(&String, &Function)> = self.functions.iter()
+            .filter(|(name, function)| function.matches(name, query))
+            .collect();
+
+        matching.sort_by(|(a_name, a), (b_name, b)| a.info.category.cmp(&b.info.category).then_with(|| a_name.cmp(b_name)));
+        matching
+    }
+
+    /**
+     * Write the list of built-in functions as markdown, with a heading for each category, as in the README
+     */
+    pub fn function_list_markdown(&self) -> String {
+        let functions = self.search_funcs("");
+        let mut lines: Vec<String> = Vec::new();
+
+        for category in FunctionCategory::all() {
+            let in_category: Vec<&(&String, &Function)> = functions.iter()
+                .filter(|(_, function)| function.info.category == category)
+                .collect();
+
+            if in_category.is_empty() {
+                continue;
+            }
+
+            lines.push(format!("##### {category}"));
+
+            for (name, function) in in_category {
+                lines.push(format!(" - {name}{function}"));
+                lines.push(format!("   - {}", function.info.description.replace('*', "\\*")));
+
+                if let Some(domain) = function.info.domain {
+                    lines.push(format!("   - Domain: {domain}"));
+                }
+
+                lines.push(format!("   - Example: `{}`", function.info.example));
+            }
+        }
+
+        lines.join("\n")
+    }
+
     /**
      * Evaluate the given expression with the given options
      */
-    pub fn evaluate_with_options(&self, expression: expression::Expr, options: EvaluateOptions) -> Result<(f64, Option<Vec<f64>>), CalculatorErr> {
+    pub fn evaluate_with_options(&self, expression: expression::Expr, options: EvaluateOptions) -> Result<(Value, Option<Vec<Value>>), CalculatorErr> {
+        self.random.borrow_mut().clear_rolls();
+
         let (evaluated_result, evaluated_memory) = match expression {
             expression::Expr::None => {
-                let result = Ok(0_f64);
+                let result = Ok(Value::default());
                 if options.preview {
                     let temp_mem = self.memory.borrow().clone();
                     (result, Some(temp_mem))
@@ -170,22 +423,26 @@ impl Interpreter {
 
                 if options.preview {
                     let temp_mem = self.memory.borrow().clone();
+                    let temp_random = self.random.borrow().clone();
 
-                    let result: Result<f64, CalculatorErr> = self.evaluate_expr_prime(*expr_prime);
+                    let result: Result<Value, CalculatorErr> = self.evaluate_expr_prime(&expr_prime);
 
                     let result_mem = self.memory.borrow().clone();
 
                     //Reset memory back to its original state (as stored in temp_mem)
                     let mut mem = self.memory.borrow_mut();
 
-                    for (i, elem) in temp_mem.iter().enumerate() {
-                        mem[i] = *elem;
+                    for (i, elem) in temp_mem.into_iter().enumerate() {
+                        mem[i] = elem;
                     }
 
+                    //Reset the source of randomness, so that the preview matches the eventual result
+                    self.random.borrow_mut().restore_generator(temp_random);
+
                     (result, Some(result_mem))
                 }
                 else {
-                    (self.evaluate_expr_prime(*expr_prime), None)
+                    (self.evaluate_expr_prime(&expr_prime), None)
                 }
             }
         };
@@ -195,7 +452,7 @@ impl Interpreter {
                 let mut history = self.history.borrow_mut();
 
                 if history.is_empty() || *history.last().unwrap() != evaluated {
-                    history.push(evaluated);
+                    history.push(evaluated.clone());
                 }
             }
 
@@ -209,62 +466,269 @@ impl Interpreter {
     /**
      * Evaluate the given expression
      */
-    pub fn evaluate(&self, expression: expression::Expr) -> Result<f64, CalculatorErr> {
+    pub fn evaluate(&self, expression: expression::Expr) -> Result<Value, CalculatorErr> {
         match self.evaluate_with_options(expression, EvaluateOptions::default()) {
             Err(e) => Err(e),
             Ok((result, _)) => Ok(result)
         }
     }
 
-    fn evaluate_expr_prime(&self, expression: expression::ExprPrime) -> Result<f64, CalculatorErr> {
+    /**
+     * Evaluate a parsed expression with variables bound to the values, without adding the result to history.
+     * Variables sharing a name with a binding are restored afterwards, so the same expression can be evaluated for many values without parsing it again.
+     * Memory and the source of randomness change as for any other evaluation, unless evaluated with a scratch state.
+     */
+    pub fn evaluate_with_variables(&self, expression: &expression::ExprPrime, bindings: &[(&str, Value)]) -> Result<Value, CalculatorErr> {
+        let bindings = bindings.iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+
+        self.with_variables(bindings, || self.evaluate_expr_prime(expression))
+    }
+
+    /**
+     * Evaluate a parsed expression for each value of the variable from start to end inclusive, counting in steps of step, without adding the results to history.
+     * Each value of the variable is paired with its own result, so that the expression failing for one value doesn't lose the others.
+     */
+    pub fn evaluate_table(&self, expression: &expression::ExprPrime, variable: &str, start: f64, end: f64, step: f64) -> Result<Vec<TableRow>, CalculatorErr> {
+        let values = range(start, end, step)?.flatten();
+
+        Ok(values.into_iter()
+            .map(|x| (x, self.evaluate_with_variables(expression, &[(variable, Value::Number(x))])))
+            .collect())
+    }
+
+    /**
+     * Run without changing memory or the source of randomness, as when sampling a function for a plot or table,
+     * so that many evaluations leave the session as they found it
+     */
+    pub fn with_scratch_state<T>(&self, f: impl FnOnce() -> T) -> T {
+        let memory = self.memory.borrow().clone();
+        let random = self.random.borrow().clone();
+
+        let result = f();
+
+        *self.memory.borrow_mut() = memory;
+        *self.random.borrow_mut() = random;
+
+        result
+    }
+
+    fn evaluate_expr_prime(&self, expression: &expression::ExprPrime) -> Result<Value, CalculatorErr> {
         match expression {
             expression::ExprPrime::Number(n) => self.evaluate_number(n),
+            expression::ExprPrime::Dice(d) => self.evaluate_dice(d),
             expression::ExprPrime::History(h) => self.evaluate_hist(h),
             expression::ExprPrime::Func(f) => self.evaluate_func(f),
-            expression::ExprPrime::Id(_id) => todo!(),
-            expression::ExprPrime::UnopPrefixesExpression(prefix, subexpr) => self.evaluate_unary_prefixes(prefix, *subexpr),
-            expression::ExprPrime::UnopSuffixesExpression(subexpr, suffixes) => self.evaluate_unary_suffixes(*subexpr, suffixes),
-            expression::ExprPrime::ParenthesesExpression(subexpr) => self.evaluate_expr_prime(*subexpr),
-            expression::ExprPrime::BinaryInfixExpression(first_child, siblings) => self.evaluate_binary_infix_expression(*first_child, siblings),
-            expression::ExprPrime::BinaryInfixFunctionExpression(first_child, siblings) => self.evaluate_binary_infix_function_expression(*first_child, siblings),
+            expression::ExprPrime::Id(id) => self.evaluate_variable(id),
+            expression::ExprPrime::List(elements) => self.evaluate_list(elements),
+            expression::ExprPrime::Range(start, end) => self.evaluate_range(start, end),
+            expression::ExprPrime::Index(subexpr, index) => self.evaluate_index(subexpr, index),
+            expression::ExprPrime::UnopPrefixesExpression(prefix, subexpr) => self.evaluate_unary_prefixes(prefix, subexpr),
+            expression::ExprPrime::UnopSuffixesExpression(subexpr, suffixes) => self.evaluate_unary_suffixes(subexpr, suffixes),
+            expression::ExprPrime::ParenthesesExpression(subexpr) => self.evaluate_expr_prime(subexpr),
+            expression::ExprPrime::BinaryInfixExpression(first_child, siblings) => self.evaluate_binary_infix_expression(first_child, siblings),
+            expression::ExprPrime::BinaryInfixFunctionExpression(first_child, siblings) => self.evaluate_binary_infix_function_expression(first_child, siblings),
             expression::ExprPrime::AccessMem(m) => self.evaluate_mem(m),
-            expression::ExprPrime::StoreMem(m, subexpr) => self.evaluate_store_mem(m, *subexpr)
+            expression::ExprPrime::StoreMem(m, subexpr) => self.evaluate_store_mem(m, subexpr)
+        }
+    }
+
+    fn evaluate_number(&self, expression: &expression::NumberToken) -> Result<Value, CalculatorErr> {
+        Ok(Value::Number(expression.value))
+    }
+
+    /**
+     * Evaluate a list literal; a list whose elements are all lists of the same length is a matrix, with each element a row
+     */
+    fn evaluate_list(&self, elements: &[expression::ExprPrime]) -> Result<Value, CalculatorErr> {
+        let mut numbers: Vec<f64> = Vec::new();
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+
+        for element in elements {
+            match self.evaluate_expr_prime(element)? {
+                Value::Number(n) => numbers.push(n),
+                Value::List(list) => rows.push(list),
+                Value::Matrix(matrix) => return Err(CalculatorErr::interp_err(format!("Lists cannot contain matrices; got a {}x{} matrix.", matrix.rows(), matrix.cols()).as_str()))
+            }
+
+            if !numbers.is_empty() && !rows.is_empty() {
+                return Err(CalculatorErr::interp_err("Lists cannot contain both numbers and lists."));
+            }
+        }
+
+        if rows.is_empty() {
+            Ok(Value::List(numbers))
+        }
+        else {
+            Ok(Value::Matrix(Matrix::from_rows(rows)?))
         }
     }
 
-    fn evaluate_number(&self, expression: expression::NumberToken) -> Result<f64, CalculatorErr> {
-        Ok(expression.value)
+    fn evaluate_range(&self, start: &expression::ExprPrime, end: &expression::ExprPrime) -> Result<Value, CalculatorErr> {
+        let start_value = self.evaluate_expr_prime(start)?.into_number()?;
+        let end_value = self.evaluate_expr_prime(end)?.into_number()?;
+
+        range(start_value, end_value, if end_value >= start_value { 1_f64 } else { -1_f64 })
     }
 
-    fn evaluate_func(&self, expression: expression::Func) -> Result<f64, CalculatorErr> {
+    fn evaluate_index(&self, expression: &expression::ExprPrime, index: &expression::ExprPrime) -> Result<Value, CalculatorErr> {
+        let value = self.evaluate_expr_prime(expression)?;
+        let index_value = self.evaluate_expr_prime(index)?.into_number()?;
+
+        value.index(index_value)
+    }
+
+    fn evaluate_dice(&self, expression: &expression::DiceToken) -> Result<Value, CalculatorErr> {
+        if expression.count == 0 {
+            return Err(CalculatorErr::interp_err(format!("Cannot roll zero dice in '{expression}'.").as_str()));
+        }
+        else if expression.count > MAX_DICE {
+            return Err(CalculatorErr::interp_err(format!("Cannot roll more than {MAX_DICE} dice in '{expression}'.").as_str()));
+        }
+        else if expression.sides == 0 {
+            return Err(CalculatorErr::interp_err(format!("Cannot roll dice with zero sides in '{expression}'.").as_str()));
+        }
+        else if expression.explode && expression.sides == 1 {
+            return Err(CalculatorErr::interp_err(format!("Cannot explode dice with one side in '{expression}'.").as_str()));
+        }
+
+        let mut random = self.borrow_random_mut()?;
+        let mut values: Vec<f64> = Vec::new();
+
+        for _ in 0..expression.count {
+            loop {
+                let value = rand::Rng::gen_range(random.rng(), 1..=expression.sides);
+                values.push(value as f64);
+
+                //Exploding dice are rolled again, adding a new die, whenever they roll their highest value
+                if !expression.explode || value != expression.sides || values.len() >= MAX_DICE {
+                    break;
+                }
+            }
+        }
+
+        //Order the dice from lowest to highest to determine which are kept
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+        let total = values.len();
+        let kept_range = match expression.selection {
+            None => 0..total,
+            Some(expression::DiceSelection::KeepHighest(n)) => total.saturating_sub(n)..total,
+            Some(expression::DiceSelection::KeepLowest(n)) => 0..n.min(total),
+            Some(expression::DiceSelection::DropHighest(n)) => 0..total.saturating_sub(n),
+            Some(expression::DiceSelection::DropLowest(n)) => n.min(total)..total
+        };
+
+        let mut kept = vec![false; total];
+
+        for index in &order[kept_range] {
+            kept[*index] = true;
+        }
+
+        let sum: f64 = values.iter()
+            .zip(kept.iter())
+            .filter(|(_, kept)| **kept)
+            .map(|(value, _)| *value)
+            .sum();
+
+        random.record(Roll::new(expression.to_string(), values, kept));
+
+        Ok(Value::Number(sum))
+    }
+
+    fn evaluate_func(&self, expression: &expression::Func) -> Result<Value, CalculatorErr> {
         let id: String;
-        let args: Vec<expression::ExprPrime>;
+        let args: &[expression::ExprPrime];
 
         match expression {
             expression::Func::EmptyFunc(name) => {
-                id = name.value;
-                args = Vec::new();
+                id = name.value.clone();
+                args = &[];
             },
             expression::Func::ConstantFunc(constant) => {
                 id = constant.to_string();
-                args = Vec::new();
+                args = &[];
             }
             expression::Func::FuncWithArgs(name, f_args) => {
-                id = name.value;
+                id = name.value.clone();
                 args = f_args;
             }
         };
 
+        //Functions which take expressions are passed their arguments unevaluated
+        let expression_func = self.functions.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&id))
+            .and_then(|(_, function)| match function.args {
+                FunctionArgs::Expression(func) => Some(func),
+                _ => None
+            });
+
+        if let Some(func) = expression_func {
+            return func(self, args);
+        }
+
+        let mut evaluated_args: Vec<Value> = Vec::new();
+
+        for arg in args {
+            let val = self.evaluate_expr_prime(arg)?;
+            evaluated_args.push(val);
+        }
+
+        self.call_func(id.as_str(), evaluated_args)
+    }
+
+    /**
+     * Call the function with the given name with already evaluated arguments.
+     * 
+     * Functions of a fixed number of arguments are applied element-wise to lists,
+     * whereas functions of a variable number of arguments receive the elements of lists as arguments.
+     */
+    fn call_func(&self, id: &str, args: Vec<Value>) -> Result<Value, CalculatorErr> {
         let matching: Vec<(&String, &Function)> = self.functions.iter()
-        .filter(|(name, _)| name.eq_ignore_ascii_case(id.as_str()))
+        .filter(|(name, _)| name.eq_ignore_ascii_case(id))
         .collect();
 
         if matching.is_empty() {
-            return Err(CalculatorErr::interp_err(format!("No such function '{id}'.").as_str()))
+            let user_function = self.user_functions.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(id))
+                .map(|(_, function)| function);
+
+            return match user_function {
+                Some(function) => self.call_user_func(id, function, args),
+                None => Err(CalculatorErr::interp_err(format!("No such function '{id}'.").as_str()))
+            };
         }
 
         let (_, function) = matching.first().unwrap();
 
+        //Functions work in radians, so convert to and from degrees
+        let degrees = self.angle_mode == AngleMode::Degrees;
+
+        let args = if degrees && function.angle == AngleUse::Arguments {
+            args.into_iter()
+                .map(|arg| arg.map(|n| Ok(n.to_radians())))
+                .collect::<Result<Vec<Value>, CalculatorErr>>()?
+        }
+        else {
+            args
+        };
+
+        let result = self.apply_func(id, &function.args, args)?;
+
+        if degrees && function.angle == AngleUse::Result {
+            result.map(|n| Ok(n.to_degrees()))
+        }
+        else {
+            Ok(result)
+        }
+    }
+
+    /**
+     * Apply a built in function to its arguments
+     */
+    fn apply_func(&self, id: &str, args_kind: &FunctionArgs, args: Vec<Value>) -> Result<Value, CalculatorErr> {
         fn validate_args_count(name: &str, expected: usize, actual: usize) -> Result<(), CalculatorErr> {
             if actual != expected {
                 Err(CalculatorErr::interp_err(format!("Function '{name}' expected {expected} arguments; got {actual}.").as_str()))
@@ -274,79 +738,101 @@ impl Interpreter {
             }
         }
 
-        fn evaluate_args(interpreter: &Interpreter, args: Vec<expression::ExprPrime>) -> Result<Vec<f64>, CalculatorErr> {
-            let mut evaluated: Vec<f64> = Vec::new();
-
-            for arg in args {
-                let val = interpreter.evaluate_expr_prime(arg)?;
-                evaluated.push(val);
-            }
-
-            Ok(evaluated)
+        fn flatten_args(args: Vec<Value>) -> Vec<f64> {
+            args.into_iter()
+                .flat_map(Value::flatten)
+                .collect()
         }
 
-        match function.args {
+        match *args_kind {
             FunctionArgs::None(func) => {
-                validate_args_count(id.as_str(), 0, args.len())?;
-                func()
+                validate_args_count(id, 0, args.len())?;
+                func().map(Value::Number)
             },
             FunctionArgs::One(func) => {
-                validate_args_count(id.as_str(), 1, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0])
+                validate_args_count(id, 1, args.len())?;
+                Value::broadcast(&args, |a| func(a[0]))
             },
             FunctionArgs::Two(func) => {
-                validate_args_count(id.as_str(), 2, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1])
+                validate_args_count(id, 2, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1]))
             },
             FunctionArgs::Three(func) => {
-                validate_args_count(id.as_str(), 3, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2])
+                validate_args_count(id, 3, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2]))
             },
             FunctionArgs::Four(func) => {
-                validate_args_count(id.as_str(), 4, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2], evaluated_args[3])
+                validate_args_count(id, 4, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2], a[3]))
             },
             FunctionArgs::Five(func) => {
-                validate_args_count(id.as_str(), 5, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2], evaluated_args[3], evaluated_args[4])
+                validate_args_count(id, 5, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2], a[3], a[4]))
             },
             FunctionArgs::Six(func) => {
-                validate_args_count(id.as_str(), 6, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2], evaluated_args[3], evaluated_args[4], 
-                    evaluated_args[5])
+                validate_args_count(id, 6, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2], a[3], a[4], a[5]))
             },
             FunctionArgs::Seven(func) => {
-                validate_args_count(id.as_str(), 7, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2], evaluated_args[3], evaluated_args[4], 
-                    evaluated_args[5], evaluated_args[6])
+                validate_args_count(id, 7, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2], a[3], a[4], a[5], a[6]))
             },
             FunctionArgs::Eight(func) => {
-                validate_args_count(id.as_str(), 8, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2], evaluated_args[3], evaluated_args[4], 
-                    evaluated_args[5], evaluated_args[6], evaluated_args[7])
+                validate_args_count(id, 8, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2], a[3], a[4], a[5], a[6], a[7]))
             },
             FunctionArgs::Nine(func) => {
-                validate_args_count(id.as_str(), 9, args.len())?;
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args[0], evaluated_args[1], evaluated_args[2], evaluated_args[3], evaluated_args[4], 
-                    evaluated_args[5], evaluated_args[6], evaluated_args[7], evaluated_args[8])
+                validate_args_count(id, 9, args.len())?;
+                Value::broadcast(&args, |a| func(a[0], a[1], a[2], a[3], a[4], a[5], a[6], a[7], a[8]))
             },
             FunctionArgs::Variable(func) => {
-                let evaluated_args = evaluate_args(self, args)?;
-                func(evaluated_args)
+                func(flatten_args(args)).map(Value::Number)
+            },
+            FunctionArgs::VariableValue(func) => {
+                func(flatten_args(args))
+            },
+            FunctionArgs::RandomNone(func) => {
+                validate_args_count(id, 0, args.len())?;
+                func(&mut *self.borrow_random_mut()?).map(Value::Number)
             },
+            FunctionArgs::RandomOne(func) => {
+                validate_args_count(id, 1, args.len())?;
+                let mut random = self.borrow_random_mut()?;
+                Value::broadcast(&args, |a| func(&mut random, a[0]))
+            },
+            FunctionArgs::RandomTwo(func) => {
+                validate_args_count(id, 2, args.len())?;
+                let mut random = self.borrow_random_mut()?;
+                Value::broadcast(&args, |a| func(&mut random, a[0], a[1]))
+            },
+            FunctionArgs::RandomVariable(func) => {
+                func(&mut *self.borrow_random_mut()?, flatten_args(args)).map(Value::Number)
+            },
+            FunctionArgs::RandomVariableValue(func) => {
+                func(&mut *self.borrow_random_mut()?, flatten_args(args))
+            },
+            FunctionArgs::ValueOne(func) => {
+                validate_args_count(id, 1, args.len())?;
+                let mut args = args.into_iter();
+                func(args.next().unwrap())
+            },
+            FunctionArgs::ValueTwo(func) => {
+                validate_args_count(id, 2, args.len())?;
+                let mut args = args.into_iter();
+                func(args.next().unwrap(), args.next().unwrap())
+            },
+            FunctionArgs::Expression(_) => {
+                Err(CalculatorErr::interp_err(format!("Function '{id}' must be called with its arguments in parentheses.").as_str()))
+            }
         }
     }
 
-    fn evaluate_hist(&self, expression: expression::HistoryToken) -> Result<f64, CalculatorErr> {
+    fn borrow_random_mut(&self) -> Result<std::cell::RefMut<'_, RandomSource>, CalculatorErr> {
+        self.random.try_borrow_mut()
+            .map_err(|borrow_error| CalculatorErr::interp_err(format!("Failed to access random number generator: {borrow_error}").as_str()))
+    }
+
+    fn evaluate_hist(&self, expression: &expression::HistoryToken) -> Result<Value, CalculatorErr> {
         match self.history.try_borrow() {
             Err(borrow_error) => Err(CalculatorErr::interp_err(format!("Failed to access past results: {borrow_error}").as_str())),
             Ok(history) => {
@@ -354,13 +840,13 @@ impl Interpreter {
                     Err(CalculatorErr::interp_err(format!("History entry {} does not exist.", expression.value).as_str()))
                 }
                 else {
-                    Ok(history[history.len() - (expression.value + 1)])
+                    Ok(history[history.len() - (expression.value + 1)].clone())
                 }
             }
         }
     }
 
-    fn evaluate_mem(&self, expression: expression::MemoryToken) -> Result<f64, CalculatorErr> {
+    fn evaluate_mem(&self, expression: &expression::MemoryToken) -> Result<Value, CalculatorErr> {
         match self.memory.try_borrow() {
             Err(borrow_error) => Err(CalculatorErr::interp_err(format!("Failed to access memory: {borrow_error}").as_str())),
             Ok(memory) => {
@@ -368,13 +854,13 @@ impl Interpreter {
                     Err(CalculatorErr::interp_err(format!("Memory entry {} does not exist.", expression.value).as_str()))
                 }
                 else {
-                    Ok(memory[expression.value])
+                    Ok(memory[expression.value].clone())
                 }
             }
         }
     }
 
-    fn evaluate_store_mem(&self, memory_token: expression::MemoryToken, subexpr: expression::ExprPrime) -> Result<f64, CalculatorErr> {
+    fn evaluate_store_mem(&self, memory_token: &expression::MemoryToken, subexpr: &expression::ExprPrime) -> Result<Value, CalculatorErr> {
         let subexpr_value = self.evaluate_expr_prime(subexpr)?;
 
         match self.memory.try_borrow_mut() {
@@ -384,71 +870,152 @@ impl Interpreter {
                     Err(CalculatorErr::interp_err(format!("Memory entry {} does not exist.", memory_token.value).as_str()))
                 }
                 else {
-                    memory[memory_token.value] = subexpr_value;
+                    memory[memory_token.value] = subexpr_value.clone();
                     Ok(subexpr_value)
                 } 
             }
         }
     }
 
-    // fn evaluate_id(&self, expression: expression::IdToken) -> Result<f64, CalculatorErr> {
-    //     unimplemented!()
-    // }
+    fn evaluate_variable(&self, expression: &expression::IdToken) -> Result<Value, CalculatorErr> {
+        match self.variables.try_borrow() {
+            Err(borrow_error) => Err(CalculatorErr::interp_err(format!("Failed to access variables: {borrow_error}").as_str())),
+            Ok(variables) => variables.get(&expression.value)
+                .cloned()
+                .ok_or_else(|| CalculatorErr::interp_err(format!("No such variable '{}'.", expression.value).as_str()))
+        }
+    }
 
-    fn evaluate_unary_prefixes(&self, prefixes: Vec<expression::UnopPrefix>, expression: expression::ExprPrime) -> Result<f64, CalculatorErr> {
+    /**
+     * Evaluate the body of a user defined function, with its parameters bound to the arguments.
+     * Variables sharing a name with a parameter are restored afterwards.
+     */
+    fn call_user_func(&self, id: &str, function: &UserFunction, args: Vec<Value>) -> Result<Value, CalculatorErr> {
+        if args.len() != function.params.len() {
+            return Err(CalculatorErr::interp_err(format!("Function '{id}' expected {} arguments; got {}.", function.params.len(), args.len()).as_str()));
+        }
+
+        if self.call_depth.get() >= MAX_CALL_DEPTH {
+            return Err(CalculatorErr::interp_err(format!("Function '{id}' exceeded the limit of {MAX_CALL_DEPTH} nested calls.").as_str()));
+        }
+
+        let bindings = function.params.iter()
+            .cloned()
+            .zip(args)
+            .collect();
+
+        self.call_depth.set(self.call_depth.get() + 1);
+        let result = self.with_variables(bindings, || self.evaluate_expr_prime(&function.body));
+        self.call_depth.set(self.call_depth.get() - 1);
+
+        result
+    }
+
+    /**
+     * Run with the variables bound to the values, restoring the variables sharing their names afterwards
+     */
+    fn with_variables<T>(&self, bindings: Vec<(String, Value)>, f: impl FnOnce() -> T) -> T {
+        let shadowed: Vec<(String, Option<Value>)> = {
+            let mut variables = self.variables.borrow_mut();
+
+            bindings.into_iter()
+                .map(|(name, value)| (name.clone(), variables.insert(name, value)))
+                .collect()
+        };
+
+        let result = f();
+
+        let mut variables = self.variables.borrow_mut();
+
+        for (name, previous) in shadowed {
+            match previous {
+                Some(value) => variables.insert(name, value),
+                None => variables.remove(&name)
+            };
+        }
+
+        result
+    }
+
+    fn evaluate_unary_prefixes(&self, prefixes: &[expression::UnopPrefix], expression: &expression::ExprPrime) -> Result<Value, CalculatorErr> {
         let mut subvalue = self.evaluate_expr_prime(expression)?;
 
         for prefix in prefixes {
             match prefix {
-                expression::UnopPrefix::Neg => subvalue *= -1_f64
+                expression::UnopPrefix::Neg => subvalue = subvalue.map(|n| Ok(-n))?
             };
         };
 
         Ok(subvalue)
     }
 
-    fn evaluate_unary_suffixes(&self, expression: expression::ExprPrime, suffixes: Vec<expression::UnopSuffix>) -> Result<f64, CalculatorErr> {
+    fn evaluate_unary_suffixes(&self, expression: &expression::ExprPrime, suffixes: &[expression::UnopSuffix]) -> Result<Value, CalculatorErr> {
         let mut subvalue = self.evaluate_expr_prime(expression)?;
 
         for suffix in suffixes {
             match suffix {
-                expression::UnopSuffix::Fac => subvalue = factorial(subvalue)?
+                expression::UnopSuffix::Fac => subvalue = subvalue.map(factorial)?
             };
         };
 
         Ok(subvalue)
     }
 
-    fn evaluate_binary_infix_expression(&self, first_child: expression::ExprPrime, siblings: Vec<(expression::BinopInfix, Box<expression::ExprPrime>)>) -> Result<f64, CalculatorErr> {
-        let mut value: f64 = self.evaluate_expr_prime(first_child)?;
+    fn evaluate_binary_infix_expression(&self, first_child: &expression::ExprPrime, siblings: &[(expression::BinopInfix, Box<expression::ExprPrime>)]) -> Result<Value, CalculatorErr> {
+        let mut value: Value = self.evaluate_expr_prime(first_child)?;
 
         for (operator, sibling_expr) in siblings {
-            let sibling_value = self.evaluate_expr_prime(*sibling_expr)?;
-
-            match operator {
-                expression::BinopInfix::Exp => value = value.powf(sibling_value),
-                expression::BinopInfix::Mult => value *= sibling_value,
-                expression::BinopInfix::Div => value /= sibling_value,
-                expression::BinopInfix::Rem => value %= sibling_value,
-                expression::BinopInfix::Add => value += sibling_value,
-                expression::BinopInfix::Sub => value -= sibling_value,
-            };
+            let sibling_value = self.evaluate_expr_prime(sibling_expr)?;
+
+            value = apply_binary_operator(*operator, value, sibling_value)?;
         };
 
         Ok(value)
     }
 
-    fn evaluate_binary_infix_function_expression(&self, first_child: expression::ExprPrime, siblings: Vec<(expression::IdToken, Box<expression::ExprPrime>)>) -> Result<f64, CalculatorErr> {
-        let mut value: f64 = self.evaluate_expr_prime(first_child)?;
+    fn evaluate_binary_infix_function_expression(&self, first_child: &expression::ExprPrime, siblings: &[(expression::IdToken, Box<expression::ExprPrime>)]) -> Result<Value, CalculatorErr> {
+        let mut value: Value = self.evaluate_expr_prime(first_child)?;
 
         for (binfunc, sibling_expr) in siblings {
-            value = self.evaluate_func(expression::Func::FuncWithArgs(binfunc, vec![
-                expression::ExprPrime::Number(expression::NumberToken::new(value)), 
-                *sibling_expr]
-            ))?
+            let sibling_value = self.evaluate_expr_prime(sibling_expr)?;
+            value = self.call_func(binfunc.value.as_str(), vec![value, sibling_value])?
         };
 
         Ok(value)
     }
 
-}
\ No newline at end of file
+}
+
+/**
+ * Apply a binary operator to two values.
+ *
+ * Operators are applied element-wise to lists and matrices, except that multiplying two matrices,
+ * or a matrix and a list, is matrix multiplication, and raising a matrix to a number is repeated matrix multiplication.
+ */
+fn apply_binary_operator(operator: expression::BinopInfix, first: Value, second: Value) -> Result<Value, CalculatorErr> {
+    match (&operator, first, second) {
+        (expression::BinopInfix::Mult, Value::Matrix(a), Value::Matrix(b)) => Ok(Value::Matrix(a.mul(&b)?)),
+        //A list on the right is a column vector, and on the left a row vector
+        (expression::BinopInfix::Mult, Value::Matrix(a), Value::List(b)) => Ok(Value::List(a.mul(&Matrix::column(b)?)?.into_data())),
+        (expression::BinopInfix::Mult, Value::List(a), Value::Matrix(b)) => Ok(Value::List(Matrix::row_vector(a)?.mul(&b)?.into_data())),
+        (expression::BinopInfix::Div, Value::Matrix(_), Value::Matrix(_)) => {
+            Err(CalculatorErr::interp_err("Cannot divide by a matrix; multiply by its inverse instead."))
+        },
+        (expression::BinopInfix::Exp, Value::Matrix(a), Value::Number(b)) => Ok(Value::Matrix(a.pow(b)?)),
+        (expression::BinopInfix::Exp, Value::Matrix(_), _) | (expression::BinopInfix::Exp, _, Value::Matrix(_)) => {
+            Err(CalculatorErr::interp_err("Matrices can only be raised to a number."))
+        },
+        (_, first, second) => {
+            Value::broadcast(&[first, second], |a| {
+                Ok(match operator {
+                    expression::BinopInfix::Exp => a[0].powf(a[1]),
+                    expression::BinopInfix::Mult => a[0] * a[1],
+                    expression::BinopInfix::Div => a[0] / a[1],
+                    expression::BinopInfix::Rem => a[0] % a[1],
+                    expression::BinopInfix::Add => a[0] + a[1],
+                    expression::BinopInfix::Sub => a[0] - a[1],
+                })
+            })
+        }
+    }
+}
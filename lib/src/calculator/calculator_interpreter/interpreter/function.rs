@@ -1,9 +1,12 @@
 pub mod function_impl;
+pub mod function_distribution;
+pub mod function_matrix;
 pub mod function_lazy_static;
 
 use std::fmt::Display;
 
-use crate::calculator::CalculatorErr;
+use crate::calculator::{CalculatorErr, calculator_parser::expression::ExprPrime};
+use super::{Interpreter, random_source::RandomSource, value::Value};
 
 pub type Func0 = fn () -> Result<f64, CalculatorErr>;
 pub type Func1 = fn (f64) -> Result<f64, CalculatorErr>;
@@ -16,6 +19,15 @@ pub type Func7 = fn (f64, f64, f64, f64, f64, f64, f64) -> Result<f64, Calculato
 pub type Func8 = fn (f64, f64, f64, f64, f64, f64, f64, f64) -> Result<f64, CalculatorErr>;
 pub type Func9 = fn (f64, f64, f64, f64, f64, f64, f64, f64, f64) -> Result<f64, CalculatorErr>;
 pub type FuncVar = fn (Vec<f64>) -> Result<f64, CalculatorErr>;
+pub type FuncRandom0 = fn (&mut RandomSource) -> Result<f64, CalculatorErr>;
+pub type FuncRandom1 = fn (&mut RandomSource, f64) -> Result<f64, CalculatorErr>;
+pub type FuncRandom2 = fn (&mut RandomSource, f64, f64) -> Result<f64, CalculatorErr>;
+pub type FuncRandomVar = fn (&mut RandomSource, Vec<f64>) -> Result<f64, CalculatorErr>;
+pub type FuncVarValue = fn (Vec<f64>) -> Result<Value, CalculatorErr>;
+pub type FuncRandomVarValue = fn (&mut RandomSource, Vec<f64>) -> Result<Value, CalculatorErr>;
+pub type FuncValue1 = fn (Value) -> Result<Value, CalculatorErr>;
+pub type FuncValue2 = fn (Value, Value) -> Result<Value, CalculatorErr>;
+pub type FuncExpression = fn (&Interpreter, &[ExprPrime]) -> Result<Value, CalculatorErr>;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum FunctionArgs {
@@ -30,18 +42,207 @@ pub enum FunctionArgs {
     Eight(Func8),
     Nine(Func9),
     Variable(FuncVar),
+    RandomNone(FuncRandom0),
+    RandomOne(FuncRandom1),
+    RandomTwo(FuncRandom2),
+    RandomVariable(FuncRandomVar),
+    VariableValue(FuncVarValue),
+    RandomVariableValue(FuncRandomVarValue),
+    ValueOne(FuncValue1),
+    ValueTwo(FuncValue2),
+    /**
+     * A function passed its arguments unevaluated, to evaluate as it needs, i.e. once for each value of a variable
+     */
+    Expression(FuncExpression),
+}
+
+/**
+ * Whether a function's arguments or result are an angle, and so depend on the angle mode
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum AngleUse {
+    #[default] None,
+    Arguments,
+    Result
+}
+
+/**
+ * The kinds of functions, in the order they are listed
+ */
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub enum FunctionCategory {
+    Arithmetic,
+    Lists,
+    Rounding,
+    Exponential,
+    Trigonometric,
+    Hyperbolic,
+    Random,
+    Statistics,
+    Matrices,
+    Logic,
+    Bitwise,
+    Constants,
+    #[default] Other
+}
+
+impl FunctionCategory {
+    pub fn all() -> [Self; 13] {
+        [
+            Self::Arithmetic,
+            Self::Lists,
+            Self::Rounding,
+            Self::Exponential,
+            Self::Trigonometric,
+            Self::Hyperbolic,
+            Self::Random,
+            Self::Statistics,
+            Self::Matrices,
+            Self::Logic,
+            Self::Bitwise,
+            Self::Constants,
+            Self::Other
+        ]
+    }
+}
+
+impl Display for FunctionCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Arithmetic => "Arithmetic",
+            Self::Lists => "Lists",
+            Self::Rounding => "Rounding",
+            Self::Exponential => "Powers and logarithms",
+            Self::Trigonometric => "Trigonometric",
+            Self::Hyperbolic => "Hyperbolic",
+            Self::Random => "Random",
+            Self::Statistics => "Statistics",
+            Self::Matrices => "Matrices",
+            Self::Logic => "Logic",
+            Self::Bitwise => "Bitwise",
+            Self::Constants => "Constants",
+            Self::Other => "Other"
+        })
+    }
+}
+
+/**
+ * What a built-in function does and how to call it, for help and documentation
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct FunctionInfo {
+    pub category: FunctionCategory,
+    /**
+     * The names of the parameters, with '...' before a parameter that takes any number of arguments
+     */
+    pub params: &'static [&'static str],
+    pub description: &'static str,
+    /**
+     * An expression calling the function
+     */
+    pub example: &'static str,
+    /**
+     * The arguments the function is defined for, if it isn't defined for every number
+     */
+    pub domain: Option<&'static str>
+}
+
+impl FunctionInfo {
+    pub fn new(category: FunctionCategory, params: &'static [&'static str], description: &'static str, example: &'static str) -> Self {
+        Self {
+            category,
+            params,
+            description,
+            example,
+            domain: None
+        }
+    }
+
+    pub fn with_domain(self, domain: &'static str) -> Self {
+        Self {
+            domain: Some(domain),
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Function {
-    pub args: FunctionArgs
+    pub args: FunctionArgs,
+    pub angle: AngleUse,
+    pub info: FunctionInfo
 }
 
 impl Function {
     pub fn new(args: FunctionArgs) -> Self {
         Self {
-            args
+            args,
+            angle: AngleUse::None,
+            info: FunctionInfo::default()
+        }
+    }
+
+    pub fn with_angle(self, angle: AngleUse) -> Self {
+        Self {
+            angle,
+            ..self
+        }
+    }
+
+    pub fn with_info(self, info: FunctionInfo) -> Self {
+        Self {
+            info,
+            ..self
+        }
+    }
+
+    /**
+     * Whether the name, description or category contain the query, ignoring case
+     */
+    pub fn matches(&self, name: &str, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+
+        [name, self.info.description, self.info.category.to_string().as_str()].iter()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+/**
+ * The function's parameters, named if it has names for them, i.e. '(x, mu, sigma)'
+ */
+impl Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.info.params.is_empty() {
+            self.args.fmt(f)
         }
+        else {
+            f.write_fmt(format_args!("({})", self.info.params.join(", ")))
+        }
+    }
+}
+
+/**
+ * A function defined by the user, whose body is evaluated with its parameters bound as variables
+ */
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserFunction {
+    pub params: Vec<String>,
+    pub body: ExprPrime
+}
+
+impl UserFunction {
+    pub fn new(params: Vec<String>, body: ExprPrime) -> Self {
+        Self {
+            params,
+            body
+        }
+    }
+}
+
+impl Display for UserFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("({}) = {}", self.params.join(", "), self.body))
     }
 }
 
@@ -54,9 +255,9 @@ impl Default for Function {
 impl Display for FunctionArgs {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let args = match self {
-            FunctionArgs::None(_) => "",
-            FunctionArgs::One(_) => "a",
-            FunctionArgs::Two(_) => "a, b",
+            FunctionArgs::None(_) | FunctionArgs::RandomNone(_) => "",
+            FunctionArgs::One(_) | FunctionArgs::RandomOne(_) | FunctionArgs::ValueOne(_) => "a",
+            FunctionArgs::Two(_) | FunctionArgs::RandomTwo(_) | FunctionArgs::ValueTwo(_) => "a, b",
             FunctionArgs::Three(_) => "a, b, c",
             FunctionArgs::Four(_) => "a, b, c, d",
             FunctionArgs::Five(_) => "a, b, c, d, e",
@@ -64,7 +265,8 @@ impl Display for FunctionArgs {
             FunctionArgs::Seven(_) => "a, b, c, d, e, f, g",
             FunctionArgs::Eight(_) => "a, b, c, d, e, f, g, h",
             FunctionArgs::Nine(_) => "a, b, c, d, e, f, g, h, i",
-            FunctionArgs::Variable(_) => "...n",
+            FunctionArgs::Variable(_) | FunctionArgs::RandomVariable(_) 
+                | FunctionArgs::VariableValue(_) | FunctionArgs::RandomVariableValue(_) | FunctionArgs::Expression(_) => "...n",
         };
 
         f.write_fmt(format_args!("({})", args))
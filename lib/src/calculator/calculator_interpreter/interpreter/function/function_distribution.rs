@@ -0,0 +1,449 @@
+use crate::calculator::CalculatorErr;
+
+/**
+ * Maximum number of iterations for the series/continued fraction expansions below
+ */
+const MAX_ITERATIONS: usize = 500;
+
+/**
+ * Relative precision at which the series/continued fraction expansions below stop
+ */
+const PRECISION: f64 = 1e-15;
+
+/**
+ * Smallest representable value used to avoid division by zero in Lentz's method
+ */
+const TINY: f64 = 1e-300;
+
+/**
+ * Lanczos approximation coefficients (g = 7, n = 9)
+ */
+const LANCZOS_G: f64 = 7_f64;
+const LANCZOS_COEFFICIENTS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7
+];
+
+/**
+ * The natural log of the gamma function, for positive x
+ */
+pub fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5_f64 {
+        //Reflection formula
+        (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1_f64 - x)
+    }
+    else {
+        let x = x - 1_f64;
+        let t = x + LANCZOS_G + 0.5_f64;
+
+        let sum = LANCZOS_COEFFICIENTS.iter()
+            .enumerate()
+            .skip(1)
+            .fold(LANCZOS_COEFFICIENTS[0], |agg, (i, c)| agg + c / (x + i as f64));
+
+        0.5_f64 * (2_f64 * std::f64::consts::PI).ln() + (x + 0.5_f64) * t.ln() - t + sum.ln()
+    }
+}
+
+/**
+ * The natural log of n choose k
+ */
+fn ln_choose(n: f64, k: f64) -> f64 {
+    ln_gamma(n + 1_f64) - ln_gamma(k + 1_f64) - ln_gamma(n - k + 1_f64)
+}
+
+/**
+ * The regularized lower incomplete gamma function P(a, x)
+ */
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if a <= 0_f64 || x < 0_f64 {
+        f64::NAN
+    }
+    else if x == 0_f64 {
+        0_f64
+    }
+    else if x < a + 1_f64 {
+        gamma_p_series(a, x)
+    }
+    else {
+        1_f64 - gamma_q_continued_fraction(a, x)
+    }
+}
+
+/**
+ * The regularized upper incomplete gamma function Q(a, x)
+ */
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if a <= 0_f64 || x < 0_f64 {
+        f64::NAN
+    }
+    else if x == 0_f64 {
+        1_f64
+    }
+    else if x < a + 1_f64 {
+        1_f64 - gamma_p_series(a, x)
+    }
+    else {
+        gamma_q_continued_fraction(a, x)
+    }
+}
+
+fn gamma_p_series(a: f64, x: f64) -> f64 {
+    let mut denominator = a;
+    let mut term = 1_f64 / a;
+    let mut sum = term;
+
+    for _ in 0..MAX_ITERATIONS {
+        denominator += 1_f64;
+        term *= x / denominator;
+        sum += term;
+
+        if term.abs() < sum.abs() * PRECISION {
+            break;
+        }
+    }
+
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+fn gamma_q_continued_fraction(a: f64, x: f64) -> f64 {
+    //Modified Lentz's method
+    let mut b = x + 1_f64 - a;
+    let mut c = 1_f64 / TINY;
+    let mut d = 1_f64 / b;
+    let mut h = d;
+
+    for i in 1..MAX_ITERATIONS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2_f64;
+
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+
+        d = 1_f64 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1_f64).abs() < PRECISION {
+            break;
+        }
+    }
+
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+/**
+ * The regularized incomplete beta function I_x(a, b)
+ */
+pub fn beta_i(a: f64, b: f64, x: f64) -> f64 {
+    if a <= 0_f64 || b <= 0_f64 || !(0_f64..=1_f64).contains(&x) {
+        f64::NAN
+    }
+    else if x == 0_f64 || x == 1_f64 {
+        x
+    }
+    else {
+        let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1_f64 - x).ln()).exp();
+
+        //Use the symmetry relation where the continued fraction converges faster
+        if x < (a + 1_f64) / (a + b + 2_f64) {
+            front * beta_continued_fraction(a, b, x) / a
+        }
+        else {
+            1_f64 - front * beta_continued_fraction(b, a, 1_f64 - x) / b
+        }
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    //Modified Lentz's method
+    let mut c = 1_f64;
+    let mut d = 1_f64 - (a + b) * x / (a + 1_f64);
+
+    if d.abs() < TINY {
+        d = TINY;
+    }
+
+    d = 1_f64 / d;
+    let mut h = d;
+
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2_f64 * m;
+
+        //Even step
+        let an = m * (b - m) * x / ((a + m2 - 1_f64) * (a + m2));
+
+        d = 1_f64 + an * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+
+        c = 1_f64 + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+
+        d = 1_f64 / d;
+        h *= d * c;
+
+        //Odd step
+        let an = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1_f64));
+
+        d = 1_f64 + an * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+
+        c = 1_f64 + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+
+        d = 1_f64 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1_f64).abs() < PRECISION {
+            break;
+        }
+    }
+
+    h
+}
+
+/**
+ * The error function
+ */
+pub fn erf(x: f64) -> f64 {
+    if x.is_nan() {
+        f64::NAN
+    }
+    else if x < 0_f64 {
+        -gamma_p(0.5_f64, x * x)
+    }
+    else {
+        gamma_p(0.5_f64, x * x)
+    }
+}
+
+/**
+ * The complementary error function
+ */
+pub fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        f64::NAN
+    }
+    else if x < 0_f64 {
+        1_f64 + gamma_p(0.5_f64, x * x)
+    }
+    else {
+        gamma_q(0.5_f64, x * x)
+    }
+}
+
+/**
+ * Whether the given number is a non-negative integer
+ */
+fn is_count(n: f64) -> bool {
+    n >= 0_f64 && n.fract() == 0_f64
+}
+
+/**
+ * Probability density at x of the normal distribution with mean mu and standard deviation sigma
+ */
+pub fn norm_pdf(x: f64, mu: f64, sigma: f64) -> Result<f64, CalculatorErr> {
+    if sigma <= 0_f64 {
+        return Ok(f64::NAN);
+    }
+
+    let z = (x - mu) / sigma;
+
+    Ok((-0.5_f64 * z * z).exp() / (sigma * (2_f64 * std::f64::consts::PI).sqrt()))
+}
+
+/**
+ * Cumulative probability at x of the normal distribution with mean mu and standard deviation sigma
+ */
+pub fn norm_cdf(x: f64, mu: f64, sigma: f64) -> Result<f64, CalculatorErr> {
+    if sigma <= 0_f64 {
+        return Ok(f64::NAN);
+    }
+
+    Ok(0.5_f64 * erfc(-(x - mu) / (sigma * std::f64::consts::SQRT_2)))
+}
+
+/**
+ * The value x such that the cumulative probability at x of the normal distribution
+ * with mean mu and standard deviation sigma is p
+ */
+pub fn norm_inv(p: f64, mu: f64, sigma: f64) -> Result<f64, CalculatorErr> {
+    if sigma <= 0_f64 || !(0_f64..=1_f64).contains(&p) {
+        return Ok(f64::NAN);
+    }
+    else if p == 0_f64 {
+        return Ok(f64::NEG_INFINITY);
+    }
+    else if p == 1_f64 {
+        return Ok(f64::INFINITY);
+    }
+
+    //Acklam's rational approximation of the standard normal quantile
+    const A: [f64; 6] = [-3.969_683_028_665_376e1, 2.209_460_984_245_205e2, -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2, -3.066_479_806_614_716e1, 2.506_628_277_459_239];
+    const B: [f64; 5] = [-5.447_609_879_822_406e1, 1.615_858_368_580_409e2, -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1, -1.328_068_155_288_572e1];
+    const C: [f64; 6] = [-7.784_894_002_430_293e-3, -3.223_964_580_411_365e-1, -2.400_758_277_161_838,
+        -2.549_732_539_343_734, 4.374_664_141_464_968, 2.938_163_982_698_783];
+    const D: [f64; 4] = [7.784_695_709_041_462e-3, 3.224_671_290_700_398e-1, 2.445_134_137_142_996,
+        3.754_408_661_907_416];
+
+    const P_LOW: f64 = 0.024_25;
+    const P_HIGH: f64 = 1_f64 - P_LOW;
+
+    let mut z = if p < P_LOW {
+        let q = (-2_f64 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1_f64)
+    }
+    else if p <= P_HIGH {
+        let q = p - 0.5_f64;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1_f64)
+    }
+    else {
+        let q = (-2_f64 * (1_f64 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1_f64)
+    };
+
+    //Refine using one step of Halley's method
+    let e = 0.5_f64 * erfc(-z / std::f64::consts::SQRT_2) - p;
+    let u = e * (2_f64 * std::f64::consts::PI).sqrt() * (z * z / 2_f64).exp();
+    z -= u / (1_f64 + z * u / 2_f64);
+
+    Ok(mu + sigma * z)
+}
+
+/**
+ * Probability of exactly k successes in n trials, each with probability of success p
+ */
+pub fn binom_pdf(k: f64, n: f64, p: f64) -> Result<f64, CalculatorErr> {
+    if !is_count(n) || !(0_f64..=1_f64).contains(&p) {
+        return Ok(f64::NAN);
+    }
+    else if !is_count(k) || k > n {
+        return Ok(0_f64);
+    }
+    else if p == 0_f64 {
+        return Ok(if k == 0_f64 { 1_f64 } else { 0_f64 });
+    }
+    else if p == 1_f64 {
+        return Ok(if k == n { 1_f64 } else { 0_f64 });
+    }
+
+    Ok((ln_choose(n, k) + k * p.ln() + (n - k) * (1_f64 - p).ln()).exp())
+}
+
+/**
+ * Probability of at most k successes in n trials, each with probability of success p
+ */
+pub fn binom_cdf(k: f64, n: f64, p: f64) -> Result<f64, CalculatorErr> {
+    if !is_count(n) || !(0_f64..=1_f64).contains(&p) {
+        return Ok(f64::NAN);
+    }
+
+    let k = k.floor();
+
+    if k < 0_f64 {
+        Ok(0_f64)
+    }
+    else if k >= n {
+        Ok(1_f64)
+    }
+    else {
+        //P(X <= k) = I_{1 - p}(n - k, k + 1)
+        Ok(beta_i(n - k, k + 1_f64, 1_f64 - p))
+    }
+}
+
+/**
+ * Probability of exactly k events in a poisson distribution with rate lambda
+ */
+pub fn poisson_pdf(k: f64, lambda: f64) -> Result<f64, CalculatorErr> {
+    if lambda <= 0_f64 {
+        return Ok(f64::NAN);
+    }
+    else if !is_count(k) {
+        return Ok(0_f64);
+    }
+
+    Ok((k * lambda.ln() - lambda - ln_gamma(k + 1_f64)).exp())
+}
+
+/**
+ * Probability of at most k events in a poisson distribution with rate lambda
+ */
+pub fn poisson_cdf(k: f64, lambda: f64) -> Result<f64, CalculatorErr> {
+    if lambda <= 0_f64 {
+        return Ok(f64::NAN);
+    }
+
+    let k = k.floor();
+
+    if k < 0_f64 {
+        Ok(0_f64)
+    }
+    else {
+        //P(X <= k) = Q(k + 1, lambda)
+        Ok(gamma_q(k + 1_f64, lambda))
+    }
+}
+
+/**
+ * Cumulative probability at x of student's t-distribution with v degrees of freedom
+ */
+pub fn t_cdf(x: f64, v: f64) -> Result<f64, CalculatorErr> {
+    if v <= 0_f64 || x.is_nan() {
+        return Ok(f64::NAN);
+    }
+
+    let tail = 0.5_f64 * beta_i(v / 2_f64, 0.5_f64, v / (v + x * x));
+
+    if x > 0_f64 {
+        Ok(1_f64 - tail)
+    }
+    else {
+        Ok(tail)
+    }
+}
+
+/**
+ * Cumulative probability at x of the chi-squared distribution with k degrees of freedom
+ */
+pub fn chisq_cdf(x: f64, k: f64) -> Result<f64, CalculatorErr> {
+    if k <= 0_f64 || x.is_nan() {
+        Ok(f64::NAN)
+    }
+    else if x <= 0_f64 {
+        Ok(0_f64)
+    }
+    else {
+        Ok(gamma_p(k / 2_f64, x / 2_f64))
+    }
+}
@@ -1,5 +1,6 @@
 use crate::calculator::CalculatorErr;
-use rand::Rng;
+use super::super::{random_source::{RandomSource, Roll}, value::{Value, MAX_LIST_LEN}};
+use rand::{Rng, seq::SliceRandom};
 
 pub fn factorial(n: f64) -> Result<f64, CalculatorErr> {
     if n == 0_f64 {
@@ -49,25 +50,113 @@ pub fn modulo(a: f64, b: f64) -> Result<f64, CalculatorErr> {
     }
 }
 
-pub fn random<T>() -> Result<f64, CalculatorErr>
+pub fn random<T>(source: &mut RandomSource) -> Result<f64, CalculatorErr>
     where T : Into<f64>, 
     rand::distributions::Standard: rand::distributions::Distribution<T>
 {
-    Ok(rand::thread_rng().gen::<T>().into())
+    Ok(source.rng().gen::<T>().into())
 }
 
-pub fn random_range<T>(range: std::ops::Range<T>) -> Result<f64, CalculatorErr>
+pub fn random_range<T>(source: &mut RandomSource, range: std::ops::Range<T>) -> Result<f64, CalculatorErr>
     where T : Into<f64> + std::cmp::PartialOrd + rand::distributions::uniform::SampleUniform, 
     rand::distributions::Standard: rand::distributions::Distribution<T>
 {
-    Ok(rand::thread_rng().gen_range(range).into())
+    Ok(source.rng().gen_range(range).into())
 }
 
-pub fn random_range_inc<T>(range: std::ops::RangeInclusive<T>) -> Result<f64, CalculatorErr>
+pub fn random_range_inc<T>(source: &mut RandomSource, range: std::ops::RangeInclusive<T>) -> Result<f64, CalculatorErr>
     where T : Into<f64> + std::cmp::PartialOrd + rand::distributions::uniform::SampleUniform, 
     rand::distributions::Standard: rand::distributions::Distribution<T>
 {
-    Ok(rand::thread_rng().gen_range(range).into())
+    Ok(source.rng().gen_range(range).into())
+}
+
+pub fn random_distribution<D, E>(source: &mut RandomSource, distribution: Result<D, E>) -> Result<f64, CalculatorErr>
+    where D : rand::distributions::Distribution<f64>,
+    E : std::fmt::Display
+{
+    match distribution {
+        Ok(distribution) => Ok(source.rng().sample(distribution)),
+        Err(e) => Err(CalculatorErr::interp_err(format!("Invalid distribution parameters: {e}.").as_str()))
+    }
+}
+
+/**
+ * Fail unless every parameter of a distribution is finite; some distributions never finish sampling with an infinite parameter
+ */
+pub fn require_finite(params: &[f64]) -> Result<(), CalculatorErr> {
+    if params.iter().all(|param| param.is_finite()) {
+        Ok(())
+    }
+    else {
+        Err(CalculatorErr::interp_err("Invalid distribution parameters: parameters must be finite."))
+    }
+}
+
+pub fn seed(source: &mut RandomSource, seed: f64) -> Result<f64, CalculatorErr> {
+    if seed < 0_f64 || seed.fract() != 0_f64 || seed >= u64::MAX as f64 {
+        Err(CalculatorErr::interp_err("Seed must be a non-negative integer."))
+    }
+    else {
+        source.reseed(seed as u64);
+        Ok(seed)
+    }
+}
+
+pub fn choose(source: &mut RandomSource, values: Vec<f64>) -> Result<f64, CalculatorErr> {
+    if values.is_empty() {
+        return Err(CalculatorErr::interp_err("Cannot choose from an empty set of values."));
+    }
+
+    let index = source.rng().gen_range(0..values.len());
+    let chosen = values[index];
+
+    let kept = (0..values.len()).map(|i| i == index).collect();
+    source.record(Roll::new("CHOOSE".to_string(), values, kept));
+
+    Ok(chosen)
+}
+
+pub fn shuffle(source: &mut RandomSource, mut values: Vec<f64>) -> Result<Value, CalculatorErr> {
+    if values.is_empty() {
+        return Err(CalculatorErr::interp_err("Cannot shuffle an empty set of values."));
+    }
+
+    values.shuffle(source.rng());
+
+    source.record(Roll::all_kept("SHUFFLE".to_string(), values.clone()));
+
+    Ok(Value::List(values))
+}
+
+pub fn range(start: f64, end: f64, step: f64) -> Result<Value, CalculatorErr> {
+    if !start.is_finite() || !end.is_finite() || !step.is_finite() {
+        return Err(CalculatorErr::interp_err("Range bounds and step must be finite."));
+    }
+    else if step == 0_f64 {
+        return Err(CalculatorErr::interp_err("Range step cannot be zero."));
+    }
+
+    //Allow for floating point error, so that steps such as 0.1 still reach the end of the range
+    let steps = ((end - start) / step + 1e-9).floor();
+
+    if steps < 0_f64 {
+        Ok(Value::List(Vec::new()))
+    }
+    else if steps >= MAX_LIST_LEN as f64 {
+        Err(CalculatorErr::interp_err(format!("Range cannot have more than {MAX_LIST_LEN} elements.").as_str()))
+    }
+    else {
+        Ok(Value::List((0..=steps as usize).map(|i| start + i as f64 * step).collect()))
+    }
+}
+
+pub fn range_all(values: Vec<f64>) -> Result<Value, CalculatorErr> {
+    match values[..] {
+        [start, end] => range(start, end, if end >= start { 1_f64 } else { -1_f64 }),
+        [start, end, step] => range(start, end, step),
+        _ => Err(CalculatorErr::interp_err(format!("Function 'RANGE' expected 2 or 3 arguments; got {}.", values.len()).as_str()))
+    }
 }
 
 pub fn add_all(values: Vec<f64>) -> Result<f64, CalculatorErr> {
@@ -110,6 +199,15 @@ pub fn rem_all(values: Vec<f64>) -> Result<f64, CalculatorErr> {
     Ok(maybe_value.unwrap_or(0_f64))
 }
 
+pub fn mean_all(values: Vec<f64>) -> Result<f64, CalculatorErr> {
+    if values.is_empty() {
+        Ok(f64::NAN)
+    }
+    else {
+        Ok(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
 pub fn max_all(values: Vec<f64>) -> Result<f64, CalculatorErr> {
     let maybe_value: Option<f64> = values.iter()
     .copied()
@@ -124,4 +222,56 @@ pub fn min_all(values: Vec<f64>) -> Result<f64, CalculatorErr> {
     .reduce(f64::min);
 
     Ok(maybe_value.unwrap_or(0_f64))
-}
\ No newline at end of file
+}
+
+/**
+ * The integer a bitwise operation is applied to. Bitwise operations are only defined for integers which can be represented exactly.
+ */
+fn bitwise_operand(n: f64) -> Result<i64, CalculatorErr> {
+    const MAX_EXACT: f64 = 9_007_199_254_740_992_f64;
+
+    if n.fract() != 0_f64 || !n.is_finite() || n.abs() > MAX_EXACT {
+        Err(CalculatorErr::interp_err(format!("Cannot apply bitwise operation to {n}; expected an integer.").as_str()))
+    }
+    else {
+        Ok(n as i64)
+    }
+}
+
+pub fn bitwise_and(a: f64, b: f64) -> Result<f64, CalculatorErr> {
+    Ok((bitwise_operand(a)? & bitwise_operand(b)?) as f64)
+}
+
+pub fn bitwise_or(a: f64, b: f64) -> Result<f64, CalculatorErr> {
+    Ok((bitwise_operand(a)? | bitwise_operand(b)?) as f64)
+}
+
+pub fn bitwise_xor(a: f64, b: f64) -> Result<f64, CalculatorErr> {
+    Ok((bitwise_operand(a)? ^ bitwise_operand(b)?) as f64)
+}
+
+pub fn bitwise_not(a: f64) -> Result<f64, CalculatorErr> {
+    Ok(!bitwise_operand(a)? as f64)
+}
+
+/**
+ * Shift the bits of a left by b, or right if b is negative. Bits shifted past the most significant bit are lost.
+ */
+pub fn shift_left(a: f64, b: f64) -> Result<f64, CalculatorErr> {
+    let a = bitwise_operand(a)?;
+    let b = bitwise_operand(b)?;
+
+    let shifted = match b {
+        //Shifting right by all the bits leaves only the sign
+        b if b <= -64 => a.signum().min(0),
+        b if b < 0 => a >> -b,
+        b if b < 64 => a << b,
+        _ => 0
+    };
+
+    Ok(shifted as f64)
+}
+
+pub fn shift_right(a: f64, b: f64) -> Result<f64, CalculatorErr> {
+    shift_left(a, -b)
+}
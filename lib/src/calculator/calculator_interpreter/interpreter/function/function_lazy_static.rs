@@ -1,63 +1,112 @@
-use super::{*, super::function::function_impl::*};
+use super::{*, super::function::{function_impl::*, function_distribution::*, function_matrix::*}, super::random_source::RandomSource};
 
 lazy_static! {
-    pub(in crate::calculator::calculator_interpreter) static ref ADD: Function = Function::new(FunctionArgs::Variable(add_all));
-    pub(in crate::calculator::calculator_interpreter) static ref SUB: Function = Function::new(FunctionArgs::Variable(sub_all));
-    pub(in crate::calculator::calculator_interpreter) static ref MULT: Function = Function::new(FunctionArgs::Variable(mult_all));
-    pub(in crate::calculator::calculator_interpreter) static ref DIV: Function = Function::new(FunctionArgs::Variable(div_all));
-    pub(in crate::calculator::calculator_interpreter) static ref REM: Function = Function::new(FunctionArgs::Variable(rem_all));
-
-    pub(in crate::calculator::calculator_interpreter) static ref MAX: Function = Function::new(FunctionArgs::Variable(max_all));
-    pub(in crate::calculator::calculator_interpreter) static ref MIN: Function = Function::new(FunctionArgs::Variable(min_all));
-
-    pub(in crate::calculator::calculator_interpreter) static ref MOD: Function = Function::new(FunctionArgs::Two(modulo));
-
-    pub(in crate::calculator::calculator_interpreter) static ref NEG: Function = Function::new(FunctionArgs::One(|n: f64| Ok(-n)));
-    pub(in crate::calculator::calculator_interpreter) static ref FAC: Function = Function::new(FunctionArgs::One(factorial));
+    pub(in crate::calculator::calculator_interpreter) static ref ADD: Function = Function::new(FunctionArgs::Variable(add_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["...a"], "a + b + c + ...", "ADD(1, 2, 3)"));
+    pub(in crate::calculator::calculator_interpreter) static ref SUB: Function = Function::new(FunctionArgs::Variable(sub_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["...a"], "a - b - c - ...", "SUB(10, 2, 3)"));
+    pub(in crate::calculator::calculator_interpreter) static ref MULT: Function = Function::new(FunctionArgs::Variable(mult_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["...a"], "a * b * c * ...", "MULT(2, 3, 4)"));
+    pub(in crate::calculator::calculator_interpreter) static ref DIV: Function = Function::new(FunctionArgs::Variable(div_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["...a"], "a / b / c / ...", "DIV(100, 5, 2)"));
+    pub(in crate::calculator::calculator_interpreter) static ref REM: Function = Function::new(FunctionArgs::Variable(rem_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["...a"], "a % b % c % ...", "REM(17, 5)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref MAX: Function = Function::new(FunctionArgs::Variable(max_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Lists, &["...a"], "The greatest value in a, b, ...", "MAX(3, 9, 4)"));
+    pub(in crate::calculator::calculator_interpreter) static ref MIN: Function = Function::new(FunctionArgs::Variable(min_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Lists, &["...a"], "The lowest value in a, b, ...", "MIN(3, 9, 4)"));
+    pub(in crate::calculator::calculator_interpreter) static ref SUM: Function = Function::new(FunctionArgs::Variable(add_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Lists, &["...a"], "a + b + c + ...", "SUM([1, 2, 3, 4])"));
+    pub(in crate::calculator::calculator_interpreter) static ref MEAN: Function = Function::new(FunctionArgs::Variable(mean_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Lists, &["...a"], "The arithmetic mean of a, b, ...", "MEAN(2, 4, 9)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref RANGE: Function = Function::new(FunctionArgs::VariableValue(range_all))
+        .with_info(FunctionInfo::new(FunctionCategory::Lists, &["a", "b", "c"], "Returns the list of numbers from a to b inclusive, counting in steps of c. If c is omitted, counts in steps of 1 (or -1 if b < a).", "RANGE(0, 10, 2)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref MOD: Function = Function::new(FunctionArgs::Two(modulo))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["a", "b"], "The euclidean modulo function a mod b, returning the remainder of euclidean division of a by b.", "MOD(-7, 3)").with_domain("b must not be 0, or the result is NaN."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref NEG: Function = Function::new(FunctionArgs::One(|n: f64| Ok(-n)))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["a"], "-a", "NEG(5)"));
+    pub(in crate::calculator::calculator_interpreter) static ref FAC: Function = Function::new(FunctionArgs::One(factorial))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["a"], "a!; the product of the integers from 1 to a.", "FAC(5)").with_domain("a must be a non-negative integer."));
     
-    pub(in crate::calculator::calculator_interpreter) static ref ABS: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::abs(n))));
+    pub(in crate::calculator::calculator_interpreter) static ref ABS: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::abs(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["a"], "The absolute value of a.", "ABS(-3)"));
     
-    pub(in crate::calculator::calculator_interpreter) static ref CEIL: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::ceil(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref FLOOR: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::floor(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ROUND: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::round(n))));
+    pub(in crate::calculator::calculator_interpreter) static ref CEIL: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::ceil(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Rounding, &["a"], "Round a up to the nearest integer.", "CEIL(2.1)"));
+    pub(in crate::calculator::calculator_interpreter) static ref FLOOR: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::floor(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Rounding, &["a"], "Round a down to the nearest integer.", "FLOOR(2.9)"));
+    pub(in crate::calculator::calculator_interpreter) static ref ROUND: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::round(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Rounding, &["a"], "Round a to the nearest integer.", "ROUND(2.5)"));
     
-    pub(in crate::calculator::calculator_interpreter) static ref FRACT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::fract(n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref SQRT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::sqrt(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref EXP: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::exp(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref EXP2: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::exp2(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref POW: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| Ok(f64::powf(a, b))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref SIN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::sin(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref COS: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::cos(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref TAN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::tan(n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref ASIN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::asin(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ACOS: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::acos(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ATAN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::atan(n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref CSC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(1_f64 / f64::sin(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref SEC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(1_f64 / f64::cos(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref COT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(1_f64 / f64::tan(n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref ACSC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::asin(1_f64 / n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ASEC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::acos(1_f64 / n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ACOT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::atan(1_f64 / n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref SINH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::sinh(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref COSH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::cosh(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref TANH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::tanh(n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref ASINH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::asinh(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ACOSH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::acosh(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref ATANH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::atanh(n))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref LOG: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::log10(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref LOG2: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::log2(n))));
-    pub(in crate::calculator::calculator_interpreter) static ref LN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(n.log(std::f64::consts::E))));
-    pub(in crate::calculator::calculator_interpreter) static ref LOGB: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| Ok(f64::log(a, b))));
-
-    pub(in crate::calculator::calculator_interpreter) static ref SIGN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::signum(n))));
+    pub(in crate::calculator::calculator_interpreter) static ref FRACT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::fract(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Rounding, &["a"], "Extract the fractional component of floating point number a.", "FRACT(3.75)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref SQRT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::sqrt(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a"], "The square root of a.", "SQRT(16)").with_domain("a must be non-negative, or the result is NaN."));
+    pub(in crate::calculator::calculator_interpreter) static ref EXP: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::exp(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a"], "Euler's number e raised to the ath power.", "EXP(1)"));
+    pub(in crate::calculator::calculator_interpreter) static ref EXP2: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::exp2(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a"], "2 raised to the ath power.", "EXP2(10)"));
+    pub(in crate::calculator::calculator_interpreter) static ref POW: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| Ok(f64::powf(a, b))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a", "b"], "a^b.", "POW(2, 8)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref SIN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::sin(n)))).with_angle(AngleUse::Arguments)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The sine of the angle a.", "SIN(PI / 2)"));
+    pub(in crate::calculator::calculator_interpreter) static ref COS: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::cos(n)))).with_angle(AngleUse::Arguments)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The cosine of the angle a.", "COS(PI)"));
+    pub(in crate::calculator::calculator_interpreter) static ref TAN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::tan(n)))).with_angle(AngleUse::Arguments)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The tangent of the angle a.", "TAN(PI / 4)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref ASIN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::asin(n)))).with_angle(AngleUse::Result)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The inverse sine of a, as an angle.", "ASIN(1)").with_domain("a must be in [-1, 1]."));
+    pub(in crate::calculator::calculator_interpreter) static ref ACOS: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::acos(n)))).with_angle(AngleUse::Result)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The inverse cosine of a, as an angle.", "ACOS(0)").with_domain("a must be in [-1, 1]."));
+    pub(in crate::calculator::calculator_interpreter) static ref ATAN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::atan(n)))).with_angle(AngleUse::Result)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The inverse tangent of a, as an angle.", "ATAN(1)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref CSC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(1_f64 / f64::sin(n)))).with_angle(AngleUse::Arguments)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The cosecant of the angle a.", "CSC(PI / 2)"));
+    pub(in crate::calculator::calculator_interpreter) static ref SEC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(1_f64 / f64::cos(n)))).with_angle(AngleUse::Arguments)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The secant of the angle a.", "SEC(0)"));
+    pub(in crate::calculator::calculator_interpreter) static ref COT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(1_f64 / f64::tan(n)))).with_angle(AngleUse::Arguments)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The cotangent of the angle a.", "COT(PI / 4)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref ACSC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::asin(1_f64 / n)))).with_angle(AngleUse::Result)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The inverse cosecant of a, as an angle.", "ACSC(2)").with_domain("a must not be in (-1, 1)."));
+    pub(in crate::calculator::calculator_interpreter) static ref ASEC: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::acos(1_f64 / n)))).with_angle(AngleUse::Result)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The inverse secant of a, as an angle.", "ASEC(2)").with_domain("a must not be in (-1, 1)."));
+    pub(in crate::calculator::calculator_interpreter) static ref ACOT: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::atan(1_f64 / n)))).with_angle(AngleUse::Result)
+        .with_info(FunctionInfo::new(FunctionCategory::Trigonometric, &["a"], "The inverse cotangent of a, as an angle.", "ACOT(1)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref SINH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::sinh(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Hyperbolic, &["a"], "The hyperbolic sine of a.", "SINH(1)"));
+    pub(in crate::calculator::calculator_interpreter) static ref COSH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::cosh(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Hyperbolic, &["a"], "The hyperbolic cosine of a.", "COSH(1)"));
+    pub(in crate::calculator::calculator_interpreter) static ref TANH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::tanh(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Hyperbolic, &["a"], "The hyperbolic tangent of a.", "TANH(1)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref ASINH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::asinh(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Hyperbolic, &["a"], "The inverse hyperbolic sine of a.", "ASINH(1)"));
+    pub(in crate::calculator::calculator_interpreter) static ref ACOSH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::acosh(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Hyperbolic, &["a"], "The inverse hyperbolic cosine of a.", "ACOSH(2)").with_domain("a must be at least 1."));
+    pub(in crate::calculator::calculator_interpreter) static ref ATANH: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::atanh(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Hyperbolic, &["a"], "The inverse hyperbolic tangent of a.", "ATANH(0.5)").with_domain("a must be in (-1, 1)."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref LOG: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::log10(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a"], "The log base 10 of a.", "LOG(1000)").with_domain("a must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref LOG2: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::log2(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a"], "The log base 2 of a.", "LOG2(1024)").with_domain("a must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref LN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(n.log(std::f64::consts::E))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a"], "The log base e of a, where e is Euler's number.", "LN(E)").with_domain("a must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref LOGB: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| Ok(f64::log(a, b))))
+        .with_info(FunctionInfo::new(FunctionCategory::Exponential, &["a", "b"], "The log base b of a.", "LOGB(8, 2)").with_domain("a and b must be positive, and b must not be 1."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref SIGN: Function = Function::new(FunctionArgs::One(|n: f64| Ok(f64::signum(n))))
+        .with_info(FunctionInfo::new(FunctionCategory::Arithmetic, &["a"], "Returns 1 if a is non-negative, and returns -1 otherwise.", "SIGN(-2)"));
     pub(in crate::calculator::calculator_interpreter) static ref COND: Function = Function::new(FunctionArgs::Four(|a: f64, b: f64, c: f64, d: f64| {
         Ok(if a == b {
             c
@@ -65,16 +114,117 @@ lazy_static! {
         else {
             d
         })
-    }));
+    }))
+        .with_info(FunctionInfo::new(FunctionCategory::Logic, &["a", "b", "c", "d"], "If a == b, returns c, and returns d otherwise.", "COND(1, 1, 10, 20)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref AND: Function = Function::new(FunctionArgs::Two(bitwise_and))
+        .with_info(FunctionInfo::new(FunctionCategory::Bitwise, &["a", "b"], "The bitwise and of a and b.", "AND(0b1100, 0b1010)").with_domain("a and b must be integers."));
+    pub(in crate::calculator::calculator_interpreter) static ref OR: Function = Function::new(FunctionArgs::Two(bitwise_or))
+        .with_info(FunctionInfo::new(FunctionCategory::Bitwise, &["a", "b"], "The bitwise or of a and b.", "OR(0b1100, 0b1010)").with_domain("a and b must be integers."));
+    pub(in crate::calculator::calculator_interpreter) static ref XOR: Function = Function::new(FunctionArgs::Two(bitwise_xor))
+        .with_info(FunctionInfo::new(FunctionCategory::Bitwise, &["a", "b"], "The bitwise exclusive or of a and b.", "XOR(0b1100, 0b1010)").with_domain("a and b must be integers."));
+    pub(in crate::calculator::calculator_interpreter) static ref NOT: Function = Function::new(FunctionArgs::One(bitwise_not))
+        .with_info(FunctionInfo::new(FunctionCategory::Bitwise, &["a"], "The bitwise complement of a, as a two's complement integer.", "NOT(0xFF)").with_domain("a must be an integer."));
+    pub(in crate::calculator::calculator_interpreter) static ref SHL: Function = Function::new(FunctionArgs::Two(shift_left))
+        .with_info(FunctionInfo::new(FunctionCategory::Bitwise, &["a", "b"], "Shifts the bits of a left by b places.", "SHL(0x0F, 4)").with_domain("a and b must be integers."));
+    pub(in crate::calculator::calculator_interpreter) static ref SHR: Function = Function::new(FunctionArgs::Two(shift_right))
+        .with_info(FunctionInfo::new(FunctionCategory::Bitwise, &["a", "b"], "Shifts the bits of a right by b places, keeping its sign.", "SHR(0xF0, 4)").with_domain("a and b must be integers."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref FRAND: Function = Function::new(FunctionArgs::RandomNone(random::<f64>))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &[], "Returns a random floating point number in the range [0, 1).", "FRAND()"));
+    pub(in crate::calculator::calculator_interpreter) static ref RFRAND: Function = Function::new(FunctionArgs::RandomTwo(|source: &mut RandomSource, a: f64, b: f64| random_range::<f64>(source, a..b)))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["a", "b"], "Returns a random floating point number in the range [a, b).", "RFRAND(1, 2)"));
+    pub(in crate::calculator::calculator_interpreter) static ref RFRANDI: Function = Function::new(FunctionArgs::RandomTwo(|source: &mut RandomSource, a: f64, b: f64| random_range_inc::<f64>(source, a..=b)))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["a", "b"], "Returns a random floating point number in the range [a, b].", "RFRANDI(1, 2)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref RAND: Function = Function::new(FunctionArgs::RandomNone(random::<i32>))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &[], "Returns a random 32-bit signed integer number.", "RAND()"));
+    pub(in crate::calculator::calculator_interpreter) static ref RRAND: Function = Function::new(FunctionArgs::RandomTwo(|source: &mut RandomSource, a: f64, b: f64| random_range::<i32>(source, a.ceil() as i32..b.floor() as i32)))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["a", "b"], "Returns a random integer number in the range [a, b).", "RRAND(1, 7)"));
+    pub(in crate::calculator::calculator_interpreter) static ref RRANDI: Function = Function::new(FunctionArgs::RandomTwo(|source: &mut RandomSource, a: f64, b: f64| random_range_inc::<i32>(source, a.ceil() as i32..=b.floor() as i32)))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["a", "b"], "Returns a random integer number in the range [a, b].", "RRANDI(1, 6)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref SEED: Function = Function::new(FunctionArgs::RandomOne(seed))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["a"], "Seeds the random number generator with a, restarting its sequence of random numbers. Returns a.", "SEED(42)").with_domain("a must be a non-negative integer."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref CHOOSE: Function = Function::new(FunctionArgs::RandomVariable(choose))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["...a"], "Returns one of a, b, ..., chosen at random.", "CHOOSE(1, 2, 3)"));
+    pub(in crate::calculator::calculator_interpreter) static ref SHUFFLE: Function = Function::new(FunctionArgs::RandomVariableValue(shuffle))
+        .with_info(FunctionInfo::new(FunctionCategory::Random, &["...a"], "Returns the list of a, b, ... shuffled into a random order.", "SHUFFLE(1, 2, 3)"));
+
+    pub(in crate::calculator::calculator_interpreter) static ref E: Function = Function::new(FunctionArgs::None(|| Ok(std::f64::consts::E)))
+        .with_info(FunctionInfo::new(FunctionCategory::Constants, &[], "Returns Euler's number, e. Can also be written without parentheses, as E.", "E()"));
+    pub(in crate::calculator::calculator_interpreter) static ref PI: Function = Function::new(FunctionArgs::None(|| Ok(std::f64::consts::PI)))
+        .with_info(FunctionInfo::new(FunctionCategory::Constants, &[], "Returns pi. Can also be written without parentheses, as PI.", "PI()"));
+}
 
-    pub(in crate::calculator::calculator_interpreter) static ref FRAND: Function = Function::new(FunctionArgs::None(random::<f64>));
-    pub(in crate::calculator::calculator_interpreter) static ref RFRAND: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| random_range::<f64>(a..b)));
-    pub(in crate::calculator::calculator_interpreter) static ref RFRANDI: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| random_range_inc::<f64>(a..=b)));
+lazy_static! {
+    pub(in crate::calculator::calculator_interpreter) static ref NORMPDF: Function = Function::new(FunctionArgs::Three(norm_pdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["x", "mu", "sigma"], "The probability density at x of the normal distribution with mean mu and standard deviation sigma.", "NORMPDF(0, 0, 1)").with_domain("sigma must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref NORMCDF: Function = Function::new(FunctionArgs::Three(norm_cdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["x", "mu", "sigma"], "The probability that a value of the normal distribution with mean mu and standard deviation sigma is at most x.", "NORMCDF(1.96, 0, 1)").with_domain("sigma must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref NORMINV: Function = Function::new(FunctionArgs::Three(norm_inv))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["p", "mu", "sigma"], "The inverse of NORMCDF; the value x such that NORMCDF(x, mu, sigma) = p.", "NORMINV(0.975, 0, 1)").with_domain("p must be in [0, 1], and sigma must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref BINOMPDF: Function = Function::new(FunctionArgs::Three(binom_pdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["k", "n", "p"], "The probability of exactly k successes in n trials, each with probability of success p.", "BINOMPDF(3, 10, 0.5)").with_domain("n must be a non-negative integer, and p must be in [0, 1]."));
+    pub(in crate::calculator::calculator_interpreter) static ref BINOMCDF: Function = Function::new(FunctionArgs::Three(binom_cdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["k", "n", "p"], "The probability of at most k successes in n trials, each with probability of success p.", "BINOMCDF(3, 10, 0.5)").with_domain("n must be a non-negative integer, and p must be in [0, 1]."));
+    pub(in crate::calculator::calculator_interpreter) static ref POISSONPDF: Function = Function::new(FunctionArgs::Two(poisson_pdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["k", "lambda"], "The probability of exactly k events in a poisson distribution with rate lambda.", "POISSONPDF(2, 3)").with_domain("lambda must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref POISSONCDF: Function = Function::new(FunctionArgs::Two(poisson_cdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["k", "lambda"], "The probability of at most k events in a poisson distribution with rate lambda.", "POISSONCDF(2, 3)").with_domain("lambda must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref TCDF: Function = Function::new(FunctionArgs::Two(t_cdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["x", "v"], "The probability that a value of student's t-distribution with v degrees of freedom is at most x.", "TCDF(2, 10)").with_domain("v must be positive."));
+    pub(in crate::calculator::calculator_interpreter) static ref CHISQCDF: Function = Function::new(FunctionArgs::Two(chisq_cdf))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["x", "k"], "The probability that a value of the chi-squared distribution with k degrees of freedom is at most x.", "CHISQCDF(3.84, 1)").with_domain("k must be positive."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref NORMRAND: Function = Function::new(FunctionArgs::RandomTwo(|source: &mut RandomSource, mu: f64, sigma: f64| {
+        require_finite(&[mu, sigma])?;
+
+        if sigma < 0_f64 {
+            return Err(CalculatorErr::interp_err("Invalid distribution parameters: standard deviation must be non-negative."));
+        }
 
-    pub(in crate::calculator::calculator_interpreter) static ref RAND: Function = Function::new(FunctionArgs::None(random::<i32>));
-    pub(in crate::calculator::calculator_interpreter) static ref RRAND: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| random_range::<i32>(a.ceil() as i32..b.floor() as i32)));
-    pub(in crate::calculator::calculator_interpreter) static ref RRANDI: Function = Function::new(FunctionArgs::Two(|a: f64, b: f64| random_range_inc::<i32>(a.ceil() as i32..=b.floor() as i32)));
+        random_distribution(source, rand_distr::Normal::new(mu, sigma))
+    }))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["mu", "sigma"], "Returns a random number sampled from the normal distribution with mean mu and standard deviation sigma.", "NORMRAND(0, 1)").with_domain("mu must be finite, and sigma must be non-negative and finite."));
+    pub(in crate::calculator::calculator_interpreter) static ref BINOMRAND: Function = Function::new(FunctionArgs::RandomTwo(|source: &mut RandomSource, n: f64, p: f64| {
+        //Larger numbers of trials can't be sampled
+        if n < 0_f64 || n.fract() != 0_f64 || n >= i64::MAX as f64 {
+            return Err(CalculatorErr::interp_err("Invalid distribution parameters: number of trials must be a non-negative integer less than 2^63."));
+        }
 
-    pub(in crate::calculator::calculator_interpreter) static ref E: Function = Function::new(FunctionArgs::None(|| Ok(std::f64::consts::E)));
-    pub(in crate::calculator::calculator_interpreter) static ref PI: Function = Function::new(FunctionArgs::None(|| Ok(std::f64::consts::PI)));
-}
\ No newline at end of file
+        random_distribution(source, rand_distr::Binomial::new(n as u64, p).map(|d| rand_distr::Distribution::map(d, |k| k as f64)))
+    }))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["n", "p"], "Returns a random number of successes in n trials, each with probability of success p.", "BINOMRAND(10, 0.5)").with_domain("n must be a non-negative integer less than 2^63, and p must be in [0, 1]."));
+    pub(in crate::calculator::calculator_interpreter) static ref POISSONRAND: Function = Function::new(FunctionArgs::RandomOne(|source: &mut RandomSource, lambda: f64| {
+        require_finite(&[lambda])?;
+        random_distribution(source, rand_distr::Poisson::new(lambda))
+    }))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["lambda"], "Returns a random number of events sampled from a poisson distribution with rate lambda.", "POISSONRAND(3)").with_domain("lambda must be positive and finite."));
+    pub(in crate::calculator::calculator_interpreter) static ref TRAND: Function = Function::new(FunctionArgs::RandomOne(|source: &mut RandomSource, v: f64| {
+        require_finite(&[v])?;
+        random_distribution(source, rand_distr::StudentT::new(v))
+    }))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["v"], "Returns a random number sampled from student's t-distribution with v degrees of freedom.", "TRAND(10)").with_domain("v must be positive and finite."));
+    pub(in crate::calculator::calculator_interpreter) static ref CHISQRAND: Function = Function::new(FunctionArgs::RandomOne(|source: &mut RandomSource, k: f64| {
+        require_finite(&[k])?;
+        random_distribution(source, rand_distr::ChiSquared::new(k))
+    }))
+        .with_info(FunctionInfo::new(FunctionCategory::Statistics, &["k"], "Returns a random number sampled from the chi-squared distribution with k degrees of freedom.", "CHISQRAND(2)").with_domain("k must be positive and finite."));
+
+    pub(in crate::calculator::calculator_interpreter) static ref TRANSPOSE: Function = Function::new(FunctionArgs::ValueOne(transpose))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["a"], "Returns the matrix a with its rows and columns swapped. A list becomes a matrix with a single column.", "TRANSPOSE([[1, 2], [3, 4]])"));
+    pub(in crate::calculator::calculator_interpreter) static ref TABLE: Function = Function::new(FunctionArgs::Expression(table))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["expr", "x", "start", "end", "step"], "Returns a matrix with a row for each value of the variable x from start to end, in steps of step, holding x and the value of expr for it.", "TABLE(x^2, x, 0, 4, 1)"));
+    pub(in crate::calculator::calculator_interpreter) static ref DET: Function = Function::new(FunctionArgs::ValueOne(determinant))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["a"], "Returns the determinant of the square matrix a.", "DET([[1, 2], [3, 4]])").with_domain("a must be a square matrix."));
+    pub(in crate::calculator::calculator_interpreter) static ref INV: Function = Function::new(FunctionArgs::ValueOne(inverse))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["a"], "Returns the inverse of the square matrix a.", "INV([[1, 2], [3, 4]])").with_domain("a must be a square matrix that isn't singular."));
+    pub(in crate::calculator::calculator_interpreter) static ref RANK: Function = Function::new(FunctionArgs::ValueOne(rank))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["a"], "Returns the rank of the matrix a; the number of linearly independent rows.", "RANK([[1, 2], [2, 4]])"));
+    pub(in crate::calculator::calculator_interpreter) static ref SOLVE_LINEAR: Function = Function::new(FunctionArgs::ValueTwo(solve_linear))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["a", "b"], "Returns x, the solution to the system of linear equations ax = b, where a is a square matrix, and b is a list or matrix.", "SOLVE_LINEAR([[2, 0], [0, 4]], [2, 8])").with_domain("a must be a square matrix that isn't singular, with as many rows as b."));
+    pub(in crate::calculator::calculator_interpreter) static ref EIGVALS: Function = Function::new(FunctionArgs::ValueOne(eigenvalues))
+        .with_info(FunctionInfo::new(FunctionCategory::Matrices, &["a"], "Returns a list of the eigenvalues of the square matrix a, from highest to lowest.", "EIGVALS([[2, 1], [1, 2]])").with_domain("a must be a square matrix of up to 16 rows, with real eigenvalues."));
+}
@@ -0,0 +1,74 @@
+use crate::calculator::{CalculatorErr, calculator_parser::expression::ExprPrime};
+use super::super::{Interpreter, value::{Value, matrix::Matrix}};
+
+/**
+ * Transpose a matrix; a list becomes a matrix with a single column
+ */
+pub fn transpose(value: Value) -> Result<Value, CalculatorErr> {
+    match value {
+        Value::Number(n) => Ok(Value::Number(n)),
+        Value::List(list) => Ok(Value::Matrix(Matrix::column(list)?)),
+        Value::Matrix(matrix) => Ok(Value::Matrix(matrix.transpose()))
+    }
+}
+
+pub fn determinant(value: Value) -> Result<Value, CalculatorErr> {
+    Ok(Value::Number(value.into_matrix()?.determinant()?))
+}
+
+pub fn inverse(value: Value) -> Result<Value, CalculatorErr> {
+    match value {
+        Value::Number(n) => Ok(Value::Number(1_f64 / n)),
+        value => Ok(Value::Matrix(value.into_matrix()?.inverse()?))
+    }
+}
+
+pub fn rank(value: Value) -> Result<Value, CalculatorErr> {
+    Ok(Value::Number(value.into_matrix()?.rank() as f64))
+}
+
+/**
+ * Solve the linear system Ax = b; if b is a list, so is the solution
+ */
+pub fn solve_linear(a: Value, b: Value) -> Result<Value, CalculatorErr> {
+    let a = a.into_matrix()?;
+
+    match b {
+        Value::List(list) => Ok(Value::List(a.solve(&Matrix::column(list)?)?.into_data())),
+        b => Ok(Value::Matrix(a.solve(&b.into_matrix()?)?))
+    }
+}
+
+pub fn eigenvalues(value: Value) -> Result<Value, CalculatorErr> {
+    Ok(Value::List(value.into_matrix()?.eigenvalues()?))
+}
+
+/**
+ * Evaluate an expression for each value of a variable from start to end, counting in steps of step;
+ * each row of the matrix is a value of the variable, followed by the value of the expression for it
+ */
+pub fn table(interpreter: &Interpreter, args: &[ExprPrime]) -> Result<Value, CalculatorErr> {
+    let [expression, variable, start, end, step] = args else {
+        return Err(CalculatorErr::interp_err(format!("Function 'TABLE' expected 5 arguments; got {}.", args.len()).as_str()));
+    };
+
+    let variable = match variable {
+        ExprPrime::Id(id) => &id.value,
+        _ => return Err(CalculatorErr::interp_err("Function 'TABLE' expected the name of a variable as its second argument."))
+    };
+
+    let start = interpreter.evaluate_with_variables(start, &[])?.into_number()?;
+    let end = interpreter.evaluate_with_variables(end, &[])?.into_number()?;
+    let step = interpreter.evaluate_with_variables(step, &[])?.into_number()?;
+
+    let rows = interpreter.evaluate_table(expression, variable, start, end, step)?
+        .into_iter()
+        .map(|(x, result)| Ok(vec![x, result?.into_number()?]))
+        .collect::<Result<Vec<Vec<f64>>, CalculatorErr>>()?;
+
+    if rows.is_empty() {
+        return Err(CalculatorErr::interp_err(format!("Function 'TABLE' has no rows from {start} to {end} in steps of {step}.").as_str()));
+    }
+
+    Ok(Value::Matrix(Matrix::from_rows(rows)?))
+}
@@ -0,0 +1,127 @@
+use std::fmt::Display;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/**
+ * The individual values produced by a dice roll or sampling function during evaluation.
+ *
+ * Values that were rolled but not counted towards the result (i.e. dropped dice) are marked as not kept.
+ */
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Roll {
+    pub label: String,
+    pub values: Vec<f64>,
+    pub kept: Vec<bool>
+}
+
+impl Roll {
+    pub fn new(label: String, values: Vec<f64>, kept: Vec<bool>) -> Self {
+        Self {
+            label,
+            values,
+            kept
+        }
+    }
+
+    /**
+     * Create a roll where every value counted towards the result
+     */
+    pub fn all_kept(label: String, values: Vec<f64>) -> Self {
+        let kept = vec![true; values.len()];
+        Self::new(label, values, kept)
+    }
+}
+
+impl Display for Roll {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values: Vec<String> = self.values.iter()
+            .enumerate()
+            .map(|(i, value)| {
+                if self.kept.get(i).copied().unwrap_or(true) {
+                    value.to_string()
+                }
+                else {
+                    format!("({value})")
+                }
+            })
+            .collect();
+
+        f.write_fmt(format_args!("{}: [{}]", self.label, values.join(", ")))
+    }
+}
+
+/**
+ * The source of randomness used by the interpreter's random functions.
+ *
+ * Uses ChaCha8, whose output for a given seed is the same on every platform
+ * (including wasm), so seeded sessions are reproducible.
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct RandomSource {
+    rng: ChaCha8Rng,
+    rolls: Vec<Roll>
+}
+
+impl RandomSource {
+    pub fn new(rng: ChaCha8Rng) -> Self {
+        Self {
+            rng,
+            rolls: Vec::new()
+        }
+    }
+
+    /**
+     * Create a random source that will produce the same sequence for the same seed
+     */
+    pub fn from_seed(seed: u64) -> Self {
+        Self::new(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /**
+     * Create a random source seeded from the operating system (or browser)
+     */
+    pub fn from_entropy() -> Self {
+        Self::new(ChaCha8Rng::from_entropy())
+    }
+
+    /**
+     * Reset the random source to the start of the sequence for the given seed
+     */
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    /**
+     * Reset the generator to that of another random source, keeping the rolls recorded so far
+     */
+    pub fn restore_generator(&mut self, other: RandomSource) {
+        self.rng = other.rng;
+    }
+
+    pub fn rng(&mut self) -> &mut ChaCha8Rng {
+        &mut self.rng
+    }
+
+    /**
+     * Record the values produced by a roll, so they can be reported alongside the result
+     */
+    pub fn record(&mut self, roll: Roll) {
+        self.rolls.push(roll);
+    }
+
+    pub fn clear_rolls(&mut self) {
+        self.rolls.clear();
+    }
+
+    pub fn rolls(&self) -> &[Roll] {
+        &self.rolls
+    }
+}
+
+impl Default for RandomSource {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
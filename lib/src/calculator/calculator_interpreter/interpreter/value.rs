@@ -0,0 +1,225 @@
+use std::fmt::Display;
+
+use crate::calculator::CalculatorErr;
+
+use self::matrix::Matrix;
+
+pub mod matrix;
+#[cfg(feature = "serde")]
+pub(crate) mod float_serde;
+
+/**
+ * The most elements a list can hold
+ */
+pub const MAX_LIST_LEN: usize = 1_000_000;
+
+/**
+ * The result of evaluating an expression; either a single number, a list of numbers, or a matrix.
+ */
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Value {
+    Number(#[cfg_attr(feature = "serde", serde(with = "float_serde"))] f64),
+    List(#[cfg_attr(feature = "serde", serde(with = "float_serde::list"))] Vec<f64>),
+    Matrix(Matrix)
+}
+
+/**
+ * The shape shared by the values being broadcast together
+ */
+enum Shape {
+    Number,
+    List(usize),
+    Matrix(usize, usize)
+}
+
+impl Value {
+    /**
+     * Get the value as a number, failing if it is a list or matrix
+     */
+    pub fn into_number(self) -> Result<f64, CalculatorErr> {
+        match self {
+            Self::Number(n) => Ok(n),
+            Self::List(list) => Err(CalculatorErr::interp_err(format!("Expected a number; got list '{}'.", Self::List(list)).as_str())),
+            Self::Matrix(matrix) => Err(CalculatorErr::interp_err(format!("Expected a number; got a {}x{} matrix.", matrix.rows(), matrix.cols()).as_str()))
+        }
+    }
+
+    /**
+     * Get the value as a matrix; a number becomes a 1x1 matrix, failing if it is a list
+     */
+    pub fn into_matrix(self) -> Result<Matrix, CalculatorErr> {
+        match self {
+            Self::Number(n) => Matrix::new(1, 1, vec![n]),
+            Self::List(list) => Err(CalculatorErr::interp_err(format!("Expected a matrix; got list '{}'.", Self::List(list)).as_str())),
+            Self::Matrix(matrix) => Ok(matrix)
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None
+        }
+    }
+
+    pub fn is_list(&self) -> bool {
+        matches!(self, Self::List(_))
+    }
+
+    pub fn is_matrix(&self) -> bool {
+        matches!(self, Self::Matrix(_))
+    }
+
+    /**
+     * Get all numbers held by the value; a number becomes a list of a single element, and a matrix is read row by row
+     */
+    pub fn flatten(self) -> Vec<f64> {
+        match self {
+            Self::Number(n) => vec![n],
+            Self::List(list) => list,
+            Self::Matrix(matrix) => matrix.into_data()
+        }
+    }
+
+    /**
+     * Apply a function to the number, or each element of the list or matrix
+     */
+    pub fn map<F>(self, f: F) -> Result<Value, CalculatorErr>
+        where F : Fn(f64) -> Result<f64, CalculatorErr>
+    {
+        match self {
+            Self::Number(n) => Ok(Self::Number(f(n)?)),
+            Self::List(list) => Ok(Self::List(list.into_iter().map(f).collect::<Result<Vec<f64>, CalculatorErr>>()?)),
+            Self::Matrix(matrix) => Ok(Self::Matrix(matrix.map(f)?))
+        }
+    }
+
+    /**
+     * Apply a function element-wise across several values.
+     *
+     * Numbers are repeated to match the shape of any lists or matrices; all lists must have the same length,
+     * all matrices must have the same dimensions, and lists cannot be combined with matrices.
+     * If none of the values are lists or matrices, the result is a number.
+     */
+    pub fn broadcast<F>(values: &[Value], mut f: F) -> Result<Value, CalculatorErr>
+        where F : FnMut(&[f64]) -> Result<f64, CalculatorErr>
+    {
+        let mut shape = Shape::Number;
+
+        for value in values {
+            match (value, &shape) {
+                (Self::Number(_), _) => {},
+                (Self::List(list), Shape::Number) => shape = Shape::List(list.len()),
+                (Self::List(list), Shape::List(len)) => {
+                    if *len != list.len() {
+                        return Err(CalculatorErr::interp_err(format!("Cannot combine lists of different lengths ({len} and {}).", list.len()).as_str()));
+                    }
+                },
+                (Self::Matrix(matrix), Shape::Number) => shape = Shape::Matrix(matrix.rows(), matrix.cols()),
+                (Self::Matrix(matrix), Shape::Matrix(rows, cols)) => {
+                    if *rows != matrix.rows() || *cols != matrix.cols() {
+                        return Err(CalculatorErr::interp_err(format!("Cannot combine matrices of different dimensions ({rows}x{cols} and {}x{}).", matrix.rows(), matrix.cols()).as_str()));
+                    }
+                },
+                (Self::List(_), Shape::Matrix(..)) | (Self::Matrix(_), Shape::List(_)) => {
+                    return Err(CalculatorErr::interp_err("Cannot combine a list with a matrix element-wise."));
+                }
+            }
+        }
+
+        let element_at = |i: usize| -> Vec<f64> {
+            values.iter()
+                .map(|value| match value {
+                    Self::Number(n) => *n,
+                    Self::List(list) => list[i],
+                    Self::Matrix(matrix) => matrix.data()[i]
+                })
+                .collect()
+        };
+
+        let mut results_for = |len: usize| -> Result<Vec<f64>, CalculatorErr> {
+            let mut results: Vec<f64> = Vec::with_capacity(len);
+
+            for i in 0..len {
+                results.push(f(&element_at(i))?);
+            }
+
+            Ok(results)
+        };
+
+        match shape {
+            Shape::Number => Ok(Self::Number(results_for(1)?[0])),
+            Shape::List(len) => Ok(Self::List(results_for(len)?)),
+            Shape::Matrix(rows, cols) => Ok(Self::Matrix(Matrix::new(rows, cols, results_for(rows * cols)?)?))
+        }
+    }
+
+    /**
+     * Get the element of the list, or row of the matrix, at the given index, counting back from the end if negative
+     */
+    pub fn index(self, index: f64) -> Result<Value, CalculatorErr> {
+        let len = match &self {
+            Self::Number(n) => return Err(CalculatorErr::interp_err(format!("Cannot index into number '{n}'.").as_str())),
+            Self::List(list) => list.len(),
+            Self::Matrix(matrix) => matrix.rows()
+        } as f64;
+
+        if index.fract() != 0_f64 {
+            return Err(CalculatorErr::interp_err(format!("List index '{index}' must be an integer.").as_str()));
+        }
+
+        let resolved = if index < 0_f64 { len + index } else { index };
+
+        if resolved < 0_f64 || resolved >= len {
+            return Err(CalculatorErr::interp_err(format!("List index '{index}' is out of range for a list of length {len}.").as_str()));
+        }
+
+        match self {
+            Self::List(list) => Ok(Self::Number(list[resolved as usize])),
+            Self::Matrix(matrix) => Ok(Self::List(matrix.row(resolved as usize))),
+            Self::Number(_) => unreachable!()
+        }
+    }
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        Self::Number(0_f64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<Vec<f64>> for Value {
+    fn from(value: Vec<f64>) -> Self {
+        Self::List(value)
+    }
+}
+
+impl From<Matrix> for Value {
+    fn from(value: Matrix) -> Self {
+        Self::Matrix(value)
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(n) => f.write_fmt(format_args!("{n}")),
+            Self::List(list) => {
+                let elements: Vec<String> = list.iter()
+                    .map(|n| n.to_string())
+                    .collect();
+
+                f.write_fmt(format_args!("[{}]", elements.join(", ")))
+            },
+            Self::Matrix(matrix) => f.write_fmt(format_args!("{matrix}"))
+        }
+    }
+}
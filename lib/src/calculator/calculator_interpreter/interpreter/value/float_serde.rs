@@ -0,0 +1,68 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+/**
+ * A number as it is written; JSON has no NaN or infinity, so those are written by name
+ */
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Float {
+    Finite(f64),
+    Named(String)
+}
+
+impl From<f64> for Float {
+    fn from(value: f64) -> Self {
+        if value.is_nan() {
+            Float::Named("NaN".to_string())
+        }
+        else if value == f64::INFINITY {
+            Float::Named("inf".to_string())
+        }
+        else if value == f64::NEG_INFINITY {
+            Float::Named("-inf".to_string())
+        }
+        else {
+            Float::Finite(value)
+        }
+    }
+}
+
+impl Float {
+    fn into_f64<E: Error>(self) -> Result<f64, E> {
+        match self {
+            Float::Finite(value) => Ok(value),
+            Float::Named(name) => match name.as_str() {
+                "NaN" => Ok(f64::NAN),
+                "inf" => Ok(f64::INFINITY),
+                "-inf" => Ok(f64::NEG_INFINITY),
+                _ => Err(E::custom(format!("'{name}' is not a number.")))
+            }
+        }
+    }
+}
+
+pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    Float::from(*value).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Float::deserialize(deserializer)?.into_f64()
+}
+
+/**
+ * The same, for each number of a list
+ */
+pub mod list {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| Float::from(*value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        Vec::<Float>::deserialize(deserializer)?
+            .into_iter()
+            .map(Float::into_f64)
+            .collect()
+    }
+}
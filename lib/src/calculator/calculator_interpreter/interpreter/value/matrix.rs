@@ -0,0 +1,618 @@
+use std::fmt::Display;
+
+use crate::calculator::CalculatorErr;
+
+/**
+ * Values smaller than this, relative to the size of the matrix's elements, are treated as zero during elimination
+ */
+const EPSILON: f64 = 1e-10;
+
+/**
+ * The largest number of rows/columns of a matrix whose eigenvalues can be found
+ */
+pub const MAX_EIGEN_SIZE: usize = 16;
+
+/**
+ * The most iterations of the QR algorithm, per eigenvalue, before giving up
+ */
+const MAX_EIGEN_ITERATIONS: usize = 500;
+
+/**
+ * The number of iterations without convergence between exceptional shifts of the QR algorithm
+ */
+const EXCEPTIONAL_SHIFT_INTERVAL: usize = 11;
+
+/**
+ * A matrix of numbers, stored in row-major order
+ */
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "MatrixParts"))]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    #[cfg_attr(feature = "serde", serde(with = "super::float_serde::list"))]
+    data: Vec<f64>
+}
+
+/**
+ * The fields of a matrix as they are deserialized, before checking that they are consistent
+ */
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct MatrixParts {
+    rows: usize,
+    cols: usize,
+    #[cfg_attr(feature = "serde", serde(with = "super::float_serde::list"))]
+    data: Vec<f64>
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MatrixParts> for Matrix {
+    type Error = CalculatorErr;
+
+    fn try_from(parts: MatrixParts) -> Result<Self, Self::Error> {
+        Self::new(parts.rows, parts.cols, parts.data)
+    }
+}
+
+impl Matrix {
+    pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Result<Self, CalculatorErr> {
+        if rows == 0 || cols == 0 {
+            Err(CalculatorErr::interp_err("A matrix must have at least one row and column."))
+        }
+        else if rows.checked_mul(cols) != Some(data.len()) {
+            Err(CalculatorErr::interp_err(format!("A {rows}x{cols} matrix must have {} elements; got {}.", rows.saturating_mul(cols), data.len()).as_str()))
+        }
+        else {
+            Ok(Self {
+                rows,
+                cols,
+                data
+            })
+        }
+    }
+
+    /**
+     * Create a matrix from its rows, which must all have the same length
+     */
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> Result<Self, CalculatorErr> {
+        let row_count = rows.len();
+        let col_count = rows.first().map(|row| row.len()).unwrap_or(0);
+
+        if let Some(row) = rows.iter().find(|row| row.len() != col_count) {
+            return Err(CalculatorErr::interp_err(format!("Matrix rows must have the same length; got rows of length {col_count} and {}.", row.len()).as_str()));
+        }
+
+        Self::new(row_count, col_count, rows.into_iter().flatten().collect())
+    }
+
+    /**
+     * Create a matrix with a single column from a list
+     */
+    pub fn column(values: Vec<f64>) -> Result<Self, CalculatorErr> {
+        Self::new(values.len(), 1, values)
+    }
+
+    /**
+     * Create a matrix with a single row from a list
+     */
+    pub fn row_vector(values: Vec<f64>) -> Result<Self, CalculatorErr> {
+        Self::new(1, values.len(), values)
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut data = vec![0_f64; size * size];
+
+        for i in 0..size {
+            data[i * size + i] = 1_f64;
+        }
+
+        Self {
+            rows: size,
+            cols: size,
+            data
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.data[row * self.cols + col]
+    }
+
+    fn set(&mut self, row: usize, col: usize, value: f64) {
+        self.data[row * self.cols + col] = value;
+    }
+
+    pub fn row(&self, row: usize) -> Vec<f64> {
+        self.data[row * self.cols..(row + 1) * self.cols].to_vec()
+    }
+
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<f64> {
+        self.data
+    }
+
+    /**
+     * Apply a function to each element of the matrix
+     */
+    pub fn map<F>(self, f: F) -> Result<Self, CalculatorErr>
+        where F : Fn(f64) -> Result<f64, CalculatorErr>
+    {
+        let data = self.data.into_iter().map(f).collect::<Result<Vec<f64>, CalculatorErr>>()?;
+        Self::new(self.rows, self.cols, data)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut data = Vec::with_capacity(self.data.len());
+
+        for col in 0..self.cols {
+            for row in 0..self.rows {
+                data.push(self.get(row, col));
+            }
+        }
+
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            data
+        }
+    }
+
+    /**
+     * Matrix multiplication
+     */
+    pub fn mul(&self, other: &Matrix) -> Result<Self, CalculatorErr> {
+        if self.cols != other.rows {
+            return Err(CalculatorErr::interp_err(format!("Cannot multiply a {}x{} matrix by a {}x{} matrix.", self.rows, self.cols, other.rows, other.cols).as_str()));
+        }
+
+        let mut data = vec![0_f64; self.rows * other.cols];
+
+        for row in 0..self.rows {
+            for col in 0..other.cols {
+                data[row * other.cols + col] = (0..self.cols)
+                    .map(|k| self.get(row, k) * other.get(k, col))
+                    .sum();
+            }
+        }
+
+        Self::new(self.rows, other.cols, data)
+    }
+
+    /**
+     * Raise a square matrix to an integer power, using the inverse for negative powers
+     */
+    pub fn pow(&self, exponent: f64) -> Result<Self, CalculatorErr> {
+        if !self.is_square() {
+            return Err(CalculatorErr::interp_err(format!("Cannot raise a non-square {}x{} matrix to a power.", self.rows, self.cols).as_str()));
+        }
+        else if exponent.fract() != 0_f64 || !exponent.is_finite() {
+            return Err(CalculatorErr::interp_err(format!("Matrices can only be raised to integer powers; got '{exponent}'.").as_str()));
+        }
+
+        let mut base = if exponent < 0_f64 { self.inverse()? } else { self.clone() };
+        let mut remaining = exponent.abs() as u64;
+        let mut result = Self::identity(self.rows);
+
+        //Exponentiation by squaring
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                result = result.mul(&base)?;
+            }
+
+            remaining /= 2;
+
+            if remaining > 0 {
+                base = base.mul(&base)?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /**
+     * The largest absolute value of the matrix's elements, used to scale tolerances
+     */
+    fn scale(&self) -> f64 {
+        self.data.iter()
+            .fold(0_f64, |max, n| max.max(n.abs()))
+            .max(1_f64)
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for col in 0..self.cols {
+                self.data.swap(a * self.cols + col, b * self.cols + col);
+            }
+        }
+    }
+
+    /**
+     * Find the row at or below the given row with the largest absolute value in the given column
+     */
+    fn pivot_row(&self, from_row: usize, col: usize) -> usize {
+        (from_row..self.rows)
+            .max_by(|a, b| self.get(*a, col).abs().total_cmp(&self.get(*b, col).abs()))
+            .unwrap_or(from_row)
+    }
+
+    fn require_square(&self, operation: &str) -> Result<(), CalculatorErr> {
+        if self.is_square() {
+            Ok(())
+        }
+        else {
+            Err(CalculatorErr::interp_err(format!("Cannot find the {operation} of a non-square {}x{} matrix.", self.rows, self.cols).as_str()))
+        }
+    }
+
+    /**
+     * The determinant, found by gaussian elimination with partial pivoting
+     */
+    pub fn determinant(&self) -> Result<f64, CalculatorErr> {
+        self.require_square("determinant")?;
+
+        let mut reduced = self.clone();
+        let mut determinant = 1_f64;
+
+        for col in 0..reduced.cols {
+            let pivot_row = reduced.pivot_row(col, col);
+
+            if reduced.get(pivot_row, col) == 0_f64 {
+                return Ok(0_f64);
+            }
+
+            if pivot_row != col {
+                reduced.swap_rows(pivot_row, col);
+                determinant = -determinant;
+            }
+
+            let pivot = reduced.get(col, col);
+            determinant *= pivot;
+
+            for row in col + 1..reduced.rows {
+                let factor = reduced.get(row, col) / pivot;
+
+                for k in col..reduced.cols {
+                    let value = reduced.get(row, k) - factor * reduced.get(col, k);
+                    reduced.set(row, k, value);
+                }
+            }
+        }
+
+        Ok(determinant)
+    }
+
+    /**
+     * Solve for X in AX = B, where A is this matrix, by gauss-jordan elimination
+     */
+    pub fn solve(&self, b: &Matrix) -> Result<Self, CalculatorErr> {
+        self.require_square("solution")?;
+
+        if b.rows != self.rows {
+            return Err(CalculatorErr::interp_err(format!("Cannot solve a {}x{} system with {} values.", self.rows, self.cols, b.rows).as_str()));
+        }
+
+        let tolerance = EPSILON * self.scale();
+        let mut a = self.clone();
+        let mut x = b.clone();
+
+        for col in 0..a.cols {
+            let pivot_row = a.pivot_row(col, col);
+
+            if a.get(pivot_row, col).abs() <= tolerance {
+                return Err(CalculatorErr::interp_err("Matrix is singular."));
+            }
+
+            a.swap_rows(pivot_row, col);
+            x.swap_rows(pivot_row, col);
+
+            let pivot = a.get(col, col);
+
+            for k in 0..a.cols {
+                a.set(col, k, a.get(col, k) / pivot);
+            }
+
+            for k in 0..x.cols {
+                x.set(col, k, x.get(col, k) / pivot);
+            }
+
+            for row in 0..a.rows {
+                if row == col {
+                    continue;
+                }
+
+                let factor = a.get(row, col);
+
+                if factor == 0_f64 {
+                    continue;
+                }
+
+                for k in 0..a.cols {
+                    a.set(row, k, a.get(row, k) - factor * a.get(col, k));
+                }
+
+                for k in 0..x.cols {
+                    x.set(row, k, x.get(row, k) - factor * x.get(col, k));
+                }
+            }
+        }
+
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Result<Self, CalculatorErr> {
+        self.require_square("inverse")?;
+        self.solve(&Self::identity(self.rows))
+    }
+
+    /**
+     * The number of linearly independent rows, found by reducing to row echelon form
+     */
+    pub fn rank(&self) -> usize {
+        let tolerance = EPSILON * self.scale();
+        let mut reduced = self.clone();
+        let mut rank = 0_usize;
+
+        for col in 0..reduced.cols {
+            if rank == reduced.rows {
+                break;
+            }
+
+            let pivot_row = reduced.pivot_row(rank, col);
+
+            if reduced.get(pivot_row, col).abs() <= tolerance {
+                continue;
+            }
+
+            reduced.swap_rows(pivot_row, rank);
+
+            let pivot = reduced.get(rank, col);
+
+            for row in rank + 1..reduced.rows {
+                let factor = reduced.get(row, col) / pivot;
+
+                for k in col..reduced.cols {
+                    let value = reduced.get(row, k) - factor * reduced.get(rank, k);
+                    reduced.set(row, k, value);
+                }
+            }
+
+            rank += 1;
+        }
+
+        rank
+    }
+
+    /**
+     * The real eigenvalues of a small square matrix, from highest to lowest, found using the shifted QR algorithm
+     * on the matrix reduced to upper Hessenberg form, so that only the subdiagonal needs checking for convergence.
+     *
+     * Fails if the matrix has complex eigenvalues.
+     */
+    pub fn eigenvalues(&self) -> Result<Vec<f64>, CalculatorErr> {
+        self.require_square("eigenvalues")?;
+
+        if self.rows > MAX_EIGEN_SIZE {
+            return Err(CalculatorErr::interp_err(format!("Can only find the eigenvalues of matrices up to {MAX_EIGEN_SIZE}x{MAX_EIGEN_SIZE}.").as_str()));
+        }
+
+        let complex_err = || CalculatorErr::interp_err("Matrix has complex eigenvalues.");
+
+        let tolerance = EPSILON * self.scale();
+        let mut a = self.hessenberg();
+        let mut eigenvalues: Vec<f64> = Vec::new();
+        let mut size = a.rows;
+        let mut iterations = 0_usize;
+        let mut until_exceptional_shift = EXCEPTIONAL_SHIFT_INTERVAL;
+
+        while size > 0 {
+            if size == 1 {
+                eigenvalues.push(a.get(0, 0));
+                break;
+            }
+
+            let last = size - 1;
+
+            //The last row has converged; deflate
+            if a.get(last, last - 1).abs() <= tolerance {
+                eigenvalues.push(a.get(last, last));
+                size -= 1;
+                iterations = 0;
+                until_exceptional_shift = EXCEPTIONAL_SHIFT_INTERVAL;
+                continue;
+            }
+
+            //The last 2x2 block is separate from the rest of the matrix; take its eigenvalues directly
+            if size == 2 || a.get(last - 1, last - 2).abs() <= tolerance {
+                let (first, second) = eigenvalues_2x2(a.get(last - 1, last - 1), a.get(last - 1, last), a.get(last, last - 1), a.get(last, last))
+                    .ok_or_else(complex_err)?;
+
+                eigenvalues.push(first);
+                eigenvalues.push(second);
+                size -= 2;
+                iterations = 0;
+                until_exceptional_shift = EXCEPTIONAL_SHIFT_INTERVAL;
+                continue;
+            }
+
+            iterations += 1;
+            until_exceptional_shift -= 1;
+
+            if iterations > MAX_EIGEN_ITERATIONS {
+                return Err(complex_err());
+            }
+
+            //Use the eigenvalue of the last 2x2 block closest to the last element as the shift,
+            //with an occasional exceptional shift to avoid stalling
+            let shift = if until_exceptional_shift == 0 {
+                until_exceptional_shift = EXCEPTIONAL_SHIFT_INTERVAL;
+                a.get(last, last) + a.get(last, last - 1).abs()
+            }
+            else {
+                match eigenvalues_2x2(a.get(last - 1, last - 1), a.get(last - 1, last), a.get(last, last - 1), a.get(last, last)) {
+                    Some((first, second)) => {
+                        if (first - a.get(last, last)).abs() < (second - a.get(last, last)).abs() { first } else { second }
+                    },
+                    None => a.get(last, last)
+                }
+            };
+
+            a.qr_step(size, shift);
+        }
+
+        eigenvalues.sort_by(|a, b| b.total_cmp(a));
+        Ok(eigenvalues)
+    }
+
+    /**
+     * A matrix with the same eigenvalues that is zero below the subdiagonal, found using householder reflections
+     */
+    fn hessenberg(&self) -> Matrix {
+        let mut a = self.clone();
+        let size = a.rows;
+
+        for col in 0..size.saturating_sub(2) {
+            //The reflection that maps the part of the column below the subdiagonal onto the subdiagonal
+            let mut v: Vec<f64> = (col + 1..size).map(|row| a.get(row, col)).collect();
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+            if norm == 0_f64 {
+                continue;
+            }
+
+            v[0] += if v[0] < 0_f64 { -norm } else { norm };
+
+            let v_norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            v.iter_mut().for_each(|x| *x /= v_norm);
+
+            //A = HAH, where H = I - 2vv^T acts on the rows and columns after the current one
+            for k in 0..size {
+                let dot: f64 = v.iter().enumerate().map(|(i, x)| x * a.get(col + 1 + i, k)).sum();
+
+                for (i, x) in v.iter().enumerate() {
+                    a.set(col + 1 + i, k, a.get(col + 1 + i, k) - 2_f64 * x * dot);
+                }
+            }
+
+            for k in 0..size {
+                let dot: f64 = v.iter().enumerate().map(|(i, x)| x * a.get(k, col + 1 + i)).sum();
+
+                for (i, x) in v.iter().enumerate() {
+                    a.set(k, col + 1 + i, a.get(k, col + 1 + i) - 2_f64 * x * dot);
+                }
+            }
+
+            for row in col + 2..size {
+                a.set(row, col, 0_f64);
+            }
+        }
+
+        a
+    }
+
+    /**
+     * Perform one shifted QR step, A - sI = QR, A = RQ + sI, on the leading block of the given size of an upper Hessenberg matrix.
+     * Only the subdiagonal needs to be rotated away, and the result is again upper Hessenberg.
+     */
+    fn qr_step(&mut self, size: usize, shift: f64) {
+        for i in 0..size {
+            self.set(i, i, self.get(i, i) - shift);
+        }
+
+        //Reduce to R using givens rotations, recording each rotation
+        let mut rotations: Vec<(usize, usize, f64, f64)> = Vec::new();
+
+        for col in 0..size - 1 {
+            let row = col + 1;
+            let a = self.get(col, col);
+            let b = self.get(row, col);
+
+            if b == 0_f64 {
+                continue;
+            }
+
+            let r = a.hypot(b);
+            let (c, s) = (a / r, b / r);
+
+            for k in 0..size {
+                let upper = self.get(col, k);
+                let lower = self.get(row, k);
+                self.set(col, k, c * upper + s * lower);
+                self.set(row, k, -s * upper + c * lower);
+            }
+
+            rotations.push((col, row, c, s));
+        }
+
+        //Multiply R by Q, by applying the transposed rotations to the columns
+        for (col, row, c, s) in rotations {
+            for k in 0..size {
+                let left = self.get(k, col);
+                let right = self.get(k, row);
+                self.set(k, col, c * left + s * right);
+                self.set(k, row, -s * left + c * right);
+            }
+        }
+
+        for i in 0..size {
+            self.set(i, i, self.get(i, i) + shift);
+        }
+    }
+}
+
+/**
+ * The eigenvalues of the 2x2 matrix [[a, b], [c, d]], or None if they are complex
+ */
+fn eigenvalues_2x2(a: f64, b: f64, c: f64, d: f64) -> Option<(f64, f64)> {
+    let half_trace = (a + d) / 2_f64;
+    let determinant = a * d - b * c;
+    let discriminant = half_trace * half_trace - determinant;
+
+    if discriminant < -EPSILON * half_trace.abs().max(1_f64) {
+        None
+    }
+    else {
+        let root = discriminant.max(0_f64).sqrt();
+        Some((half_trace + root, half_trace - root))
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells: Vec<String> = self.data.iter()
+            .map(|n| n.to_string())
+            .collect();
+
+        //Pad each column to the width of its widest element, so that the columns line up
+        let widths: Vec<usize> = (0..self.cols)
+            .map(|col| (0..self.rows).map(|row| cells[row * self.cols + col].len()).max().unwrap_or(0))
+            .collect();
+
+        let rows: Vec<String> = (0..self.rows)
+            .map(|row| {
+                let padded: Vec<String> = (0..self.cols)
+                    .map(|col| format!("{:>width$}", cells[row * self.cols + col], width = widths[col]))
+                    .collect();
+
+                format!("[{}]", padded.join(", "))
+            })
+            .collect();
+
+        f.write_fmt(format_args!("[{}]", rows.join(",\n ")))
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::{Range, RangeInclusive};
 
-use super::interpreter::*;
+use super::interpreter::{*, function::{FunctionArgs, FunctionCategory, UserFunction}, value::{Value, matrix::Matrix}};
 use crate::calculator::calculator_parser::{parser::Parser, expression::Expr};
 
 fn parse(input: &str) -> Expr {
@@ -17,7 +17,7 @@ fn parse(input: &str) -> Expr {
 fn default_test(input: &str, expected: f64) {
     const THRESHOLD: f64 = 4_f64 * f64::EPSILON;
 
-    match Interpreter::default().evaluate(parse(input)) {
+    match Interpreter::default().evaluate(parse(input)).and_then(Value::into_number) {
         Ok(result) => {
             assert!((result - expected).abs() < THRESHOLD, "Testing equality of {result} and {expected}.")
         },
@@ -27,6 +27,75 @@ fn default_test(input: &str, expected: f64) {
     }
 }
 
+/**
+ * The provided input, should be parsed and evaluated
+ * to equal to the expected value, within the given threshold
+ */
+fn approx_test(input: &str, expected: f64, threshold: f64) {
+    match Interpreter::default().evaluate(parse(input)).and_then(Value::into_number) {
+        Ok(result) => {
+            assert!((result - expected).abs() < threshold, "Testing equality of {result} and {expected}.")
+        },
+        Err(err) => {
+            panic!("{err}")
+        }
+    }
+}
+
+/**
+ * The provided input, should be parsed and evaluated
+ * to equal to the expected list or number
+ */
+fn value_test(input: &str, expected: Value) {
+    value_test_within(input, expected, 4_f64 * f64::EPSILON);
+}
+
+/**
+ * The provided input, should be parsed and evaluated
+ * to be within the threshold of the expected list, matrix or number
+ */
+fn value_test_within(input: &str, expected: Value, threshold: f64) {
+    match Interpreter::default().evaluate(parse(input)) {
+        Ok(result) => {
+            assert_eq!(result.is_list(), expected.is_list(), "Testing equality of {result} and {expected}.");
+            assert_eq!(result.is_matrix(), expected.is_matrix(), "Testing equality of {result} and {expected}.");
+
+            if let (Value::Matrix(r), Value::Matrix(e)) = (&result, &expected) {
+                assert_eq!((r.rows(), r.cols()), (e.rows(), e.cols()), "Testing equality of {result} and {expected}.");
+            }
+
+            let result_elements = result.clone().flatten();
+            let expected_elements = expected.clone().flatten();
+
+            assert_eq!(result_elements.len(), expected_elements.len(), "Testing equality of {result} and {expected}.");
+
+            for (r, e) in result_elements.iter().zip(expected_elements.iter()) {
+                assert!((r - e).abs() < threshold, "Testing equality of {result} and {expected}.")
+            }
+        },
+        Err(err) => {
+            panic!("{err}")
+        }
+    }
+}
+
+/**
+ * Create a matrix value from its rows
+ */
+fn matrix(rows: Vec<Vec<f64>>) -> Value {
+    Value::Matrix(Matrix::from_rows(rows).unwrap())
+}
+
+/**
+ * The body of a user defined function
+ */
+fn body(input: &str) -> crate::calculator::calculator_parser::expression::ExprPrime {
+    match parse(input) {
+        Expr::ExprPrime(expr_prime) => *expr_prime,
+        Expr::None => panic!("Expected a function body.")
+    }
+}
+
 /**
  * Each provided input should be parsed and evaluated
  * to equal to the expected value
@@ -37,7 +106,7 @@ fn sequence_test(sequence: Vec<(&str, Option<f64>)>) {
     let interpreter = Interpreter::default();
 
     for (n, (input, expected)) in sequence.iter().enumerate() {
-        match interpreter.evaluate(parse(input)) {
+        match interpreter.evaluate(parse(input)).and_then(Value::into_number) {
             Ok(result) => {
                 match expected {
                     None => (),
@@ -58,7 +127,7 @@ fn sequence_test(sequence: Vec<(&str, Option<f64>)>) {
  */
 fn inf_test(input: &str) {
 
-    match Interpreter::default().evaluate(parse(input)) {
+    match Interpreter::default().evaluate(parse(input)).and_then(Value::into_number) {
         Ok(result) => {
             assert!(result.is_infinite(), "Testing that {result} = f64::inf")
         },
@@ -74,7 +143,7 @@ fn inf_test(input: &str) {
  */
 fn nan_test(input: &str) {
 
-    match Interpreter::default().evaluate(parse(input)) {
+    match Interpreter::default().evaluate(parse(input)).and_then(Value::into_number) {
         Ok(result) => {
             assert!(result.is_nan(), "Testing that {result} is not a number")
         },
@@ -88,7 +157,7 @@ fn in_range_test(input: &str, range: Range<f64>, repeat: usize) {
     let interpreter = Interpreter::default();
 
     for _ in 0_usize..repeat {
-        match interpreter.evaluate(parse(input)) {
+        match interpreter.evaluate(parse(input)).and_then(Value::into_number) {
             Ok(result) => {
                 assert!(range.contains(&result), "Testing that {result} is in the range [{}, {}).", range.start, range.end)
             },
@@ -103,7 +172,7 @@ fn in_range_inc_test(input: &str, range: RangeInclusive<f64>, repeat: usize) {
     let interpreter = Interpreter::default();
 
     for _ in 0_usize..repeat {
-        match interpreter.evaluate(parse(input)) {
+        match interpreter.evaluate(parse(input)).and_then(Value::into_number) {
             Ok(result) => {
                 assert!(range.contains(&result), "Testing that {result} is in the range [{}, {}].", range.start(), range.end())
             },
@@ -121,7 +190,7 @@ fn is_integer_test(input: &str, repeat: usize) {
     let interpreter = Interpreter::default();
 
     for _ in 0_usize..repeat {
-        match interpreter.evaluate(parse(input)) {
+        match interpreter.evaluate(parse(input)).and_then(Value::into_number) {
             Ok(result) => {
                 assert!(result.fract().abs() < f64::EPSILON, "Testing that {input} is an integer.")
             },
@@ -136,7 +205,7 @@ fn is_integer_test(input: &str, repeat: usize) {
  * The provided input should panic
  */
 fn panic_test(input: &str) {
-    match Interpreter::default().evaluate(parse(input)) {
+    match Interpreter::default().evaluate(parse(input)).and_then(Value::into_number) {
         Ok(result) => println!("{result}"),
         Err(err) => {
             panic!("{err}")
@@ -1341,207 +1410,1372 @@ fn rand_2() {
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the normal probability density function evaluates as expected
  */
-fn mod_0() {
-    let expected: f64 = 1_f64;
-    let input: &str = "5 mod 4";
-    default_test(input, expected);
+fn normpdf_0() {
+    let expected: f64 = 0.398_942_280_401_432_7_f64;
+    let input: &str = "normpdf(0, 0, 1)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the normal probability density function evaluates as expected
+ * for a non-standard distribution
  */
-fn mod_1() {
-    let expected: f64 = 4_f64;
-    let input: &str = "4 mod 5";
-    default_test(input, expected);
+fn normpdf_1() {
+    let expected: f64 = 0.398_942_280_401_432_7_f64 / 2_f64 * (-0.5_f64).exp();
+    let input: &str = "normpdf(7, 5, 2)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the normal probability density function is undefined
+ * for a non-positive standard deviation
  */
-fn mod_2() {
-    let expected: f64 = 0_f64;
-    let input: &str = "5 mod 5";
-    default_test(input, expected);
+fn normpdf_2() {
+    let input: &str = "normpdf(0, 0, 0)";
+    nan_test(input);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the normal cumulative distribution function evaluates as expected
  */
-fn mod_3() {
-    let expected: f64 = 2_f64;
-    let input: &str = "-3 mod 5";
-    default_test(input, expected);
+fn normcdf_0() {
+    let expected: f64 = 0.5_f64;
+    let input: &str = "normcdf(0, 0, 1)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the normal cumulative distribution function evaluates as expected
  */
-fn mod_4() {
-    let expected: f64 = -2_f64;
-    let input: &str = "3 mod -5";
-    default_test(input, expected);
+fn normcdf_1() {
+    let expected: f64 = 0.975_002_104_851_779_5_f64;
+    let input: &str = "normcdf(1.96, 0, 1)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the normal cumulative distribution function evaluates as expected
+ * in the lower tail
  */
-fn mod_5() {
-    let expected: f64 = -3_f64;
-    let input: &str = "-3 mod -5";
-    default_test(input, expected);
+fn normcdf_2() {
+    let expected: f64 = 2.866_515_718_791_939e-7_f64;
+    let input: &str = "normcdf(-5, 0, 1)";
+    approx_test(input, expected, 1e-15);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the inverse normal cumulative distribution function evaluates as expected
  */
-fn mod_6() {
-    let expected: f64 = 0.5_f64;
-    let input: &str = "0.5 mod 2.1";
-    default_test(input, expected);
+fn norminv_0() {
+    let expected: f64 = 1.959_963_984_540_054_f64;
+    let input: &str = "norminv(0.975, 0, 1)";
+    approx_test(input, expected, 1e-9);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the inverse normal cumulative distribution function evaluates as expected
+ * for a non-standard distribution
  */
-fn mod_7() {
-    let expected: f64 = 0_f64;
-    let input: &str = "0.5 mod 0.25";
-    default_test(input, expected);
+fn norminv_1() {
+    let expected: f64 = 100_f64 - 15_f64 * 1.281_551_565_544_601_f64;
+    let input: &str = "norminv(0.1, 100, 15)";
+    approx_test(input, expected, 1e-8);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the inverse normal cumulative distribution function is undefined
+ * outside of [0, 1]
  */
-fn mod_8() {
-    let input: &str = "0.5 mod 0";
-    nan_test(input)
+fn norminv_2() {
+    let input: &str = "norminv(1.5, 0, 1)";
+    nan_test(input);
 }
 
-
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the binomial probability mass function evaluates as expected
  */
-fn mod_9() {
-    let expected: f64 = -0.5_f64;
-    let input: &str = "0.5 mod -1";
-    default_test(input, expected);
+fn binompdf_0() {
+    let expected: f64 = 120_f64 / 1024_f64;
+    let input: &str = "binompdf(3, 10, 0.5)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the binomial probability mass function is 0
+ * for an impossible number of successes
  */
-fn mod_10() {
+fn binompdf_1() {
     let expected: f64 = 0_f64;
-    let input: &str = "3 mod -1";
+    let input: &str = "binompdf(11, 10, 0.5)";
     default_test(input, expected);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the binomial cumulative distribution function evaluates as expected
  */
-fn mod_11() {
-    let expected: f64 = -0.8_f64;
-    let input: &str = "5.2 mod -3";
-    default_test(input, expected);
+fn binomcdf_0() {
+    let expected: f64 = 176_f64 / 1024_f64;
+    let input: &str = "binomcdf(3, 10, 0.5)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the binomial cumulative distribution function evaluates as expected
+ * at the bounds of its support
  */
-fn mod_12() {
-    let expected: f64 = 0.8_f64;
-    let input: &str = "-5.2 mod 3";
-    default_test(input, expected);
+fn binomcdf_1() {
+    let seq: Vec<(&str, Option<f64>)> = vec![
+        ("binomcdf(-1, 10, 0.3)", Some(0_f64)),
+        ("binomcdf(10, 10, 0.3)", Some(1_f64))
+    ];
+    sequence_test(seq);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the poisson probability mass function evaluates as expected
  */
-fn mod_13() {
-    let expected: f64 = -2.2_f64;
-    let input: &str = "-5.2 mod -3";
-    default_test(input, expected);
+fn poissonpdf_0() {
+    let expected: f64 = (-3_f64).exp() * 4.5_f64;
+    let input: &str = "poissonpdf(2, 3)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that the modulo function outputs as expected
+ * Test that the poisson cumulative distribution function evaluates as expected
  */
-fn mod_14() {
-    let expected: f64 = 2.2_f64;
-    let input: &str = "5.2 mod 3";
-    default_test(input, expected);
+fn poissoncdf_0() {
+    let expected: f64 = (-3_f64).exp() * 8.5_f64;
+    let input: &str = "poissoncdf(2, 3)";
+    approx_test(input, expected, 1e-12);
 }
 
 #[test]
 /**
- * Test that history access evaluates as expected
+ * Test that student's t cumulative distribution function evaluates as expected
  */
-fn history_0() {
+fn tcdf_0() {
     let seq: Vec<(&str, Option<f64>)> = vec![
-        ("2", Some(2_f64)),
-        ("$0", Some(2_f64)),
-        ("3", Some(3_f64)),
-        ("$0", Some(3_f64)),
-        ("$1", Some(2_f64)),
-        ("$1 + $0 * 5", Some(13_f64)),
+        ("tcdf(0, 5)", Some(0.5_f64)),
+        ("tcdf(1, 1)", Some(0.75_f64)),
+        ("tcdf(-1, 1)", Some(0.25_f64))
     ];
-    sequence_test(seq);
+
+    for (input, expected) in seq {
+        approx_test(input, expected.unwrap(), 1e-12);
+    }
+}
+
+#[test]
+/**
+ * Test that student's t cumulative distribution function evaluates as expected
+ */
+fn tcdf_1() {
+    let expected: f64 = 0.975_f64;
+    let input: &str = "tcdf(2.228138851986, 10)";
+    approx_test(input, expected, 1e-9);
+}
+
+#[test]
+/**
+ * Test that the chi-squared cumulative distribution function evaluates as expected
+ */
+fn chisqcdf_0() {
+    let expected: f64 = 1_f64 - (-1_f64).exp();
+    let input: &str = "chisqcdf(2, 2)";
+    approx_test(input, expected, 1e-12);
+}
+
+#[test]
+/**
+ * Test that the chi-squared cumulative distribution function evaluates as expected
+ */
+fn chisqcdf_1() {
+    let expected: f64 = 0.95_f64;
+    let input: &str = "chisqcdf(3.841458820694124, 1)";
+    approx_test(input, expected, 1e-9);
+}
+
+#[test]
+/**
+ * Test that sampling from a normal distribution returns a finite number
+ */
+fn normrand_0() {
+    let repeat: usize = 500_usize;
+    let range = -1000_f64..1000_f64;
+    let input: &str = "normrand(5, 2)";
+    in_range_test(input, range, repeat)
 }
 
 #[test]
 #[should_panic]
 /**
- * Test that history access evaluates as expected
+ * Test that sampling from a normal distribution with a negative standard deviation
+ * fails
  */
-fn history_1() {
-    let seq: Vec<(&str, Option<f64>)> = vec![
-        ("2", Some(2_f64)),
-        ("$0", Some(2_f64)),
-        ("3", Some(3_f64)),
-        ("$0", Some(3_f64)),
-        ("$1", Some(2_f64)),
-        ("$5", None),
-    ];
-    sequence_test(seq);
+fn normrand_1() {
+    let input: &str = "normrand(0, -1)";
+    panic_test(input);
 }
 
 #[test]
 /**
- * Test that memory access/assignment evaluates as expected
+ * Test that sampling from a binomial distribution returns an integer
+ * in the support of the distribution
  */
-fn memory_access_0() {
+fn binomrand_0() {
+    let repeat: usize = 500_usize;
+    let range = 0_f64..=10_f64;
+    let input: &str = "binomrand(10, 0.5)";
+    in_range_inc_test(input, range, repeat);
+    is_integer_test(input, repeat);
+}
+
+#[test]
+/**
+ * Test that sampling from a poisson distribution returns a non-negative integer
+ */
+fn poissonrand_0() {
+    let repeat: usize = 500_usize;
+    let range = 0_f64..f64::INFINITY;
+    let input: &str = "poissonrand(3)";
+    in_range_test(input, range, repeat);
+    is_integer_test(input, repeat);
+}
+
+#[test]
+/**
+ * Test that sampling from a chi-squared distribution returns a non-negative number
+ */
+fn chisqrand_0() {
+    let repeat: usize = 500_usize;
+    let range = 0_f64..f64::INFINITY;
+    let input: &str = "chisqrand(2)";
+    in_range_test(input, range, repeat)
+}
+
+#[test]
+/**
+ * Test that sampling from student's t distribution returns a finite number
+ */
+fn trand_0() {
+    let repeat: usize = 500_usize;
+    let range = f64::MIN..f64::MAX;
+    let input: &str = "trand(4)";
+    in_range_test(input, range, repeat)
+}
+
+#[test]
+/**
+ * Test that sampling from distributions with infinite or undefined parameters fails, rather than sampling forever
+ */
+fn random_distribution_0() {
+    let inputs: Vec<&str> = vec!["poissonrand(1/0)", "trand(1/0)", "chisqrand(1/0)", "normrand(1/0, 1)", "normrand(0, 1/0)", "normrand(0, sqrt(-1))", "poissonrand(sqrt(-1))"];
+
+    for input in inputs {
+        assert!(Interpreter::default().evaluate(parse(input)).is_err(), "Testing '{input}'");
+    }
+}
+
+#[test]
+/**
+ * Test that sampling from a binomial distribution with more trials than can be sampled fails, rather than panicking
+ */
+fn binomrand_1() {
+    let inputs: Vec<&str> = vec!["binomrand(10^30, 0.5)", "binomrand(2^63, 0.5)", "binomrand(1/0, 0.5)"];
+
+    for input in inputs {
+        assert!(Interpreter::default().evaluate(parse(input)).is_err(), "Testing '{input}'");
+    }
+
+    is_integer_test("binomrand(2^62, 0.5)", 10);
+}
+
+#[test]
+/**
+ * Test that interpreters with the same seed produce the same random sequence
+ */
+fn seed_0() {
+    let inputs: Vec<&str> = vec!["frand()", "rand()", "rrandi(1, 6)", "rfrand(-5, 5)", "normrand(0, 1)", "binomrand(20, 0.3)"];
+
+    let mut first = Interpreter::default();
+    let mut second = Interpreter::default();
+
+    first.seed(1234);
+    second.seed(1234);
+
+    for input in inputs {
+        let first_result = first.evaluate(parse(input)).and_then(Value::into_number);
+        let second_result = second.evaluate(parse(input)).and_then(Value::into_number);
+        assert_eq!(first_result, second_result, "Testing that {input} is reproducible.");
+    }
+}
+
+#[test]
+/**
+ * Test that the seed function restarts the random sequence
+ */
+fn seed_1() {
+    let interpreter = Interpreter::default();
+
+    let mut sequences: Vec<Vec<f64>> = Vec::new();
+
+    for _ in 0..2 {
+        interpreter.evaluate(parse("seed(7)")).and_then(Value::into_number).unwrap();
+
+        let sequence = (0..10)
+            .map(|_| interpreter.evaluate(parse("rrandi(1, 100)")).and_then(Value::into_number).unwrap())
+            .collect();
+
+        sequences.push(sequence);
+    }
+
+    assert_eq!(sequences[0], sequences[1]);
+}
+
+#[test]
+/**
+ * Test that a seeded sequence matches known values, so that results are
+ * the same across platforms and releases
+ */
+fn seed_2() {
     let seq: Vec<(&str, Option<f64>)> = vec![
-        ("$m0", Some(0_f64)),
-        ("$m1", Some(0_f64)),
-        ("$m0: 4", Some(4_f64)),
-        ("$m0", Some(4_f64)),
-        ("$m1: 3", Some(3_f64)),
-        ("$m1", Some(3_f64)),
-        ("$m1: 2", Some(2_f64)),
-        ("$m1", Some(2_f64)),
-        ("$m1 * $m0", Some(8_f64)),
-        ("$m1", Some(2_f64)),
-        ("$m0", Some(4_f64)),
-        ("$m1: $m1 * $m0", Some(8_f64)),
-        ("$m1", Some(8_f64)),
-        ("$m0", Some(4_f64)),
+        ("seed(42)", Some(42_f64)),
+        ("rrandi(1, 6)", Some(2_f64)),
+        ("rrandi(1, 6)", Some(5_f64)),
+        ("rrandi(1, 6)", Some(6_f64)),
+        ("frand()", Some(0.427_516_402_856_519_7_f64)),
+        ("rand()", Some(1_477_863_250_f64))
     ];
     sequence_test(seq);
-}
\ No newline at end of file
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that the seed function rejects seeds that are not non-negative integers
+ */
+fn seed_3() {
+    let input: &str = "seed(-1.5)";
+    panic_test(input);
+}
+
+#[test]
+/**
+ * Test that previewing an expression does not advance the random sequence
+ */
+fn seed_4() {
+    let mut interpreter = Interpreter::default();
+    interpreter.seed(99);
+
+    let (preview, _) = interpreter.evaluate_with_options(parse("frand()"), EvaluateOptions::new(true)).unwrap();
+    let result = interpreter.evaluate(parse("frand()")).unwrap();
+
+    assert_eq!(preview, result);
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that the seed function rejects 2^64, which is one more than the largest seed
+ */
+fn seed_5() {
+    let input: &str = "seed(2^64)";
+    panic_test(input);
+}
+
+#[test]
+/**
+ * Test that dice rolls are integers within the possible totals
+ */
+fn dice_0() {
+    let input: &str = "3d6";
+    in_range_inc_test(input, 3_f64..=18_f64, 100);
+    is_integer_test(input, 100);
+}
+
+#[test]
+/**
+ * Test that keeping and dropping dice only totals the selected dice
+ */
+fn dice_1() {
+    in_range_inc_test("2d20kh1", 1_f64..=20_f64, 100);
+    in_range_inc_test("2d20kl1", 1_f64..=20_f64, 100);
+    in_range_inc_test("4d6dl1", 3_f64..=18_f64, 100);
+    in_range_inc_test("4d6dh3", 1_f64..=6_f64, 100);
+}
+
+#[test]
+/**
+ * Test that the individual rolls are recorded, marking dropped dice as not kept
+ */
+fn dice_2() {
+    let interpreter = Interpreter::default();
+
+    for _ in 0..100 {
+        let result = interpreter.evaluate(parse("4d6dl1 + 2")).and_then(Value::into_number).unwrap();
+        let rolls = interpreter.clone_rolls();
+
+        assert_eq!(rolls.len(), 1);
+
+        let roll = &rolls[0];
+        assert_eq!(roll.label, "4d6dl1");
+        assert_eq!(roll.values.len(), 4);
+        assert_eq!(roll.kept.iter().filter(|kept| !**kept).count(), 1);
+
+        let lowest = roll.values.iter().copied().reduce(f64::min).unwrap();
+        let dropped = roll.values.iter().zip(roll.kept.iter()).find(|(_, kept)| !**kept).unwrap().0;
+        assert_eq!(*dropped, lowest);
+
+        let kept_sum: f64 = roll.values.iter().zip(roll.kept.iter()).filter(|(_, kept)| **kept).map(|(value, _)| *value).sum();
+        assert_eq!(result, kept_sum + 2_f64);
+    }
+}
+
+#[test]
+/**
+ * Test that exploding dice roll an extra die whenever a die rolls its highest value
+ */
+fn dice_3() {
+    let interpreter = Interpreter::default();
+    interpreter.evaluate(parse("seed(3)")).and_then(Value::into_number).unwrap();
+
+    for _ in 0..100 {
+        let result = interpreter.evaluate(parse("2d2!")).and_then(Value::into_number).unwrap();
+        let roll = &interpreter.clone_rolls()[0];
+
+        //Every die that rolled its highest value was followed by another die
+        let exploded = roll.values.iter().filter(|value| **value == 2_f64).count();
+        assert_eq!(roll.values.len(), 2 + exploded);
+        assert_eq!(result, roll.values.iter().sum::<f64>());
+    }
+}
+
+#[test]
+/**
+ * Test that rolls are only kept for the most recently evaluated expression
+ */
+fn dice_4() {
+    let interpreter = Interpreter::default();
+
+    interpreter.evaluate(parse("d20 + d4")).and_then(Value::into_number).unwrap();
+    assert_eq!(interpreter.clone_rolls().len(), 2);
+
+    interpreter.evaluate(parse("2 + 2")).and_then(Value::into_number).unwrap();
+    assert!(interpreter.clone_rolls().is_empty());
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that rolling zero dice is an error
+ */
+fn dice_5() {
+    let input: &str = "0d6";
+    default_test(input, 0_f64);
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that rolling dice with zero sides is an error
+ */
+fn dice_6() {
+    let input: &str = "2d0";
+    default_test(input, 0_f64);
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that exploding single-sided dice is an error
+ */
+fn dice_7() {
+    let input: &str = "2d1!";
+    default_test(input, 0_f64);
+}
+
+#[test]
+/**
+ * Test that choose returns one of its arguments, and records which was chosen
+ */
+fn choose_0() {
+    let interpreter = Interpreter::default();
+
+    for _ in 0..100 {
+        let result = interpreter.evaluate(parse("choose(2, 4, 8)")).and_then(Value::into_number).unwrap();
+        assert!([2_f64, 4_f64, 8_f64].contains(&result), "Testing that {result} is one of the arguments.");
+
+        let roll = &interpreter.clone_rolls()[0];
+        assert_eq!(roll.values, vec![2_f64, 4_f64, 8_f64]);
+        assert_eq!(roll.kept.iter().filter(|kept| **kept).count(), 1);
+    }
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that choosing from no values is an error
+ */
+fn choose_1() {
+    let input: &str = "choose()";
+    default_test(input, 0_f64);
+}
+
+#[test]
+/**
+ * Test that shuffle returns and records a permutation of its arguments
+ */
+fn shuffle_0() {
+    let interpreter = Interpreter::default();
+
+    for _ in 0..100 {
+        let result = interpreter.evaluate(parse("shuffle(1, 2, 3, 4, 5)")).unwrap();
+
+        let roll = &interpreter.clone_rolls()[0];
+        assert_eq!(Value::List(roll.values.clone()), result);
+
+        let mut sorted = roll.values.clone();
+        sorted.sort_by(f64::total_cmp);
+        assert_eq!(sorted, vec![1_f64, 2_f64, 3_f64, 4_f64, 5_f64]);
+    }
+}
+
+#[test]
+/**
+ * Test that previewing dice gives the same rolls as the eventual result
+ */
+fn shuffle_1() {
+    let mut interpreter = Interpreter::default();
+    interpreter.seed(11);
+
+    let (preview, _) = interpreter.evaluate_with_options(parse("shuffle(1, 2, 3) + 3d6"), EvaluateOptions::new(true)).unwrap();
+    let preview_rolls = interpreter.clone_rolls();
+
+    let result = interpreter.evaluate(parse("shuffle(1, 2, 3) + 3d6")).unwrap();
+
+    assert_eq!(preview, result);
+    assert_eq!(preview_rolls, interpreter.clone_rolls());
+}
+
+#[test]
+/**
+ * Test that there must be something to shuffle, as there must be to choose from
+ */
+fn shuffle_2() {
+    let interpreter = Interpreter::default();
+
+    assert!(interpreter.evaluate(parse("shuffle()")).is_err());
+    assert!(interpreter.evaluate(parse("shuffle([])")).is_err());
+    assert!(interpreter.evaluate(parse("choose([])")).is_err());
+    assert_eq!(interpreter.evaluate(parse("shuffle([4])")).unwrap(), Value::List(vec![4_f64]));
+}
+
+#[test]
+/**
+ * Test that lists evaluate to their elements
+ */
+fn list_0() {
+    value_test("[1, 2 + 3, sqrt(16)]", Value::List(vec![1_f64, 5_f64, 4_f64]));
+    value_test("[]", Value::List(Vec::new()));
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that lists cannot contain lists
+ */
+fn list_1() {
+    let input: &str = "[1, [2, 3]]";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that operators are applied element-wise, repeating numbers for each element
+ */
+fn list_2() {
+    let inputs: Vec<(&str, Vec<f64>)> = vec![
+        ("[1, 2, 3] + 1", vec![2_f64, 3_f64, 4_f64]),
+        ("2 * [1, 2, 3]", vec![2_f64, 4_f64, 6_f64]),
+        ("[1, 2, 3] * [4, 5, 6]", vec![4_f64, 10_f64, 18_f64]),
+        ("[1, 2, 3] ^ 2 - 1", vec![0_f64, 3_f64, 8_f64]),
+        ("-[1, 2]", vec![-1_f64, -2_f64]),
+        ("[3, 4]!", vec![6_f64, 24_f64]),
+        ("[10, 20] mod 3", vec![1_f64, 2_f64])
+    ];
+
+    for (input, expected) in inputs {
+        value_test(input, Value::List(expected));
+    }
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that lists of different lengths cannot be combined
+ */
+fn list_3() {
+    let input: &str = "[1, 2, 3] + [1, 2]";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that functions of a fixed number of arguments are applied element-wise
+ */
+fn list_4() {
+    value_test("sqrt([4, 9, 16])", Value::List(vec![2_f64, 3_f64, 4_f64]));
+    value_test("pow(2, [1, 2, 3])", Value::List(vec![2_f64, 4_f64, 8_f64]));
+    value_test("cond([1, 2], 1, 10, 20)", Value::List(vec![10_f64, 20_f64]));
+}
+
+#[test]
+/**
+ * Test that functions of a variable number of arguments accept the elements of lists
+ */
+fn list_5() {
+    let inputs: Vec<(&str, f64)> = vec![
+        ("sum([1, 2, 3, 4])", 10_f64),
+        ("mean([1, 2, 3, 4])", 2.5_f64),
+        ("max([3, 9, 2])", 9_f64),
+        ("min([3, 9], 1)", 1_f64),
+        ("sum(1..100)", 5050_f64),
+        ("add([1, 2], 3)", 6_f64)
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+}
+
+#[test]
+/**
+ * Test that lists can be indexed from the start, or from the end with negative indexes
+ */
+fn list_6() {
+    let inputs: Vec<(&str, f64)> = vec![
+        ("[5, 6, 7][0]", 5_f64),
+        ("[5, 6, 7][2]", 7_f64),
+        ("[5, 6, 7][-1]", 5_f64 + 2_f64),
+        ("[5, 6, 7][1 + 1] * 2", 14_f64),
+        ("(1..10)[4]", 5_f64)
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that indexing past the end of a list is an error
+ */
+fn list_7() {
+    let input: &str = "[1, 2, 3][3]";
+    value_test(input, Value::default());
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that numbers cannot be indexed
+ */
+fn list_8() {
+    let input: &str = "5[0]";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that ranges include both ends, and can count down
+ */
+fn range_0() {
+    value_test("1..5", Value::List(vec![1_f64, 2_f64, 3_f64, 4_f64, 5_f64]));
+    value_test("3..1", Value::List(vec![3_f64, 2_f64, 1_f64]));
+    value_test("1..1 + 2", Value::List(vec![1_f64, 2_f64, 3_f64]));
+    value_test("0.5..2", Value::List(vec![0.5_f64, 1.5_f64]));
+}
+
+#[test]
+/**
+ * Test that the range function can take a step
+ */
+fn range_1() {
+    let expected: Vec<f64> = (0..=10).map(|i| i as f64 * 0.1_f64).collect();
+
+    value_test("range(0, 1, 0.1)", Value::List(expected));
+    value_test("range(10, 0, -5)", Value::List(vec![10_f64, 5_f64, 0_f64]));
+    value_test("range(0, 4)", Value::List(vec![0_f64, 1_f64, 2_f64, 3_f64, 4_f64]));
+    value_test("range(0, 1, -1)", Value::List(Vec::new()));
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that a range cannot have a step of zero
+ */
+fn range_2() {
+    let input: &str = "range(0, 1, 0)";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that memory and history can hold lists
+ */
+fn list_memory_0() {
+    let interpreter = Interpreter::default();
+
+    interpreter.evaluate(parse("$m0: [1, 2, 3]")).unwrap();
+    interpreter.evaluate(parse("$m0 * 2")).unwrap();
+
+    assert_eq!(interpreter.clone_mem()[0], Value::List(vec![1_f64, 2_f64, 3_f64]));
+    assert_eq!(interpreter.evaluate(parse("$0[1]")).unwrap(), Value::Number(4_f64));
+    assert_eq!(interpreter.clone_stack(), vec![
+        Value::List(vec![1_f64, 2_f64, 3_f64]),
+        Value::List(vec![2_f64, 4_f64, 6_f64]),
+        Value::Number(4_f64)
+    ]);
+}
+
+#[test]
+/**
+ * Test that a list of lists of the same length is a matrix
+ */
+fn matrix_0() {
+    value_test("[[1, 2], [3, 4]]", matrix(vec![vec![1_f64, 2_f64], vec![3_f64, 4_f64]]));
+    value_test("[[1, 2, 3]]", matrix(vec![vec![1_f64, 2_f64, 3_f64]]));
+    value_test("[1..3, [4, 5, 6]]", matrix(vec![vec![1_f64, 2_f64, 3_f64], vec![4_f64, 5_f64, 6_f64]]));
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that the rows of a matrix must have the same length
+ */
+fn matrix_1() {
+    let input: &str = "[[1, 2], [3]]";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that multiplying matrices is matrix multiplication, whereas other operators are element-wise
+ */
+fn matrix_2() {
+    value_test("[[1, 2], [3, 4]] * [[5, 6], [7, 8]]", matrix(vec![vec![19_f64, 22_f64], vec![43_f64, 50_f64]]));
+    value_test("[[1, 2, 3]] * [[1], [2], [3]]", matrix(vec![vec![14_f64]]));
+    value_test("[[1, 2], [3, 4]] + [[5, 6], [7, 8]]", matrix(vec![vec![6_f64, 8_f64], vec![10_f64, 12_f64]]));
+    value_test("2 * [[1, 2], [3, 4]] - 1", matrix(vec![vec![1_f64, 3_f64], vec![5_f64, 7_f64]]));
+    value_test("sqrt([[1, 4], [9, 16]])", matrix(vec![vec![1_f64, 2_f64], vec![3_f64, 4_f64]]));
+    value_test("sum([[1, 2], [3, 4]])", Value::Number(10_f64));
+}
+
+#[test]
+/**
+ * Test that multiplying a matrix and a list treats the list as a vector
+ */
+fn matrix_3() {
+    value_test("[[1, 2], [3, 4]] * [1, 1]", Value::List(vec![3_f64, 7_f64]));
+    value_test("[1, 1] * [[1, 2], [3, 4]]", Value::List(vec![4_f64, 6_f64]));
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that matrices of incompatible dimensions cannot be multiplied
+ */
+fn matrix_4() {
+    let input: &str = "[[1, 2], [3, 4]] * [[1, 2, 3]]";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that square matrices can be raised to integer powers
+ */
+fn matrix_5() {
+    value_test("[[1, 1], [1, 0]] ^ 10", matrix(vec![vec![89_f64, 55_f64], vec![55_f64, 34_f64]]));
+    value_test("[[2, 0], [0, 4]] ^ 0", matrix(vec![vec![1_f64, 0_f64], vec![0_f64, 1_f64]]));
+    value_test_within("[[2, 0], [0, 4]] ^ -1", matrix(vec![vec![0.5_f64, 0_f64], vec![0_f64, 0.25_f64]]), 1e-12);
+}
+
+#[test]
+/**
+ * Test that indexing a matrix gives its rows
+ */
+fn matrix_6() {
+    value_test("[[1, 2], [3, 4]][1]", Value::List(vec![3_f64, 4_f64]));
+    value_test("[[1, 2], [3, 4]][-1][0]", Value::Number(3_f64));
+}
+
+#[test]
+/**
+ * Test that transpose swaps rows and columns, and turns lists into columns
+ */
+fn matrix_7() {
+    value_test("transpose([[1, 2, 3], [4, 5, 6]])", matrix(vec![vec![1_f64, 4_f64], vec![2_f64, 5_f64], vec![3_f64, 6_f64]]));
+    value_test("transpose([1, 2])", matrix(vec![vec![1_f64], vec![2_f64]]));
+}
+
+#[test]
+/**
+ * Test that the determinant, inverse and rank are found
+ */
+fn matrix_8() {
+    value_test_within("det([[1, 2], [3, 4]])", Value::Number(-2_f64), 1e-12);
+    value_test_within("det([[2, 0, 1], [1, 3, 2], [1, 1, 2]])", Value::Number(6_f64), 1e-12);
+    value_test("det([[1, 2], [2, 4]])", Value::Number(0_f64));
+    value_test_within("inv([[4, 7], [2, 6]])", matrix(vec![vec![0.6_f64, -0.7_f64], vec![-0.2_f64, 0.4_f64]]), 1e-12);
+    value_test_within("[[4, 7], [2, 6]] * inv([[4, 7], [2, 6]])", matrix(vec![vec![1_f64, 0_f64], vec![0_f64, 1_f64]]), 1e-12);
+    value_test("rank([[1, 2], [2, 4]])", Value::Number(1_f64));
+    value_test("rank([[1, 2, 3], [4, 5, 6], [7, 8, 10]])", Value::Number(3_f64));
+    value_test("rank([[1, 2, 3], [2, 4, 6]])", Value::Number(1_f64));
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that singular matrices have no inverse
+ */
+fn matrix_9() {
+    let input: &str = "inv([[1, 2], [2, 4]])";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that linear systems are solved for both vectors and matrices
+ */
+fn matrix_10() {
+    value_test_within("solve_linear([[2, 1], [1, 3]], [3, 5])", Value::List(vec![0.8_f64, 1.4_f64]), 1e-12);
+    value_test_within("solve_linear([[1, 1, 1], [0, 2, 5], [2, 5, -1]], [6, -4, 27])", Value::List(vec![5_f64, 3_f64, -2_f64]), 1e-12);
+    value_test_within("solve_linear([[2, 0], [0, 4]], [[2, 4], [4, 8]])", matrix(vec![vec![1_f64, 2_f64], vec![1_f64, 2_f64]]), 1e-12);
+}
+
+#[test]
+/**
+ * Test that the real eigenvalues of small matrices are found, from highest to lowest
+ */
+fn matrix_11() {
+    value_test_within("eigvals([[2, 0], [0, 3]])", Value::List(vec![3_f64, 2_f64]), 1e-9);
+    value_test_within("eigvals([[4, 1], [2, 3]])", Value::List(vec![5_f64, 2_f64]), 1e-9);
+    value_test_within("eigvals([[2, -1, 0], [-1, 2, -1], [0, -1, 2]])", Value::List(vec![2_f64 + 2_f64.sqrt(), 2_f64, 2_f64 - 2_f64.sqrt()]), 1e-9);
+    value_test_within("eigvals([[1, 2, 3], [0, 4, 5], [0, 0, 6]])", Value::List(vec![6_f64, 4_f64, 1_f64]), 1e-9);
+    value_test_within("eigvals([[6, -11, 6], [1, 0, 0], [0, 1, 0]])", Value::List(vec![3_f64, 2_f64, 1_f64]), 1e-9);
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that matrices with complex eigenvalues fail
+ */
+fn matrix_12() {
+    let input: &str = "eigvals([[0, -1], [1, 0]])";
+    value_test(input, Value::default());
+}
+
+#[test]
+/**
+ * Test that matrices are displayed with their columns aligned
+ */
+fn matrix_13() {
+    let result = Interpreter::default().evaluate(parse("[[1, -20], [300, 4]]")).unwrap();
+    assert_eq!(result.to_string(), "[[  1, -20],\n [300,   4]]");
+}
+
+#[test]
+/**
+ * Test that the eigenvalues of dense non-symmetric matrices sum to the trace and multiply to the determinant
+ */
+fn matrix_14() {
+    value_test_within("eigvals([[4, 1, 2], [0, 3, 0], [1, 0, 5]])", Value::List(vec![6_f64, 3_f64, 3_f64]), 1e-9);
+
+    for (input, trace) in [("[[1, 2, 3], [4, 5, 6], [7, 0, 9]]", 15_f64), ("[[2, 7, 1, 8], [2, 8, 1, 8], [4, 5, 9, 0], [4, 5, 2, 3]]", 22_f64)] {
+        let eigenvalues = Interpreter::default().evaluate(parse(format!("eigvals({input})").as_str())).unwrap().flatten();
+        let determinant = Interpreter::default().evaluate(parse(format!("det({input})").as_str())).and_then(Value::into_number).unwrap();
+
+        assert!((eigenvalues.iter().sum::<f64>() - trace).abs() < 1e-9, "Eigenvalues {eigenvalues:?} of {input} should sum to {trace}.");
+        assert!((eigenvalues.iter().product::<f64>() - determinant).abs() < 1e-9, "Eigenvalues {eigenvalues:?} of {input} should multiply to {determinant}.");
+    }
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_0() {
+    let expected: f64 = 1_f64;
+    let input: &str = "5 mod 4";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_1() {
+    let expected: f64 = 4_f64;
+    let input: &str = "4 mod 5";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_2() {
+    let expected: f64 = 0_f64;
+    let input: &str = "5 mod 5";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_3() {
+    let expected: f64 = 2_f64;
+    let input: &str = "-3 mod 5";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_4() {
+    let expected: f64 = -2_f64;
+    let input: &str = "3 mod -5";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_5() {
+    let expected: f64 = -3_f64;
+    let input: &str = "-3 mod -5";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_6() {
+    let expected: f64 = 0.5_f64;
+    let input: &str = "0.5 mod 2.1";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_7() {
+    let expected: f64 = 0_f64;
+    let input: &str = "0.5 mod 0.25";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_8() {
+    let input: &str = "0.5 mod 0";
+    nan_test(input)
+}
+
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_9() {
+    let expected: f64 = -0.5_f64;
+    let input: &str = "0.5 mod -1";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_10() {
+    let expected: f64 = 0_f64;
+    let input: &str = "3 mod -1";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_11() {
+    let expected: f64 = -0.8_f64;
+    let input: &str = "5.2 mod -3";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_12() {
+    let expected: f64 = 0.8_f64;
+    let input: &str = "-5.2 mod 3";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_13() {
+    let expected: f64 = -2.2_f64;
+    let input: &str = "-5.2 mod -3";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that the modulo function outputs as expected
+ */
+fn mod_14() {
+    let expected: f64 = 2.2_f64;
+    let input: &str = "5.2 mod 3";
+    default_test(input, expected);
+}
+
+#[test]
+/**
+ * Test that history access evaluates as expected
+ */
+fn history_0() {
+    let seq: Vec<(&str, Option<f64>)> = vec![
+        ("2", Some(2_f64)),
+        ("$0", Some(2_f64)),
+        ("3", Some(3_f64)),
+        ("$0", Some(3_f64)),
+        ("$1", Some(2_f64)),
+        ("$1 + $0 * 5", Some(13_f64)),
+    ];
+    sequence_test(seq);
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that history access evaluates as expected
+ */
+fn history_1() {
+    let seq: Vec<(&str, Option<f64>)> = vec![
+        ("2", Some(2_f64)),
+        ("$0", Some(2_f64)),
+        ("3", Some(3_f64)),
+        ("$0", Some(3_f64)),
+        ("$1", Some(2_f64)),
+        ("$5", None),
+    ];
+    sequence_test(seq);
+}
+
+#[test]
+/**
+ * Test that memory access/assignment evaluates as expected
+ */
+fn memory_access_0() {
+    let seq: Vec<(&str, Option<f64>)> = vec![
+        ("$m0", Some(0_f64)),
+        ("$m1", Some(0_f64)),
+        ("$m0: 4", Some(4_f64)),
+        ("$m0", Some(4_f64)),
+        ("$m1: 3", Some(3_f64)),
+        ("$m1", Some(3_f64)),
+        ("$m1: 2", Some(2_f64)),
+        ("$m1", Some(2_f64)),
+        ("$m1 * $m0", Some(8_f64)),
+        ("$m1", Some(2_f64)),
+        ("$m0", Some(4_f64)),
+        ("$m1: $m1 * $m0", Some(8_f64)),
+        ("$m1", Some(8_f64)),
+        ("$m0", Some(4_f64)),
+    ];
+    sequence_test(seq);
+}
+
+#[test]
+/**
+ * Test that variables evaluate to their assigned values
+ */
+fn variable_0() {
+    let mut interpreter = Interpreter::default();
+    interpreter.set_variable("price", Value::Number(20_f64));
+    interpreter.set_variable("rates", Value::List(vec![0.1_f64, 0.2_f64]));
+
+    assert_eq!(interpreter.evaluate(parse("price * (1 + rates)")).unwrap(), Value::List(vec![22_f64, 24_f64]));
+    assert_eq!(interpreter.evaluate(parse("price max 30")).unwrap(), Value::Number(30_f64));
+
+    interpreter.set_variable("price", Value::Number(5_f64));
+    assert_eq!(interpreter.evaluate(parse("price")).unwrap(), Value::Number(5_f64));
+
+    assert_eq!(interpreter.remove_variable("price"), Some(Value::Number(5_f64)));
+    assert!(interpreter.evaluate(parse("price")).is_err());
+}
+
+#[test]
+/**
+ * Test that variables are case sensitive, and must be assigned before use
+ */
+fn variable_1() {
+    let mut interpreter = Interpreter::default();
+    interpreter.set_variable("x", Value::Number(1_f64));
+
+    assert!(interpreter.evaluate(parse("X")).is_err());
+    assert!(interpreter.evaluate(parse("y + 1")).is_err());
+    assert!(interpreter.evaluate(parse("sqrt")).is_err());
+}
+
+#[test]
+/**
+ * Test that user defined functions bind their arguments to their parameters
+ */
+fn user_function_0() {
+    let mut interpreter = Interpreter::default();
+    interpreter.define_function("hyp", UserFunction::new(vec!["a".to_string(), "b".to_string()], body("sqrt(a^2 + b^2)"))).unwrap();
+    interpreter.define_function("scale", UserFunction::new(vec!["x".to_string()], body("x * factor"))).unwrap();
+    interpreter.set_variable("factor", Value::Number(10_f64));
+    interpreter.set_variable("a", Value::Number(-1_f64));
+
+    assert_eq!(interpreter.evaluate(parse("hyp(3, 4)")).unwrap(), Value::Number(5_f64));
+    assert_eq!(interpreter.evaluate(parse("(3 hyp 4) + HYP(6, 8)")).unwrap(), Value::Number(15_f64));
+    assert_eq!(interpreter.evaluate(parse("scale([1, 2])")).unwrap(), Value::List(vec![10_f64, 20_f64]));
+    assert_eq!(interpreter.evaluate(parse("scale(hyp(3, 4))")).unwrap(), Value::Number(50_f64));
+
+    //Variables shadowed by parameters are restored after the call
+    assert_eq!(interpreter.evaluate(parse("a")).unwrap(), Value::Number(-1_f64));
+    assert_eq!(interpreter.get_variable("b"), None);
+
+    assert!(interpreter.evaluate(parse("hyp(3)")).is_err());
+}
+
+#[test]
+/**
+ * Test that user defined functions cannot replace built in functions or call themselves without limit
+ */
+fn user_function_1() {
+    let mut interpreter = Interpreter::default();
+
+    assert!(interpreter.define_function("Sqrt", UserFunction::new(vec!["x".to_string()], body("x"))).is_err());
+    assert!(interpreter.define_function("f", UserFunction::new(vec!["x".to_string(), "x".to_string()], body("x"))).is_err());
+
+    interpreter.define_function("f", UserFunction::new(vec!["x".to_string()], body("f(x + 1)"))).unwrap();
+    assert!(interpreter.evaluate(parse("f(1)")).is_err());
+
+    interpreter.define_function("F", UserFunction::new(vec!["x".to_string()], body("x + 1"))).unwrap();
+    assert_eq!(interpreter.evaluate(parse("f(1)")).unwrap(), Value::Number(2_f64));
+    assert_eq!(interpreter.get_user_funcs().len(), 1);
+}
+
+#[test]
+/**
+ * Test that trigonometric functions take degrees, and their inverses return degrees, in degrees mode
+ */
+fn angle_mode_0() {
+    const THRESHOLD: f64 = 1e-12_f64;
+
+    let mut interpreter = Interpreter::default();
+    interpreter.set_angle_mode(AngleMode::Degrees);
+
+    let tests: Vec<(&str, f64)> = vec![
+        ("sin(30)", 0.5_f64),
+        ("cos(60)", 0.5_f64),
+        ("tan(45)", 1_f64),
+        ("sec(60)", 2_f64),
+        ("asin(0.5)", 30_f64),
+        ("acot(1)", 45_f64),
+        ("atan(1) * 4", 180_f64),
+        ("sinh(0)", 0_f64),
+        ("sqrt(16)", 4_f64)
+    ];
+
+    for (input, expected) in tests {
+        let result = interpreter.evaluate(parse(input)).and_then(Value::into_number).unwrap();
+        assert!((result - expected).abs() < THRESHOLD, "Testing equality of {result} and {expected} for '{input}'.");
+    }
+
+    assert_eq!(interpreter.evaluate(parse("sin([0, 90])")).unwrap(), Value::List(vec![0_f64, 1_f64]));
+
+    interpreter.set_angle_mode(AngleMode::Radians);
+    let result = interpreter.evaluate(parse("sin(pi / 2)")).and_then(Value::into_number).unwrap();
+    assert!((result - 1_f64).abs() < THRESHOLD);
+}
+
+#[test]
+/**
+ * Test that every built-in function is described, names as many parameters as it takes, and has an example which evaluates
+ */
+fn function_info_0() {
+    let interpreter = Interpreter::default();
+
+    for (name, function) in interpreter.get_funcs() {
+        let info = function.info;
+
+        assert_ne!(info.category, FunctionCategory::Other, "Testing category of '{name}'.");
+        assert!(!info.description.is_empty(), "Testing description of '{name}'.");
+        assert!(info.example.to_ascii_uppercase().starts_with(name.as_str()), "Testing example of '{name}' calls it.");
+
+        let expected_params = match function.args {
+            FunctionArgs::None(_) | FunctionArgs::RandomNone(_) => Some(0),
+            FunctionArgs::One(_) | FunctionArgs::RandomOne(_) | FunctionArgs::ValueOne(_) => Some(1),
+            FunctionArgs::Two(_) | FunctionArgs::RandomTwo(_) | FunctionArgs::ValueTwo(_) => Some(2),
+            FunctionArgs::Three(_) => Some(3),
+            FunctionArgs::Four(_) => Some(4),
+            _ => None
+        };
+
+        if let Some(expected_params) = expected_params {
+            assert_eq!(info.params.len(), expected_params, "Testing parameters of '{name}'.");
+        }
+
+        if let Err(err) = interpreter.evaluate(parse(info.example)) {
+            panic!("Testing example of '{name}' evaluates: {err}");
+        }
+    }
+}
+
+#[test]
+/**
+ * Test that functions are found by their name, description or category, in order of category and then name
+ */
+fn function_info_1() {
+    let interpreter = Interpreter::default();
+
+    let names = |query: &str| -> Vec<String> {
+        interpreter.search_funcs(query).into_iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    };
+
+    assert_eq!(names("hyperbolic"), vec!["ACOSH", "ASINH", "ATANH", "COSH", "SINH", "TANH"]);
+    assert_eq!(names("SQRT"), vec!["SQRT"]);
+    assert_eq!(names("square root"), vec!["SQRT"]);
+    assert_eq!(names("eigen"), vec!["EIGVALS"]);
+    assert!(names("no such function").is_empty());
+    assert_eq!(names("").len(), interpreter.get_funcs().len());
+
+    let sqrt = interpreter.get_func_by_name("sqrt").unwrap();
+    assert_eq!(sqrt.to_string(), "(a)");
+    assert_eq!(interpreter.get_func_by_name("normpdf").unwrap().to_string(), "(x, mu, sigma)");
+    assert_eq!(interpreter.get_func_by_name("max").unwrap().to_string(), "(...a)");
+    assert_eq!(interpreter.get_func_by_name("frand").unwrap().to_string(), "()");
+}
+
+#[test]
+/**
+ * Test that the list of functions in the README is the one generated from the functions' descriptions
+ */
+fn function_info_2() {
+    let readme = include_str!("../../../../README.md");
+    let generated = Interpreter::default().function_list_markdown();
+
+    assert!(readme.contains(&generated), "The README's list of functions is out of date; replace it with the output of 'console functions'.");
+}
+
+#[test]
+/**
+ * Test the bitwise functions, including in infix notation and applied to lists
+ */
+fn bitwise_0() {
+    let tests: Vec<(&str, f64)> = vec![
+        ("and(0b1100, 0b1010)", 8_f64),
+        ("or(0b1100, 0b1010)", 14_f64),
+        ("xor(0b1100, 0b1010)", 6_f64),
+        ("not(0)", -1_f64),
+        ("not(0xFF)", -256_f64),
+        ("shl(0x0F, 4)", 240_f64),
+        ("shr(0xF0, 4)", 15_f64),
+        ("shr(-8, 1)", -4_f64),
+        ("shl(8, -1)", 4_f64),
+        ("shl(1, 64)", 0_f64),
+        ("shr(-1, 100)", -1_f64),
+        ("0xF0 or 0x0F", 255_f64),
+        ("0o17 and 0b101", 5_f64)
+    ];
+
+    for (input, expected) in tests {
+        default_test(input, expected);
+    }
+
+    value_test("shl([1, 2, 3], 1)", Value::List(vec![2_f64, 4_f64, 6_f64]));
+
+    for input in ["and(1.5, 1)", "not(2 ^ 60)", "or(1, 0 / 0)"] {
+        assert!(Interpreter::default().evaluate(parse(input)).is_err(), "Testing '{input}'");
+    }
+}
+
+#[test]
+/**
+ * Test that a table evaluates the same expression for each value of its variable, keeping the results that fail
+ */
+fn table_0() {
+    let mut interpreter = Interpreter::default();
+    interpreter.set_variable("x", Value::Number(-1_f64));
+
+    let rows = interpreter.evaluate_table(&body("sqrt(x) * 2"), "x", -1_f64, 4_f64, 2.5_f64).unwrap();
+    let xs: Vec<f64> = rows.iter().map(|(x, _)| *x).collect();
+
+    assert_eq!(xs, vec![-1_f64, 1.5_f64, 4_f64]);
+    assert!(rows[0].1.as_ref().unwrap().clone().into_number().unwrap().is_nan());
+    assert_eq!(rows[2].1.as_ref().unwrap(), &Value::Number(4_f64));
+
+    let rows = interpreter.evaluate_table(&body("1 / [x, y]"), "x", 0_f64, 0.2_f64, 0.1_f64).unwrap();
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|(_, result)| result.is_err()));
+
+    //The variable is restored afterwards
+    assert_eq!(interpreter.get_variable("x"), Some(Value::Number(-1_f64)));
+
+    assert!(interpreter.evaluate_table(&body("x"), "x", 0_f64, 1_f64, 0_f64).is_err());
+    assert!(interpreter.evaluate_table(&body("x"), "x", 0_f64, 1e9_f64, 1_f64).is_err());
+    assert!(interpreter.evaluate_table(&body("x"), "x", 1_f64, 0_f64, 1_f64).unwrap().is_empty());
+}
+
+#[test]
+/**
+ * Test that TABLE takes its expression and variable unevaluated
+ */
+fn table_1() {
+    let interpreter = Interpreter::default();
+
+    assert_eq!(interpreter.evaluate(parse("TABLE(x^2, x, 0, 3, 1)")).unwrap(), matrix(vec![vec![0_f64, 0_f64], vec![1_f64, 1_f64], vec![2_f64, 4_f64], vec![3_f64, 9_f64]]));
+    assert_eq!(interpreter.evaluate(parse("table(t * 2 + 1, t, 1, 0, -0.5)")).unwrap(), matrix(vec![vec![1_f64, 3_f64], vec![0.5_f64, 2_f64], vec![0_f64, 1_f64]]));
+    assert_eq!(interpreter.evaluate(parse("TABLE(n, n, 2 - 1, sqrt(4), 1)")).unwrap(), matrix(vec![vec![1_f64, 1_f64], vec![2_f64, 2_f64]]));
+    assert_eq!(interpreter.get_variable("x"), None);
+
+    assert!(interpreter.evaluate(parse("TABLE(x^2, x, 0, 3)")).is_err());
+    assert!(interpreter.evaluate(parse("TABLE(x^2, 2, 0, 3, 1)")).is_err());
+    assert!(interpreter.evaluate(parse("TABLE(x^2, x, 3, 0, 1)")).is_err());
+    assert!(interpreter.evaluate(parse("TABLE([x, x], x, 0, 3, 1)")).is_err());
+    assert!(interpreter.evaluate(parse("TABLE(y, x, 0, 3, 1)")).is_err());
+}
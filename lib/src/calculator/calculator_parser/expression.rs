@@ -2,8 +2,12 @@ use std::fmt::Display;
 
 use super::terminal::{Terminal, terminals};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+mod canonical;
+pub(crate) mod precedence;
+mod typeset;
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Constant {
     Pi,
     E
@@ -50,6 +54,8 @@ impl Display for Constant {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Token {
     OpAdd,
     OpSub,
@@ -60,6 +66,9 @@ pub enum Token {
     OpFac,
     OpParO,
     OpParC,
+    OpListO,
+    OpListC,
+    OpRange,
     Delimiter,
     OpSetMem,
     Number(f64),
@@ -81,6 +90,9 @@ impl Token {
             Token::OpFac => &terminals::OP_FAC,
             Token::OpParO => &terminals::OP_PAR_O,
             Token::OpParC => &terminals::OP_PAR_C,
+            Token::OpListO => &terminals::OP_LIST_O,
+            Token::OpListC => &terminals::OP_LIST_C,
+            Token::OpRange => &terminals::OP_RANGE,
             Token::Delimiter => &terminals::DELIMITER,
             Token::OpSetMem => &terminals::OP_SETMEM,
             Token::Number(_) => &terminals::DIGIT,
@@ -112,6 +124,8 @@ impl Display for Token {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Expr {
     None,
     ExprPrime(Box<ExprPrime>)
@@ -131,11 +145,17 @@ impl Display for Expr {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum ExprPrime {
     Number(NumberToken),
+    Dice(DiceToken),
     History(HistoryToken),
     Func(Func),
     Id(IdToken),
+    List(Vec<ExprPrime>),
+    Range(Box<ExprPrime>, Box<ExprPrime>),
+    Index(Box<ExprPrime>, Box<ExprPrime>),
     AccessMem(MemoryToken),
     StoreMem(MemoryToken, Box<ExprPrime>),
     UnopPrefixesExpression(Vec<UnopPrefix>, Box<ExprPrime>),
@@ -154,12 +174,14 @@ impl Display for ExprPrime {
             UnopSuffix,
             Parentheses,
             BinaryInfix,
+            Range,
+            Index,
             StoreMem
         }
 
         fn subexpr_str(subexpr: &ExprPrime, _parent_type: SubexprStrParentType) -> String {
-            if matches!(_parent_type, SubexprStrParentType::StoreMem) || !matches!(subexpr, ExprPrime::Number(_) | ExprPrime::Func(_) | ExprPrime::Id(_) | ExprPrime::ParenthesesExpression(_) 
-                | ExprPrime::History(_) | ExprPrime::AccessMem(_)) {
+            if matches!(_parent_type, SubexprStrParentType::StoreMem) || !matches!(subexpr, ExprPrime::Number(_) | ExprPrime::Dice(_) | ExprPrime::Func(_) | ExprPrime::Id(_) | ExprPrime::ParenthesesExpression(_) 
+                | ExprPrime::History(_) | ExprPrime::AccessMem(_) | ExprPrime::List(_) | ExprPrime::Index(_, _)) {
 
                 // match parent_type {
                 //     SubexprStrParentType::UnopPrefixesExpression => {
@@ -180,7 +202,7 @@ impl Display for ExprPrime {
                 //     }
                 // }
 
-                format!("[{subexpr}]")
+                format!("{}{subexpr}{}", Token::OpParO, Token::OpParC)
             }
             else {
                 subexpr.to_string()
@@ -189,9 +211,19 @@ impl Display for ExprPrime {
 
         let to_print = match self {
             Self::Number(n) => n.get_token().to_string(),
+            Self::Dice(d) => d.to_string(),
             Self::History(n) => format!("{}{}", n.get_token(), n.value),
             Self::Func(func) => func.to_string(),
             Self::Id(id) => id.get_token().to_string(),
+            Self::List(elements) => {
+                let element_strings: Vec<String> = elements.iter()
+                    .map(|element| element.to_string())
+                    .collect();
+
+                format!("{}{}{}", Token::OpListO, element_strings.join(", "), Token::OpListC)
+            },
+            Self::Range(start, end) => format!("{}{}{}", subexpr_str(start, SubexprStrParentType::Range), Token::OpRange, subexpr_str(end, SubexprStrParentType::Range)),
+            Self::Index(subexpr, index) => format!("{}{}{}{}", subexpr_str(subexpr, SubexprStrParentType::Index), Token::OpListO, index, Token::OpListC),
             Self::UnopPrefixesExpression(prefix, expr) => {
                 let prefix_strings: Vec<String> = prefix.iter()
                     .map(|op| op.to_string())
@@ -243,7 +275,45 @@ impl Display for ExprPrime {
     }
 }
 
+impl ExprPrime {
+    /**
+     * The names of the variables used in the expression, in the order they first appear, without repeats
+     */
+    pub fn variables(&self) -> Vec<&str> {
+        fn collect<'a>(expr: &'a ExprPrime, names: &mut Vec<&'a str>) {
+            match expr {
+                ExprPrime::Id(id) => {
+                    if !names.contains(&id.value.as_str()) {
+                        names.push(&id.value);
+                    }
+                },
+                ExprPrime::Func(Func::FuncWithArgs(_, args)) | ExprPrime::List(args) => args.iter().for_each(|arg| collect(arg, names)),
+                ExprPrime::Range(first, second) | ExprPrime::Index(first, second) => {
+                    collect(first, names);
+                    collect(second, names);
+                },
+                ExprPrime::StoreMem(_, subexpr) | ExprPrime::UnopPrefixesExpression(_, subexpr) | ExprPrime::UnopSuffixesExpression(subexpr, _) | ExprPrime::ParenthesesExpression(subexpr) => collect(subexpr, names),
+                ExprPrime::BinaryInfixExpression(first, children) => {
+                    collect(first, names);
+                    children.iter().for_each(|(_, child)| collect(child, names));
+                },
+                ExprPrime::BinaryInfixFunctionExpression(first, children) => {
+                    collect(first, names);
+                    children.iter().for_each(|(_, child)| collect(child, names));
+                },
+                ExprPrime::Number(_) | ExprPrime::Dice(_) | ExprPrime::History(_) | ExprPrime::AccessMem(_) | ExprPrime::Func(_) => {}
+            }
+        }
+
+        let mut names = Vec::new();
+        collect(self, &mut names);
+        names
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Func {
     EmptyFunc(IdToken),
     ConstantFunc(Constant),
@@ -269,6 +339,7 @@ impl Display for Func {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinopInfix {
     Exp,
     Mult,
@@ -316,6 +387,7 @@ impl Display for BinopInfix {
 
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinopInfix2 {
     Add,
     Sub
@@ -350,6 +422,7 @@ impl Display for BinopInfix2 {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinopInfix1 {
     Mult,
     Div,
@@ -387,6 +460,7 @@ impl Display for BinopInfix1 {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinopInfix0 {
     Exp
 }
@@ -418,6 +492,7 @@ impl Display for BinopInfix0 {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinopInfixPriority {
     Priority0(BinopInfix0),
     Priority1(BinopInfix1),
@@ -448,6 +523,7 @@ impl BinopInfix {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnopPrefix {
     Neg
 }
@@ -479,6 +555,7 @@ impl Display for UnopPrefix {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnopSuffix {
     Fac
 }
@@ -510,38 +587,78 @@ impl Display for UnopSuffix {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpAddToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpSubToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpMultToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpDivToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpRemToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpExpToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpFacToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpParOToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpParCToken {}
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DelimiterToken {}
-#[derive(Debug, PartialEq, Clone, Copy)]
+/**
+ * Numbers are equal if their values are, however they were written
+ */
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NumberToken {
-    pub value: f64
+    #[cfg_attr(feature = "serde", serde(with = "crate::calculator::calculator_interpreter::interpreter::value::float_serde"))]
+    pub value: f64,
+    /**
+     * The number as it was written, if it was parsed, i.e. '0xFF' or '1.50'. It isn't serialized, as it doesn't change the number.
+     */
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub lexeme: Option<String>
 }
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiceToken {
+    pub count: usize,
+    pub sides: usize,
+    pub explode: bool,
+    pub selection: Option<DiceSelection>
+}
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum DiceSelection {
+    KeepHighest(usize),
+    KeepLowest(usize),
+    DropHighest(usize),
+    DropLowest(usize)
+}
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HistoryToken {
     pub value: usize
 }
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MemoryToken {
     pub value: usize
 }
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IdToken {
     pub value: String
 }
@@ -870,7 +987,17 @@ impl TryFrom<Token> for DelimiterToken {
 impl NumberToken {
     pub fn new(value: f64) -> Self {
         Self {
-            value
+            value,
+            lexeme: None
+        }
+    }
+    /**
+     * A number parsed from the text it was written as
+     */
+    pub fn with_lexeme(value: f64, lexeme: &str) -> Self {
+        Self {
+            value,
+            lexeme: Some(String::from(lexeme))
         }
     }
     pub fn get_token(&self) -> Token {
@@ -878,6 +1005,12 @@ impl NumberToken {
     }
 }
 
+impl PartialEq for NumberToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
 impl Default for NumberToken {
     fn default() -> Self {
         Self::new(0_f64)
@@ -895,12 +1028,60 @@ impl TryFrom<Token> for NumberToken {
 
 	fn try_from(value: Token) -> Result<Self, Self::Error> {
 		match value {
-			Token::Number(value) => Ok(NumberToken { value }),
+			Token::Number(value) => Ok(NumberToken::new(value)),
 			_ => Err("The passed value is not an Number token.")
 		}
 	}
 }
 
+impl DiceToken {
+    pub fn new(count: usize, sides: usize, explode: bool, selection: Option<DiceSelection>) -> Self {
+        Self {
+            count,
+            sides,
+            explode,
+            selection
+        }
+    }
+}
+
+impl Default for DiceToken {
+    fn default() -> Self {
+        Self::new(1_usize, 6_usize, false, None)
+    }
+}
+
+impl Display for DiceToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let explode = if self.explode {
+            terminals::DICE_EXPLODE.to_string()
+        }
+        else {
+            String::from("")
+        };
+
+        let selection = match self.selection {
+            Some(selection) => selection.to_string(),
+            None => String::from("")
+        };
+
+        f.write_fmt(format_args!("{}{}{}{explode}{selection}", self.count, terminals::DICE.to_string(), self.sides))
+    }
+}
+
+impl Display for DiceSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (action, end, n) = match self {
+            Self::KeepHighest(n) => (&*terminals::DICE_KEEP, &*terminals::DICE_HIGHEST, n),
+            Self::KeepLowest(n) => (&*terminals::DICE_KEEP, &*terminals::DICE_LOWEST, n),
+            Self::DropHighest(n) => (&*terminals::DICE_DROP, &*terminals::DICE_HIGHEST, n),
+            Self::DropLowest(n) => (&*terminals::DICE_DROP, &*terminals::DICE_LOWEST, n)
+        };
+
+        f.write_fmt(format_args!("{}{}{n}", action.to_string(), end.to_string()))
+    }
+}
+
 impl HistoryToken {
     pub fn new(value: usize) -> Self {
         Self {
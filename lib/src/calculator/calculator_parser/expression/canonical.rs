@@ -0,0 +1,118 @@
+use super::{Expr, ExprPrime, Func, Token, precedence::{self, LEVEL_ADD, LEVEL_ATOM, LEVEL_EXP, LEVEL_INFIX_FUNCTION, LEVEL_STORE, LEVEL_SUFFIX, binop_level}};
+
+impl Expr {
+    /**
+     * The expression written in the calculator's own syntax, which parses back to the same expression
+     */
+    pub fn to_canonical_string(&self) -> String {
+        match self {
+            Self::None => String::new(),
+            Self::ExprPrime(expr) => expr.to_canonical_string()
+        }
+    }
+}
+
+impl ExprPrime {
+    /**
+     * The expression written in the calculator's own syntax, which parses back to the same expression.
+     * Binary operators, infix functions and the arguments of functions and lists are spaced the same way every time.
+     * Parentheses are kept where they were written; others are only added where the priority of the operators needs them.
+     */
+    pub fn to_canonical_string(&self) -> String {
+        canonical(self, true)
+    }
+}
+
+/**
+ * How tightly the expression binds, given whether anything follows it before the end of the enclosing brackets
+ */
+fn level(expr: &ExprPrime, last: bool) -> u8 {
+    match expr {
+        //Assigning to memory takes the rest of the expression, so it can be an operand when nothing follows it
+        ExprPrime::StoreMem(_, _) if last => LEVEL_ATOM,
+        ExprPrime::BinaryInfixExpression(first, children) if children.is_empty() => level(first, last),
+        expr => precedence::level(expr)
+    }
+}
+
+/**
+ * Write the expression, in parentheses if it binds more loosely than the level
+ */
+fn operand(expr: &ExprPrime, min_level: u8, last: bool) -> String {
+    if level(expr, last) < min_level {
+        format!("{}{}{}", Token::OpParO, canonical(expr, true), Token::OpParC)
+    }
+    else {
+        canonical(expr, last)
+    }
+}
+
+/**
+ * Write a chain of operators, spaced, where every operand after the first binds at least as tightly as the rest
+ */
+fn chain<'a, T>(first: &ExprPrime, children: &'a [(T, Box<ExprPrime>)], (first_level, min_level): (u8, u8), last: bool, operator: impl Fn(&'a T) -> String) -> String {
+    let mut written = operand(first, first_level, false);
+
+    for (i, (op, expr)) in children.iter().enumerate() {
+        written.push_str(&format!(" {} {}", operator(op), operand(expr, min_level, last && i == children.len() - 1)));
+    }
+
+    written
+}
+
+/**
+ * Write each expression, separated by delimiters
+ */
+fn delimited(exprs: &[ExprPrime]) -> String {
+    exprs.iter()
+        .map(|expr| operand(expr, LEVEL_STORE, true))
+        .collect::<Vec<String>>()
+        .join(format!("{} ", Token::Delimiter).as_str())
+}
+
+/**
+ * Write the expression, where 'last' is whether anything follows it before the end of the enclosing brackets
+ */
+fn canonical(expr: &ExprPrime, last: bool) -> String {
+    match expr {
+        //Numbers are written as they were, so that the formatter doesn't change the radix or digits of a literal
+        ExprPrime::Number(n) => n.lexeme.clone().unwrap_or_else(|| n.value.to_string()),
+        ExprPrime::Dice(dice) => dice.to_string(),
+        ExprPrime::History(history) => format!("{}{}", history.get_token(), history.value),
+        ExprPrime::AccessMem(memory) => format!("{}{}", memory.get_token(), memory.value),
+        ExprPrime::Id(id) => id.value.clone(),
+        ExprPrime::Func(Func::ConstantFunc(constant)) => constant.to_string().to_lowercase(),
+        ExprPrime::Func(Func::EmptyFunc(id)) => format!("{}{}{}", id.value, Token::OpParO, Token::OpParC),
+        ExprPrime::Func(Func::FuncWithArgs(id, args)) => format!("{}{}{}{}", id.value, Token::OpParO, delimited(args), Token::OpParC),
+        ExprPrime::List(elements) => format!("{}{}{}", Token::OpListO, delimited(elements), Token::OpListC),
+        ExprPrime::Range(start, end) => format!("{}{}{}", operand(start, LEVEL_INFIX_FUNCTION, false), Token::OpRange, operand(end, LEVEL_INFIX_FUNCTION, last)),
+        ExprPrime::Index(subexpr, index) => format!("{}{}{}{}", operand(subexpr, LEVEL_ATOM, false), Token::OpListO, canonical(index, true), Token::OpListC),
+        ExprPrime::StoreMem(memory, subexpr) => format!("{}{}{} {}", memory.get_token(), memory.value, Token::OpSetMem, canonical(subexpr, true)),
+        ExprPrime::UnopPrefixesExpression(prefixes, subexpr) => {
+            let prefixes: String = prefixes.iter().map(|op| op.to_string()).collect();
+            format!("{prefixes}{}", operand(subexpr, LEVEL_SUFFIX, last))
+        },
+        ExprPrime::UnopSuffixesExpression(subexpr, suffixes) => {
+            let suffixes: String = suffixes.iter().map(|op| op.to_string()).collect();
+
+            //A factorial directly after dice would be read as exploding them
+            let subexpr = match **subexpr {
+                ExprPrime::Dice(_) => format!("{}{}{}", Token::OpParO, canonical(subexpr, true), Token::OpParC),
+                _ => operand(subexpr, LEVEL_ATOM, false)
+            };
+
+            format!("{subexpr}{suffixes}")
+        },
+        ExprPrime::ParenthesesExpression(subexpr) => format!("{}{}{}", Token::OpParO, canonical(subexpr, true), Token::OpParC),
+        ExprPrime::BinaryInfixExpression(first, children) if children.is_empty() => canonical(first, last),
+        ExprPrime::BinaryInfixExpression(first, children) => {
+            let chain_level = binop_level(children[0].0);
+
+            //Exponents are right associative, so only the first operand must bind more tightly
+            let min_level = if chain_level == LEVEL_EXP { LEVEL_EXP } else { chain_level + 1 };
+
+            chain(first, children, (chain_level + 1, min_level), last, |binop| binop.to_string())
+        },
+        ExprPrime::BinaryInfixFunctionExpression(first, children) => chain(first, children, (LEVEL_ADD, LEVEL_ADD), last, |id| id.value.clone())
+    }
+}
@@ -0,0 +1,43 @@
+use super::{BinopInfix, BinopInfixPriority, ExprPrime};
+
+/*
+ * How tightly each kind of expression binds, loosest first, following the steps of the parser.
+ * An operand is put in parentheses when it binds more loosely than its position requires.
+ */
+pub const LEVEL_STORE: u8 = 0;
+pub const LEVEL_RANGE: u8 = 1;
+pub const LEVEL_INFIX_FUNCTION: u8 = 2;
+pub const LEVEL_ADD: u8 = 3;
+pub const LEVEL_MULT: u8 = 4;
+/**
+ * A chain of divisions only, which binds as tightly as multiplication when written, but is typeset as a stacked fraction
+ */
+pub const LEVEL_FRACTION: u8 = 5;
+pub const LEVEL_EXP: u8 = 6;
+pub const LEVEL_PREFIX: u8 = 7;
+pub const LEVEL_SUFFIX: u8 = 8;
+pub const LEVEL_ATOM: u8 = 9;
+
+pub fn binop_level(binop: BinopInfix) -> u8 {
+    match binop.get_priority() {
+        BinopInfixPriority::Priority0(_) => LEVEL_EXP,
+        BinopInfixPriority::Priority1(_) => LEVEL_MULT,
+        BinopInfixPriority::Priority2(_) => LEVEL_ADD
+    }
+}
+
+/**
+ * How tightly the expression binds as written, where an expression in parentheses binds as tightly as a number
+ */
+pub fn level(expr: &ExprPrime) -> u8 {
+    match expr {
+        ExprPrime::StoreMem(_, _) => LEVEL_STORE,
+        ExprPrime::Range(_, _) => LEVEL_RANGE,
+        ExprPrime::BinaryInfixFunctionExpression(_, _) => LEVEL_INFIX_FUNCTION,
+        ExprPrime::BinaryInfixExpression(first, children) if children.is_empty() => level(first),
+        ExprPrime::BinaryInfixExpression(_, children) => binop_level(children[0].0),
+        ExprPrime::UnopPrefixesExpression(_, _) => LEVEL_PREFIX,
+        ExprPrime::UnopSuffixesExpression(_, _) => LEVEL_SUFFIX,
+        _ => LEVEL_ATOM
+    }
+}
@@ -0,0 +1,375 @@
+use super::{BinopInfix, Constant, Expr, ExprPrime, Func, UnopPrefix, UnopSuffix, precedence::{self, LEVEL_ADD, LEVEL_ATOM, LEVEL_EXP, LEVEL_FRACTION, LEVEL_INFIX_FUNCTION, LEVEL_MULT, LEVEL_RANGE, LEVEL_STORE, LEVEL_SUFFIX, binop_level}};
+
+/**
+ * Functions which LaTeX has a command for, by the calculator's name for them
+ */
+const LATEX_FUNCTIONS: [(&str, &str); 18] = [
+    ("sin", "\\sin"), ("cos", "\\cos"), ("tan", "\\tan"), ("csc", "\\csc"), ("sec", "\\sec"), ("cot", "\\cot"),
+    ("asin", "\\arcsin"), ("acos", "\\arccos"), ("atan", "\\arctan"), ("sinh", "\\sinh"), ("cosh", "\\cosh"), ("tanh", "\\tanh"),
+    ("log", "\\log"), ("ln", "\\ln"), ("max", "\\max"), ("min", "\\min"), ("det", "\\det"), ("exp", "\\exp")
+];
+
+/**
+ * The layout of an expression as typeset math, with the parentheses it needs already decided
+ */
+#[derive(Debug, PartialEq, Clone)]
+enum Typeset {
+    Row(Vec<Typeset>),
+    Number(String),
+    Identifier(String),
+    Operator(String),
+    Text(String),
+    Fraction(Box<Typeset>, Box<Typeset>),
+    Superscript(Box<Typeset>, Box<Typeset>),
+    /**
+     * An element of a list or matrix, with the index below its operand
+     */
+    Subscript(Box<Typeset>, Box<Typeset>),
+    Radical(Box<Typeset>),
+    /**
+     * Content between an opening and closing symbol, such as parentheses or the bars of an absolute value
+     */
+    Fenced(&'static str, &'static str, Box<Typeset>),
+    Table(Vec<Vec<Typeset>>)
+}
+
+impl Expr {
+    /**
+     * The expression as a MathML 'math' element
+     */
+    pub fn to_mathml(&self) -> String {
+        match self {
+            Self::None => String::from("<math display=\"block\"></math>"),
+            Self::ExprPrime(expr) => expr.to_mathml()
+        }
+    }
+
+    /**
+     * The expression as LaTeX math, without the delimiters of math mode
+     */
+    pub fn to_latex(&self) -> String {
+        match self {
+            Self::None => String::new(),
+            Self::ExprPrime(expr) => expr.to_latex()
+        }
+    }
+}
+
+impl ExprPrime {
+    /**
+     * The expression as a MathML 'math' element, with divisions as fractions, exponents as superscripts, indexes as subscripts,
+     * square roots as radicals, and only the parentheses which the priority of the operators needs
+     */
+    pub fn to_mathml(&self) -> String {
+        format!("<math display=\"block\">{}</math>", typeset(self).to_mathml())
+    }
+
+    /**
+     * The expression as LaTeX math, without the delimiters of math mode, typeset the same as by 'to_mathml'
+     */
+    pub fn to_latex(&self) -> String {
+        typeset(self).to_latex()
+    }
+}
+
+impl Typeset {
+    fn to_mathml(&self) -> String {
+        match self {
+            Self::Row(items) if items.len() == 1 => items[0].to_mathml(),
+            Self::Row(items) => format!("<mrow>{}</mrow>", items.iter().map(Self::to_mathml).collect::<String>()),
+            Self::Number(n) => format!("<mn>{}</mn>", escape(n)),
+            Self::Identifier(id) => format!("<mi>{}</mi>", escape(id)),
+            Self::Operator(op) => format!("<mo>{}</mo>", escape(op)),
+            Self::Text(text) => format!("<mtext>{}</mtext>", escape(text)),
+            Self::Fraction(numerator, denominator) => format!("<mfrac>{}{}</mfrac>", numerator.to_mathml(), denominator.to_mathml()),
+            Self::Superscript(base, exponent) => format!("<msup>{}{}</msup>", base.to_mathml(), exponent.to_mathml()),
+            Self::Subscript(base, index) => format!("<msub>{}{}</msub>", base.to_mathml(), index.to_mathml()),
+            Self::Radical(radicand) => format!("<msqrt>{}</msqrt>", radicand.to_mathml()),
+            Self::Fenced(open, close, content) => format!("<mrow><mo>{}</mo>{}<mo>{}</mo></mrow>", escape(open), content.to_mathml(), escape(close)),
+            Self::Table(rows) => {
+                let rows: String = rows.iter()
+                    .map(|row| format!("<mtr>{}</mtr>", row.iter().map(|cell| format!("<mtd>{}</mtd>", cell.to_mathml())).collect::<String>()))
+                    .collect();
+
+                format!("<mtable>{rows}</mtable>")
+            }
+        }
+    }
+
+    fn to_latex(&self) -> String {
+        match self {
+            Self::Row(items) => {
+                let mut latex = String::new();
+
+                for (i, item) in items.iter().enumerate() {
+                    //Commas, suffixes and the arguments of a function follow directly
+                    let attached = matches!(item, Self::Operator(op) if op == "," || op == "!")
+                        || matches!((i.checked_sub(1).map(|i| &items[i]), item), (Some(Self::Identifier(_)), Self::Fenced("(", _, _)));
+
+                    if i > 0 && !attached {
+                        latex.push(' ');
+                    }
+
+                    latex.push_str(&item.to_latex());
+                }
+
+                latex
+            },
+            Self::Number(n) => n.clone(),
+            Self::Identifier(id) => match (id.as_str(), LATEX_FUNCTIONS.iter().find(|(name, _)| name.eq_ignore_ascii_case(id))) {
+                ("π", _) => String::from("\\pi"),
+                (_, Some((_, command))) => String::from(*command),
+                (id, _) if id.chars().count() == 1 => escape_latex(id),
+                (id, _) => format!("\\operatorname{{{}}}", escape_latex(id))
+            },
+            Self::Operator(op) => match op.as_str() {
+                "−" => String::from("-"),
+                "×" => String::from("\\times"),
+                "mod" => String::from("\\bmod"),
+                "%" => String::from("\\%"),
+                "≔" => String::from(":="),
+                "+" | "!" | "," | ".." => op.clone(),
+                op => format!("\\operatorname{{{}}}", escape_latex(op))
+            },
+            Self::Text(text) => format!("\\text{{{}}}", escape_latex(text)),
+            Self::Fraction(numerator, denominator) => format!("\\frac{{{}}}{{{}}}", numerator.to_latex(), denominator.to_latex()),
+            Self::Superscript(base, exponent) => format!("{{{}}}^{{{}}}", base.to_latex(), exponent.to_latex()),
+            Self::Subscript(base, index) => format!("{{{}}}_{{{}}}", base.to_latex(), index.to_latex()),
+            Self::Radical(radicand) => format!("\\sqrt{{{}}}", radicand.to_latex()),
+            Self::Fenced(open, close, content) => {
+                fn delimiter(symbol: &str) -> &str {
+                    match symbol {
+                        "⌊" => "\\lfloor",
+                        "⌋" => "\\rfloor",
+                        "⌈" => "\\lceil",
+                        "⌉" => "\\rceil",
+                        symbol => symbol
+                    }
+                }
+
+                format!("\\left{}{}\\right{}", delimiter(open), content.to_latex(), delimiter(close))
+            },
+            Self::Table(rows) => {
+                let rows: Vec<String> = rows.iter()
+                    .map(|row| row.iter().map(Self::to_latex).collect::<Vec<String>>().join(" & "))
+                    .collect();
+
+                format!("\\begin{{matrix}}{}\\end{{matrix}}", rows.join(" \\\\ "))
+            }
+        }
+    }
+}
+
+/**
+ * Escape the characters which have a meaning in LaTeX
+ */
+fn escape_latex(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => String::from("\\backslash "),
+            '$' | '%' | '_' | '#' | '&' | '{' | '}' => format!("\\{c}"),
+            c => String::from(c)
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/**
+ * How tightly the expression binds once typeset
+ */
+fn level(expr: &ExprPrime) -> u8 {
+    match expr {
+        //Parentheses are dropped, and put back where they are needed
+        ExprPrime::ParenthesesExpression(subexpr) => level(subexpr),
+        ExprPrime::BinaryInfixExpression(first, children) if children.is_empty() => level(first),
+        ExprPrime::BinaryInfixExpression(_, children) if children.iter().all(|(binop, _)| *binop == BinopInfix::Div) => LEVEL_FRACTION,
+        ExprPrime::Func(Func::FuncWithArgs(id, args)) if matches!((id.value.to_lowercase().as_str(), args.len()), ("exp", 1) | ("pow", 2)) => LEVEL_EXP,
+        expr => precedence::level(expr)
+    }
+}
+
+/**
+ * Typeset the expression, in parentheses if it binds more loosely than the level
+ */
+fn operand(expr: &ExprPrime, min_level: u8) -> Typeset {
+    if level(expr) < min_level {
+        Typeset::Fenced("(", ")", Box::new(typeset(expr)))
+    }
+    else {
+        typeset(expr)
+    }
+}
+
+/**
+ * Typeset a chain of left associative operators, where the first operand may bind less tightly than the rest
+ */
+fn chain<'a, T>(first: &ExprPrime, children: impl Iterator<Item = (T, &'a ExprPrime)>, (first_level, min_level): (u8, u8), operator: impl Fn(T) -> Typeset) -> Typeset {
+    let mut items = vec![operand(first, first_level)];
+
+    for (op, expr) in children {
+        items.push(operator(op));
+        items.push(operand(expr, min_level));
+    }
+
+    Typeset::Row(items)
+}
+
+fn binop_operator(binop: BinopInfix) -> Typeset {
+    Typeset::Operator(String::from(match binop {
+        BinopInfix::Exp => "^",
+        BinopInfix::Mult => "×",
+        BinopInfix::Div => "/",
+        BinopInfix::Rem => "%",
+        BinopInfix::Add => "+",
+        BinopInfix::Sub => "−"
+    }))
+}
+
+fn call(name: &str, args: &[ExprPrime]) -> Typeset {
+    let mut items = Vec::new();
+
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            items.push(Typeset::Operator(String::from(",")));
+        }
+
+        items.push(operand(arg, LEVEL_STORE));
+    }
+
+    Typeset::Row(vec![Typeset::Identifier(String::from(name)), Typeset::Fenced("(", ")", Box::new(Typeset::Row(items)))])
+}
+
+fn power(base: &ExprPrime, exponent: &ExprPrime) -> Typeset {
+    Typeset::Superscript(Box::new(operand(base, LEVEL_ATOM)), Box::new(typeset(exponent)))
+}
+
+fn typeset(expr: &ExprPrime) -> Typeset {
+    match expr {
+        ExprPrime::Number(n) => Typeset::Number(n.value.to_string()),
+        ExprPrime::Dice(dice) => Typeset::Text(dice.to_string()),
+        ExprPrime::History(history) => Typeset::Identifier(format!("{}{}", history.get_token(), history.value)),
+        ExprPrime::AccessMem(memory) => Typeset::Identifier(format!("{}{}", memory.get_token(), memory.value)),
+        ExprPrime::Id(id) => Typeset::Identifier(id.value.clone()),
+        ExprPrime::Func(Func::ConstantFunc(constant)) => Typeset::Identifier(String::from(match constant {
+            Constant::Pi => "π",
+            Constant::E => "e"
+        })),
+        ExprPrime::Func(Func::EmptyFunc(id)) => call(&id.value, &[]),
+        ExprPrime::Func(Func::FuncWithArgs(id, args)) => match (id.value.to_lowercase().as_str(), args.as_slice()) {
+            ("sqrt", [arg]) => Typeset::Radical(Box::new(typeset(arg))),
+            ("abs", [arg]) => Typeset::Fenced("|", "|", Box::new(typeset(arg))),
+            ("floor", [arg]) => Typeset::Fenced("⌊", "⌋", Box::new(typeset(arg))),
+            ("ceil", [arg]) => Typeset::Fenced("⌈", "⌉", Box::new(typeset(arg))),
+            ("exp", [arg]) => Typeset::Superscript(Box::new(Typeset::Identifier(String::from("e"))), Box::new(typeset(arg))),
+            ("pow", [base, exponent]) => power(base, exponent),
+            _ => call(&id.value, args)
+        },
+        ExprPrime::List(elements) => {
+            //A list of lists of the same length is a matrix
+            let columns = match elements.first() {
+                Some(ExprPrime::List(row)) => row.len(),
+                _ => 0
+            };
+
+            let is_matrix = columns > 0 && elements.iter().all(|element| matches!(element, ExprPrime::List(row) if row.len() == columns));
+
+            if is_matrix {
+                let rows = elements.iter()
+                    .map(|row| match row {
+                        ExprPrime::List(row) => row.iter().map(|cell| operand(cell, LEVEL_STORE)).collect(),
+                        _ => Vec::new()
+                    })
+                    .collect();
+
+                Typeset::Fenced("[", "]", Box::new(Typeset::Table(rows)))
+            }
+            else {
+                let mut items = Vec::new();
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        items.push(Typeset::Operator(String::from(",")));
+                    }
+
+                    items.push(operand(element, LEVEL_STORE));
+                }
+
+                Typeset::Fenced("[", "]", Box::new(Typeset::Row(items)))
+            }
+        },
+        ExprPrime::Range(start, end) => Typeset::Row(vec![
+            operand(start, LEVEL_RANGE + 1),
+            Typeset::Operator(String::from("..")),
+            operand(end, LEVEL_RANGE + 1)
+        ]),
+        ExprPrime::Index(subexpr, index) => Typeset::Subscript(Box::new(operand(subexpr, LEVEL_ATOM)), Box::new(typeset(index))),
+        ExprPrime::StoreMem(memory, subexpr) => Typeset::Row(vec![
+            Typeset::Identifier(format!("{}{}", memory.get_token(), memory.value)),
+            Typeset::Operator(String::from("≔")),
+            typeset(subexpr)
+        ]),
+        ExprPrime::UnopPrefixesExpression(prefixes, subexpr) => {
+            let mut items: Vec<Typeset> = prefixes.iter()
+                .map(|prefix| match prefix {
+                    UnopPrefix::Neg => Typeset::Operator(String::from("−"))
+                })
+                .collect();
+
+            //Negating a power or fraction needs no parentheses; '-2^2' is read as '-(2^2)'
+            items.push(operand(subexpr, LEVEL_FRACTION));
+            Typeset::Row(items)
+        },
+        ExprPrime::UnopSuffixesExpression(subexpr, suffixes) => {
+            let mut items = vec![operand(subexpr, LEVEL_SUFFIX)];
+
+            items.extend(suffixes.iter().map(|suffix| match suffix {
+                UnopSuffix::Fac => Typeset::Operator(String::from("!"))
+            }));
+
+            Typeset::Row(items)
+        },
+        ExprPrime::ParenthesesExpression(subexpr) => typeset(subexpr),
+        ExprPrime::BinaryInfixExpression(first, children) if children.is_empty() => typeset(first),
+        ExprPrime::BinaryInfixExpression(first, children) if binop_level(children[0].0) == LEVEL_EXP => {
+            //The parser nests repeated exponents on the right, so a chain of them is applied from the left like the other operators
+            let mut base = operand(first, LEVEL_ATOM);
+
+            for (i, (_, exponent)) in children.iter().enumerate() {
+                if i > 0 {
+                    base = Typeset::Fenced("(", ")", Box::new(base));
+                }
+
+                base = Typeset::Superscript(Box::new(base), Box::new(typeset(exponent)));
+            }
+
+            base
+        },
+        ExprPrime::BinaryInfixExpression(first, children) if binop_level(children[0].0) == LEVEL_MULT => {
+            //Divisions are stacked, with everything before them as the numerator, which needs no parentheses of its own
+            let first_level = if children[0].0 == BinopInfix::Div { LEVEL_STORE } else { LEVEL_MULT };
+            let mut items = vec![operand(first, first_level)];
+
+            for (binop, expr) in children {
+                if *binop == BinopInfix::Div {
+                    let numerator = Typeset::Row(std::mem::take(&mut items));
+                    items.push(Typeset::Fraction(Box::new(numerator), Box::new(typeset(expr))));
+                }
+                else {
+                    items.push(binop_operator(*binop));
+                    items.push(operand(expr, LEVEL_MULT + 1));
+                }
+            }
+
+            Typeset::Row(items)
+        },
+        ExprPrime::BinaryInfixExpression(first, children) => chain(first, children.iter().map(|(binop, expr)| (*binop, expr.as_ref())), (LEVEL_ADD, LEVEL_ADD + 1), binop_operator),
+        //Sums are put in parentheses, as infix functions such as 'mod' would otherwise be read as binding more tightly
+        ExprPrime::BinaryInfixFunctionExpression(first, children) => chain(first, children.iter().map(|(id, expr)| (id, expr.as_ref())), (LEVEL_INFIX_FUNCTION, LEVEL_MULT),
+            |id| Typeset::Operator(id.value.clone()))
+    }
+}
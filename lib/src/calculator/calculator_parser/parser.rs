@@ -5,17 +5,46 @@ use crate::calculator::calculator_err::CalculatorErr;
 
 use super::expression as xpr;
 
+/**
+ * Where operators are written relative to their operands
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Notation {
+    /**
+     * Operators between their operands, i.e. '(3 + 4) * 2'
+     */
+    #[default] Infix,
+    /**
+     * Reverse polish notation; operators after their operands, i.e. '3 4 + 2 *'
+     */
+    Postfix
+}
+
+impl std::fmt::Display for Notation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Infix => f.write_str("infix"),
+            Self::Postfix => f.write_str("postfix")
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct ParserSettings {
-    
+    pub notation: Notation
 }
 
 impl ParserSettings {
     pub fn new() -> Self {
         Self {
-            
+            notation: Notation::default()
         }
     }
+
+    pub fn with_notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
 }
 
 impl Default for ParserSettings {
@@ -24,18 +53,32 @@ impl Default for ParserSettings {
     }
 }
 
+/**
+ * The values left on the stack after parsing postfix input, from the bottom of the stack to the top,
+ * and the number of operands that were missing from the input, and so were read from history
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct PostfixStack {
+    pub values: Vec<xpr::ExprPrime>,
+    pub history_operands: usize
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Parser {
     settings: ParserSettings
 }
 
 impl Parser {
-    fn new(settings: ParserSettings) -> Self {
+    pub fn new(settings: ParserSettings) -> Self {
         Self {
             settings
         }
     }
 
+    pub fn settings(&self) -> ParserSettings {
+        self.settings
+    }
+
     fn create_parser<'a>(&self, input: &'a str) -> ParserInner<'a> {
         ParserInner::new(self.settings, input)
     }
@@ -45,6 +88,15 @@ impl Parser {
             .parse_expression()
     }
 
+    /**
+     * Parse postfix input, keeping every value left on the stack, rather than requiring the input to leave a single value
+     */
+    pub fn parse_postfix_stack(&self, input: &str) -> Result<PostfixStack, CalculatorErr> {
+        self.create_parser(input)
+            .postfix_stack()
+            .map(|(values, history_operands)| PostfixStack { values, history_operands })
+    }
+
     pub fn parse_expression<TExpr: Parsable>(&self, input: &str) -> Result<TExpr, CalculatorErr> {
         self.create_parser(input)
             .parse_expression()
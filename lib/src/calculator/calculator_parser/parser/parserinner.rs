@@ -1,9 +1,10 @@
 pub mod parsableimpl;
 
 use unicode_segmentation::{self, UnicodeSegmentation};
-use crate::calculator::calculator_parser::{ terminal::*, expression as xpr };
+use crate::calculator::calculator_parser::{ terminal::*, expression as xpr, expression::precedence };
 use crate::calculator::calculator_parser::parser::ParserSettings;
 use crate::calculator::CalculatorErr;
+use crate::calculator::calculator_interpreter::interpreter::value::MAX_LIST_LEN;
 
 pub trait Parsable
     where Self : Sized { 
@@ -60,7 +61,7 @@ impl<'a> ParserInner<'a> {
         self.whitespace();
 
         //Handle in steps for each priority, starting with the step with the lowest priority to account for operator precedence
-        let result = self.expr_id_fn();
+        let result = self.expr_range();
 
         //Optional whitespace
         self.whitespace();
@@ -68,6 +69,53 @@ impl<'a> ParserInner<'a> {
         result
     }
 
+    pub fn expr_range(&mut self) -> Result<xpr::ExprPrime, CalculatorErr> {
+        //First handle operators of higher priority to account for operator precedence
+        let start_result = self.expr_id_fn();
+
+        //Start of range is required.
+        if let Err(start_err) = start_result {
+            if start_err.propagate() {
+                return Err(start_err);
+            }
+            else {
+                return Err(CalculatorErr::default())
+            }
+        }
+
+        let start = start_result.unwrap();
+        let initial_lah = self.lah;
+
+        //Match optional whitespace
+        self.whitespace();
+
+        //Check for the range operator, made of two radix points
+        if !(terminals::RADIX_PT.match_symbol(self.token_at(self.lah)) && terminals::RADIX_PT.match_symbol(self.token_at(self.lah + 1))) {
+            //Not a range. Rollback lah.
+            self.lah = initial_lah;
+            return Ok(start);
+        }
+
+        self.lah += 2;
+
+        //Match optional whitespace
+        self.whitespace();
+
+        //End of range is required
+        let end_result = self.expr_id_fn();
+
+        if let Err(end_err) = end_result {
+            if end_err.propagate() {
+                return Err(end_err);
+            }
+            else {
+                return Err(CalculatorErr::parse_err(format!("Expected expression after range '{}{}'.", start, terminals::OP_RANGE.to_string()).as_str(), true, self.lah))
+            }
+        }
+
+        Ok(xpr::ExprPrime::Range(Box::new(start), Box::new(end_result.unwrap())))
+    }
+
     pub fn expr_id_fn(&mut self) -> Result<xpr::ExprPrime, CalculatorErr> {
         //First handle operators of higher priority to account for operator precedence
         let expr_2_result = self.expr_2();
@@ -378,11 +426,11 @@ impl<'a> ParserInner<'a> {
             continue;
         };
 
-        //Try to match number.
-        let number_result = self.number();
+        //Try to match dice notation.
+        let dice_result = self.dice();
 
-        result = match number_result {
-            Ok(num) => Some(Ok(xpr::ExprPrime::Number(num))),
+        result = match dice_result {
+            Ok(dice) => Some(Ok(xpr::ExprPrime::Dice(dice))),
             Err(err) => {
                 if err.propagate() {
                     return Err(err);
@@ -393,6 +441,23 @@ impl<'a> ParserInner<'a> {
             }
         };
 
+        if result.is_none() {
+            //Failed to match. Try to match number.
+            let number_result = self.number();
+
+            result = match number_result {
+                Ok(num) => Some(Ok(xpr::ExprPrime::Number(num))),
+                Err(err) => {
+                    if err.propagate() {
+                        return Err(err);
+                    }
+                    else {
+                        None
+                    }
+                }
+            };
+        }
+
         if result.is_none() {
             //Failed to match. Try to match history stack/memory access.
             let history_memory_result = self.history_memory();
@@ -427,6 +492,40 @@ impl<'a> ParserInner<'a> {
             };
         }
 
+        if result.is_none() {
+            //Failed to match. Try to match a variable.
+            let id_result = self.id();
+
+            result = match id_result {
+                Ok(id) => Some(Ok(xpr::ExprPrime::Id(id))),
+                Err(err) => {
+                    if err.propagate() {
+                        return Err(err);
+                    }
+                    else {
+                        None
+                    }
+                }
+            };
+        }
+
+        if result.is_none() {
+            //Failed to match. Try to match a list.
+            let list_result = self.list();
+
+            result = match list_result {
+                Ok(list) => Some(Ok(list)),
+                Err(err) => {
+                    if err.propagate() {
+                        return Err(err);
+                    }
+                    else {
+                        None
+                    }
+                }
+            };
+        }
+
         if result.is_none() {
             //Failed to match. Try to match an expression in parentheses.
             let paren_expression_paren_result = self.paren_expression_paren();
@@ -444,7 +543,27 @@ impl<'a> ParserInner<'a> {
             };
         }
 
-        if let Some(some_result) = result {
+        if let Some(mut some_result) = result {
+            //Match 0+ indexes
+            if let Ok(mut indexed) = some_result {
+                loop {
+                    let index_result = self.index();
+
+                    if let Err(index_err) = index_result {
+                        if index_err.propagate() {
+                            return Err(index_err);
+                        }
+                        else {
+                            break;
+                        }
+                    }
+
+                    indexed = xpr::ExprPrime::Index(Box::new(indexed), Box::new(index_result.unwrap()));
+                }
+
+                some_result = Ok(indexed);
+            }
+
             //Match 0+ unary suffix operators
             loop {
                 let unop_suffix_result = self.unop_suf();
@@ -490,6 +609,18 @@ impl<'a> ParserInner<'a> {
     }
 
     pub fn number(&mut self) -> Result<xpr::NumberToken, CalculatorErr> {
+        //Try to match an integer in hexadecimal, octal or binary
+        let integer_result = self.radix_integer();
+
+        if let Ok(integer) = integer_result {
+            return Ok(integer);
+        }
+        else if let Err(integer_err) = integer_result {
+            if integer_err.propagate() {
+                return Err(integer_err);
+            }
+        }
+
         let mut current_lah = self.lah;
 
         let mut collected: Vec<&str> = Vec::new();
@@ -512,10 +643,10 @@ impl<'a> ParserInner<'a> {
             return Err(CalculatorErr::default());
         }
 
-        //Check if the next symbol is a decimal point
+        //Check if the next symbol is a decimal point, and not the start of a range
         let radix_pt_token = self.token_at(current_lah);
 
-        if terminals::RADIX_PT.match_symbol(radix_pt_token) {
+        if terminals::RADIX_PT.match_symbol(radix_pt_token) && !terminals::RADIX_PT.match_symbol(self.token_at(current_lah + 1)) {
             let mut current_lah_1 = current_lah;
             let mut collected_1: Vec<&str> = Vec::new();
 
@@ -557,7 +688,161 @@ impl<'a> ParserInner<'a> {
         }
 
         self.lah = current_lah;
-        Ok(xpr::NumberToken::new(parsed.unwrap()))
+        Ok(xpr::NumberToken::with_lexeme(parsed.unwrap(), &concatenated))
+    }
+
+    /**
+     * Match an integer written with a radix prefix; '0x' for hexadecimal, '0o' for octal or '0b' for binary, i.e. '0xFF'
+     */
+    pub fn radix_integer(&mut self) -> Result<xpr::NumberToken, CalculatorErr> {
+        if self.token_at(self.lah) != "0" {
+            return Err(CalculatorErr::default());
+        }
+
+        let prefix = self.token_at(self.lah + 1);
+
+        let radix: u32 = match prefix {
+            "x" | "X" => 16,
+            "o" | "O" => 8,
+            "b" | "B" => 2,
+            _ => return Err(CalculatorErr::default())
+        };
+
+        let mut current_lah = self.lah + 2;
+        let mut collected: Vec<&str> = Vec::new();
+
+        //Match 1 or more digits of the radix
+        loop {
+            let current_token = self.token_at(current_lah);
+
+            if current_token.chars().count() == 1 && current_token.chars().all(|c| c.is_digit(radix)) {
+                current_lah += 1;
+                collected.push(current_token);
+                continue;
+            }
+
+            break;
+        };
+
+        if collected.is_empty() {
+            return Err(CalculatorErr::parse_err(format!("Expected digit after '0{prefix}'.").as_str(), true, current_lah));
+        }
+
+        let concatenated = collected.join("");
+
+        let parsed = match u64::from_str_radix(&concatenated, radix) {
+            Ok(parsed) => parsed,
+            Err(_) => return Err(CalculatorErr::parse_err(format!("Failed to parse number '0{prefix}{concatenated}'.").as_str(), true, self.lah))
+        };
+
+        let token = xpr::NumberToken::with_lexeme(parsed as f64, &format!("0{prefix}{concatenated}"));
+
+        self.lah = current_lah;
+        Ok(token)
+    }
+
+    pub fn dice(&mut self) -> Result<xpr::DiceToken, CalculatorErr> {
+        let initial_lah = self.lah;
+
+        //Match 0+ digits for the number of dice
+        let count_digits = self.digits();
+
+        //Dice separator is required. Rollback and return error if not present.
+        if !terminals::DICE.match_symbol(self.get_and_increment()) {
+            self.lah = initial_lah;
+            return Err(CalculatorErr::default());
+        }
+
+        //Match 1+ digits for the number of sides. Rollback and return error if not present.
+        let sides_digits = self.digits();
+
+        if sides_digits.is_empty() {
+            self.lah = initial_lah;
+            return Err(CalculatorErr::default());
+        }
+
+        let count = if count_digits.is_empty() {
+            1_usize
+        }
+        else {
+            match count_digits.parse::<usize>() {
+                Ok(count) => count,
+                Err(_) => return Err(CalculatorErr::parse_err(format!("Failed to parse number of dice '{count_digits}'.").as_str(), true, initial_lah))
+            }
+        };
+
+        let sides = match sides_digits.parse::<usize>() {
+            Ok(sides) => sides,
+            Err(_) => return Err(CalculatorErr::parse_err(format!("Failed to parse number of sides '{sides_digits}'.").as_str(), true, initial_lah))
+        };
+
+        //Optional exploding dice marker
+        let explode = terminals::DICE_EXPLODE.match_symbol(self.token_at(self.lah));
+
+        if explode {
+            self.lah += 1;
+        }
+
+        //Optional selection of which dice to keep, i.e. kh1, dl1
+        let selection_lah = self.lah;
+        let action = self.get_and_increment().to_string();
+        let end = self.get_and_increment().to_string();
+
+        let is_keep = terminals::DICE_KEEP.match_symbol(&action);
+        let is_drop = terminals::DICE_DROP.match_symbol(&action);
+        let is_highest = terminals::DICE_HIGHEST.match_symbol(&end);
+        let is_lowest = terminals::DICE_LOWEST.match_symbol(&end);
+
+        let selection = if (is_keep || is_drop) && (is_highest || is_lowest) {
+            let selected_digits = self.digits();
+
+            if selected_digits.is_empty() {
+                return Err(CalculatorErr::parse_err(format!("Expected number of dice after '{action}{end}'.").as_str(), true, self.lah));
+            }
+
+            let selected = match selected_digits.parse::<usize>() {
+                Ok(selected) => selected,
+                Err(_) => return Err(CalculatorErr::parse_err(format!("Failed to parse number of dice '{selected_digits}'.").as_str(), true, selection_lah))
+            };
+
+            Some(match (is_keep, is_highest) {
+                (true, true) => xpr::DiceSelection::KeepHighest(selected),
+                (true, false) => xpr::DiceSelection::KeepLowest(selected),
+                (false, true) => xpr::DiceSelection::DropHighest(selected),
+                (false, false) => xpr::DiceSelection::DropLowest(selected)
+            })
+        }
+        else {
+            //No selection. Rollback to before the selection.
+            self.lah = selection_lah;
+            None
+        };
+
+        Ok(xpr::DiceToken::new(count, sides, explode, selection))
+    }
+
+    /**
+     * Match 0+ digits, returning them concatenated
+     */
+    fn digits(&mut self) -> String {
+        let mut collected: Vec<&str> = Vec::new();
+        let mut current_lah = self.lah;
+
+        loop {
+            let current_token = self.token_at(current_lah);
+
+            if terminals::DIGIT.match_symbol(current_token) {
+                current_lah += 1;
+                collected.push(current_token);
+                continue;
+            }
+
+            break;
+        };
+
+        let concatenated = collected.join("");
+        self.lah = current_lah;
+        concatenated
     }
 
     pub fn func(&mut self) -> Result<xpr::Func, CalculatorErr> {
@@ -571,6 +856,7 @@ impl<'a> ParserInner<'a> {
         }
 
         let id = id_result.unwrap();
+        let id_lah = self.lah;
 
         //Optional whitespace
         self.whitespace();
@@ -581,9 +867,9 @@ impl<'a> ParserInner<'a> {
         if !xpr::Token::OpParO.get_terminal().match_symbol(token) {
             //Opening paren is required, unless the id is a predefined constant,
             //in which case it will be treated as a zero-arg function.
-            //Descrement lah.
+            //Rollback lah to the end of the id.
             if let Ok(constant) = xpr::Constant::try_from(id.value) {
-                self.lah -= 1;
+                self.lah = id_lah;
                 return Ok(xpr::Func::ConstantFunc(constant));
             }
             //Not a constant. Rollback and return error if not present.
@@ -888,6 +1174,103 @@ impl<'a> ParserInner<'a> {
         Ok(xpr::ExprPrime::ParenthesesExpression(Box::new(expr_prime)))
     }
 
+    pub fn list(&mut self) -> Result<xpr::ExprPrime, CalculatorErr> {
+        let initial_lah = self.lah;
+
+        //Try to match an opening bracket
+        let token = self.get_and_increment();
+
+        //Opening bracket is required. Rollback and return error if not present.
+        if !xpr::Token::OpListO.get_terminal().match_symbol(token) {
+            self.lah = initial_lah;
+            return Err(CalculatorErr::default());
+        }
+
+        //Optional whitespace
+        self.whitespace();
+
+        let mut elements: Vec<xpr::ExprPrime> = Vec::new();
+
+        //If next character is not a closing bracket, then the list should have elements
+        if !xpr::Token::OpListC.get_terminal().match_symbol(self.token_at(self.lah)) {
+            loop {
+                //Match element
+                let expr_prime_result = self.expr_prime();
+
+                //Element is required.
+                if let Err(expr_prime_err) = expr_prime_result {
+                    if expr_prime_err.propagate() {
+                        return Err(expr_prime_err);
+                    }
+                    else {
+                        return Err(CalculatorErr::parse_err("Expected list element.", true, self.lah))
+                    }
+                }
+
+                elements.push(expr_prime_result.unwrap());
+
+                //Another element follows if there is a delimiter
+                if xpr::Token::Delimiter.get_terminal().match_symbol(self.token_at(self.lah)) {
+                    self.lah += 1;
+                }
+                else {
+                    break;
+                }
+            }
+        }
+
+        //Try to match a closing bracket
+        let token = self.get_and_increment();
+
+        //Closing bracket is required.
+        if !xpr::Token::OpListC.get_terminal().match_symbol(token) {
+            let list = xpr::ExprPrime::List(elements).to_string();
+            let concatenated = list.trim_end_matches(terminals::OP_LIST_C.to_string().as_str());
+
+            return Err(CalculatorErr::parse_err(format!("Expected closing bracket ']' after '{concatenated}'.").as_str(), true, self.lah));
+        }
+
+        Ok(xpr::ExprPrime::List(elements))
+    }
+
+    pub fn index(&mut self) -> Result<xpr::ExprPrime, CalculatorErr> {
+        let initial_lah = self.lah;
+
+        //Try to match an opening bracket
+        let token = self.get_and_increment();
+
+        //Opening bracket is required. Rollback and return error if not present.
+        if !xpr::Token::OpListO.get_terminal().match_symbol(token) {
+            self.lah = initial_lah;
+            return Err(CalculatorErr::default());
+        }
+
+        //Try to match the index
+        let expr_prime_result = self.expr_prime();
+
+        //Index is required.
+        if let Err(expr_prime_err) = expr_prime_result {
+            if expr_prime_err.propagate() {
+                return Err(expr_prime_err);
+            }
+            else {
+                return Err(CalculatorErr::parse_err("Expected index after opening bracket '['.", true, self.lah));
+            }
+        }
+
+        let expr_prime = expr_prime_result.unwrap();
+
+        //Try to match a closing bracket
+        let token = self.get_and_increment();
+
+        //Closing bracket is required.
+        if !xpr::Token::OpListC.get_terminal().match_symbol(token) {
+            return Err(CalculatorErr::parse_err(format!("Expected closing bracket ']' after index '[{expr_prime}'.").as_str(), true, self.lah));
+        }
+
+        Ok(expr_prime)
+    }
+
     pub fn unop_pre(&mut self) -> Result<xpr::UnopPrefix, CalculatorErr> {
         let initial_lah = self.lah;
         let current = self.get_and_increment();
@@ -1037,6 +1420,212 @@ impl<'a> ParserInner<'a> {
         Err(CalculatorErr::default())
     }
 
+    /**
+     * Parse postfix input into the values left on the stack, from the bottom of the stack to the top, and the number of operands read from history.
+     * Items are separated by whitespace. Operators and functions take their operands from the stack, or from history once the stack is empty,
+     * such that '+' alone is '$1 + $0'. A function takes one operand, unless followed by the number of operands it takes, i.e. 'max#3'.
+     */
+    pub fn postfix_stack(&mut self) -> Result<(Vec<xpr::ExprPrime>, usize), CalculatorErr> {
+        let mut stack: Vec<xpr::ExprPrime> = Vec::new();
+        let mut history_operands: usize = 0;
+
+        //Optional whitespace
+        self.whitespace();
+
+        while self.lah < self.tokens.len() {
+            let item_lah = self.lah;
+
+            if let Some(binop) = self.postfix_binop() {
+                let right = Self::postfix_pop(&mut stack, &mut history_operands);
+                let left = Self::postfix_pop(&mut stack, &mut history_operands);
+                stack.push(Self::postfix_binary(left, binop, right));
+            }
+            else if let Some(unop_suffix) = self.postfix_unop_suf() {
+                let operand = Self::postfix_pop(&mut stack, &mut history_operands);
+                stack.push(Self::postfix_suffix(operand, unop_suffix));
+            }
+            else if let Some(memory) = self.postfix_store_mem() {
+                let operand = Self::postfix_pop(&mut stack, &mut history_operands);
+                stack.push(xpr::ExprPrime::StoreMem(memory, Box::new(operand)));
+            }
+            else {
+                let operand = match self.expr_base() {
+                    Ok(operand) => operand,
+                    Err(err) if err.propagate() => return Err(err),
+                    Err(_) => return Err(CalculatorErr::parse_err(format!("Unexpected token '{}'.", self.token_at(self.lah)).as_str(), true, self.lah))
+                };
+
+                //A name on its own is a function, applied to operands from the stack
+                if let xpr::ExprPrime::Id(id) = operand {
+                    let arg_count = self.postfix_arg_count(&id)?;
+                    let mut args: Vec<xpr::ExprPrime> = (0..arg_count)
+                        .map(|_| Self::postfix_pop(&mut stack, &mut history_operands))
+                        .collect();
+                    args.reverse();
+
+                    if args.is_empty() {
+                        stack.push(xpr::ExprPrime::Func(xpr::Func::EmptyFunc(id)));
+                    }
+                    else {
+                        stack.push(xpr::ExprPrime::Func(xpr::Func::FuncWithArgs(id, args)));
+                    }
+                }
+                else {
+                    stack.push(operand);
+                }
+            }
+
+            //Items must be separated by whitespace
+            if !self.postfix_separator() {
+                let item = self.tokens[item_lah..self.lah].join("");
+                return Err(CalculatorErr::parse_err(format!("Expected whitespace after '{item}'.").as_str(), true, self.lah));
+            }
+
+            self.whitespace();
+        }
+
+        Ok((stack, history_operands))
+    }
+
+    /**
+     * Check for whitespace or the end of the input, which ends an item of postfix input
+     */
+    fn postfix_separator(&self) -> bool {
+        self.lah >= self.tokens.len() || terminals::WHITESPACE.match_symbol(self.token_at(self.lah))
+    }
+
+    /**
+     * Match a binary operator on its own, rather than the start of an operand such as '-3'
+     */
+    fn postfix_binop(&mut self) -> Option<xpr::BinopInfix> {
+        let initial_lah = self.lah;
+
+        match self.binop_in() {
+            Ok(binop) if self.postfix_separator() => Some(binop),
+            _ => {
+                self.lah = initial_lah;
+                None
+            }
+        }
+    }
+
+    fn postfix_unop_suf(&mut self) -> Option<xpr::UnopSuffix> {
+        let initial_lah = self.lah;
+
+        match self.unop_suf() {
+            Ok(unop_suffix) if self.postfix_separator() => Some(unop_suffix),
+            _ => {
+                self.lah = initial_lah;
+                None
+            }
+        }
+    }
+
+    /**
+     * Match a memory assignment on its own, i.e. '$m0:', which assigns the value on top of the stack
+     */
+    fn postfix_store_mem(&mut self) -> Option<xpr::MemoryToken> {
+        let initial_lah = self.lah;
+
+        if terminals::HISTORY.match_symbol(self.token_at(self.lah)) && terminals::HISTORY_MEMORY_QUALIFIER.match_symbol(self.token_at(self.lah + 1)) {
+            self.lah += 2;
+
+            let digits = self.digits();
+
+            if let Ok(memory) = digits.parse::<usize>() {
+                if terminals::OP_SETMEM.match_symbol(self.token_at(self.lah)) {
+                    self.lah += 1;
+
+                    if self.postfix_separator() {
+                        return Some(xpr::MemoryToken::new(memory));
+                    }
+                }
+            }
+        }
+
+        //Not a memory assignment on its own. Rollback.
+        self.lah = initial_lah;
+        None
+    }
+
+    /**
+     * Match the optional number of operands after a function name, i.e. '#3' in 'max#3'.
+     * No function takes more operands than a list can hold.
+     */
+    fn postfix_arg_count(&mut self, id: &xpr::IdToken) -> Result<usize, CalculatorErr> {
+        if !terminals::ARG_COUNT.match_symbol(self.token_at(self.lah)) {
+            return Ok(1);
+        }
+
+        self.lah += 1;
+
+        let digits = self.digits();
+
+        match digits.parse::<usize>() {
+            Ok(count) if count <= MAX_LIST_LEN => Ok(count),
+            Ok(_) => Err(CalculatorErr::parse_err(format!("Expected at most {MAX_LIST_LEN} arguments after '{}{}'.", id.value, terminals::ARG_COUNT.to_string()).as_str(), true, self.lah)),
+            Err(_) => Err(CalculatorErr::parse_err(format!("Expected the number of arguments after '{}{}'.", id.value, terminals::ARG_COUNT.to_string()).as_str(), true, self.lah))
+        }
+    }
+
+    /**
+     * Take the operand on top of the stack, or the next value of history if the stack is empty
+     */
+    fn postfix_pop(stack: &mut Vec<xpr::ExprPrime>, history_operands: &mut usize) -> xpr::ExprPrime {
+        stack.pop()
+            .unwrap_or_else(|| {
+                *history_operands += 1;
+                xpr::ExprPrime::History(xpr::HistoryToken::new(*history_operands - 1))
+            })
+    }
+
+    fn parenthesize(expression: xpr::ExprPrime) -> xpr::ExprPrime {
+        xpr::ExprPrime::ParenthesesExpression(Box::new(expression))
+    }
+
+    /**
+     * Combine two operands with an operator, grouping them as the infix parser would group the same expression written with parentheses.
+     * Exponentiation is right associative, and the other operators are left associative.
+     */
+    fn postfix_binary(left: xpr::ExprPrime, binop: xpr::BinopInfix, right: xpr::ExprPrime) -> xpr::ExprPrime {
+        let level = precedence::binop_level(binop);
+        let is_exp = level == precedence::LEVEL_EXP;
+
+        let right_level = precedence::level(&right);
+
+        let right = if right_level < level || (right_level == level && !is_exp) {
+            Self::parenthesize(right)
+        }
+        else {
+            right
+        };
+
+        let left_level = precedence::level(&left);
+
+        match left {
+            //Left associative operators of the same priority are chained
+            xpr::ExprPrime::BinaryInfixExpression(first, mut children) if left_level == level && !is_exp => {
+                children.push((binop, Box::new(right)));
+                xpr::ExprPrime::BinaryInfixExpression(first, children)
+            },
+            left if left_level <= level => xpr::ExprPrime::BinaryInfixExpression(Box::new(Self::parenthesize(left)), vec![(binop, Box::new(right))]),
+            left => xpr::ExprPrime::BinaryInfixExpression(Box::new(left), vec![(binop, Box::new(right))])
+        }
+    }
+
+    fn postfix_suffix(operand: xpr::ExprPrime, unop_suffix: xpr::UnopSuffix) -> xpr::ExprPrime {
+        match operand {
+            xpr::ExprPrime::UnopSuffixesExpression(subexpr, mut suffixes) => {
+                suffixes.push(unop_suffix);
+                xpr::ExprPrime::UnopSuffixesExpression(subexpr, suffixes)
+            },
+            operand if precedence::level(&operand) < precedence::LEVEL_SUFFIX => {
+                xpr::ExprPrime::UnopSuffixesExpression(Box::new(Self::parenthesize(operand)), vec![unop_suffix])
+            },
+            operand => xpr::ExprPrime::UnopSuffixesExpression(Box::new(operand), vec![unop_suffix])
+        }
+    }
+
     pub fn whitespace(&mut self) {
         let mut current_lah = self.lah;
 
@@ -1,4 +1,5 @@
 use super::{ ParserInner, Parsable };
+use crate::calculator::calculator_parser::parser::Notation;
 use crate::calculator::calculator_parser::expression as xpr;
 use crate::calculator::CalculatorErr;
 
@@ -8,6 +9,16 @@ impl Parsable for xpr::Expr {
         if parser.tokens.is_empty() {
             Ok(xpr::Expr::None)
         }
+        else if parser.settings.notation == Notation::Postfix {
+            let (mut values, _) = parser.postfix_stack()?;
+
+            if values.len() == 1 {
+                Ok(xpr::Expr::ExprPrime(Box::new(values.remove(0))))
+            }
+            else {
+                Err(CalculatorErr::parse_err(format!("Expected a single value; the input left {} values on the stack.", values.len()).as_str(), true, parser.lah))
+            }
+        }
         else {
             let expression_result = parser.expr_prime();
 
@@ -14,14 +14,25 @@ const OP_EXP_STR: &str = "^";
 const OP_FAC_STR: &str = "!";
 const OP_PAR_O_STR: &str = "(";
 const OP_PAR_C_STR: &str = ")";
+const OP_LIST_O_STR: &str = "[";
+const OP_LIST_C_STR: &str = "]";
+const OP_RANGE_STR: &str = "..";
 const OP_SETMEM_STR: &str = ":";
 const DELIMITER_STR: &str = ",";
 const UNDERSCORE_STR: &str = "_";
+const ARG_COUNT_STR: &str = "#";
 
 const HISTORY_STR: &str = "$";
 const MEMORY_STR: &str = "$m";
 const HISTORY_MEMORY_QUALIFIER_STR: &str = "m";
 
+const DICE_STR: &str = "d";
+const DICE_EXPLODE_STR: &str = "!";
+const DICE_KEEP_STR: &str = "k";
+const DICE_DROP_STR: &str = "d";
+const DICE_HIGHEST_STR: &str = "h";
+const DICE_LOWEST_STR: &str = "l";
+
 const PI_CONST_STR: &str = "PI";
 const E_CONST_STR: &str = "E";
 
@@ -85,6 +96,21 @@ lazy_static! {
      */
     pub static ref OP_PAR_C: Terminal = Terminal::Literal(String::from(OP_PAR_C_STR));
 
+    /**
+     * Opening bracket of a list
+     */
+    pub static ref OP_LIST_O: Terminal = Terminal::Literal(String::from(OP_LIST_O_STR));
+
+    /**
+     * Closing bracket of a list
+     */
+    pub static ref OP_LIST_C: Terminal = Terminal::Literal(String::from(OP_LIST_C_STR));
+
+    /**
+     * Inclusive range of numbers
+     */
+    pub static ref OP_RANGE: Terminal = Terminal::Literal(String::from(OP_RANGE_STR));
+
     /**
      * Set memory
      */
@@ -95,6 +121,11 @@ lazy_static! {
      */
     pub static ref DELIMITER: Terminal = Terminal::Literal(String::from(DELIMITER_STR));
 
+    /**
+     * The number of arguments a function takes from the stack in postfix notation, i.e. 'max#3'
+     */
+    pub static ref ARG_COUNT: Terminal = Terminal::Literal(String::from(ARG_COUNT_STR));
+
     /**
      * Function argument delimiter
      */
@@ -115,6 +146,36 @@ lazy_static! {
      */
     pub static ref HISTORY_MEMORY_QUALIFIER: Terminal = Terminal::Literal(String::from(HISTORY_MEMORY_QUALIFIER_STR));
 
+    /**
+     * Separates the number of dice from the number of sides in dice notation
+     */
+    pub static ref DICE: Terminal = Terminal::Literal(String::from(DICE_STR));
+
+    /**
+     * Marks dice that are rolled again whenever they roll their highest value
+     */
+    pub static ref DICE_EXPLODE: Terminal = Terminal::Literal(String::from(DICE_EXPLODE_STR));
+
+    /**
+     * Keep a number of the rolled dice
+     */
+    pub static ref DICE_KEEP: Terminal = Terminal::Literal(String::from(DICE_KEEP_STR));
+
+    /**
+     * Drop a number of the rolled dice
+     */
+    pub static ref DICE_DROP: Terminal = Terminal::Literal(String::from(DICE_DROP_STR));
+
+    /**
+     * Keep/drop the highest of the rolled dice
+     */
+    pub static ref DICE_HIGHEST: Terminal = Terminal::Literal(String::from(DICE_HIGHEST_STR));
+
+    /**
+     * Keep/drop the lowest of the rolled dice
+     */
+    pub static ref DICE_LOWEST: Terminal = Terminal::Literal(String::from(DICE_LOWEST_STR));
+
     /**
      * Constant PI (Archimedes' Constant)
      */
@@ -3,19 +3,46 @@ use super::{terminal, expression, parser};
 
 /**
  * The provided input, should parse into an Expr
- * whose to_string method equals the expected string.
+ * whose to_string method equals the expected string,
+ * and whose canonical string parses back into the same Expr.
  */
 fn default_test(input: &str, expected: &str) {
     match parser::Parser::default().parse(input) {
         Ok(result) => {
             let result_string = result.to_string();
             assert_eq!(expected, result_string.as_str());
+            round_trip_test(&result);
         },
         Err(err) => panic!("{err} (at {0})", err.lah())
 
     };
 }
 
+/**
+ * The canonical string of the provided Expr should parse back into the same Expr.
+ */
+fn round_trip_test(expr: &expression::Expr) {
+    let canonical = expr.to_canonical_string();
+
+    match parser::Parser::default().parse(&canonical) {
+        Ok(result) => assert_eq!(expr, &result, "Testing round trip of '{canonical}'"),
+        Err(err) => panic!("{err} (at {0}) in '{canonical}'", err.lah())
+    };
+}
+
+/**
+ * The provided input should parse into an Expr whose canonical string equals the expected string, and parses back into the same Expr.
+ */
+fn canonical_test(input: &str, expected: &str) {
+    match parser::Parser::default().parse(input) {
+        Ok(result) => {
+            assert_eq!(expected, result.to_canonical_string(), "Testing '{input}'");
+            round_trip_test(&result);
+        },
+        Err(err) => panic!("{err} (at {0})", err.lah())
+    };
+}
+
 #[test]
 /**
  * Test that an empty string parses to an
@@ -119,7 +146,7 @@ fn binary_expression_order_of_operations_test_2() {
  * Test that ^ takes precedence over *,/,%.
  */
 fn binary_expression_order_of_operations_test_3() {
-    const EXPECTED: &str = "0 % [1 ^ 2] / 3";
+    const EXPECTED: &str = "0 % (1 ^ 2) / 3";
     let input: &str = "0 % 1 ^ 2 / 3";
     default_test(input, EXPECTED);
 }
@@ -129,7 +156,7 @@ fn binary_expression_order_of_operations_test_3() {
  * Test that *,/,% take precedence over +/-
  */
 fn binary_expression_order_of_operations_test_4() {
-    const EXPECTED: &str = "0 + [1 * 2] - 3";
+    const EXPECTED: &str = "0 + (1 * 2) - 3";
     let input: &str = "0 + 1 * 2 - 3";
     default_test(input, EXPECTED);
 }
@@ -139,7 +166,7 @@ fn binary_expression_order_of_operations_test_4() {
  * Test that ^ takes precedence over +/-
  */
 fn binary_expression_order_of_operations_test_5() {
-    const EXPECTED: &str = "5 + [2 ^ 3] - 5";
+    const EXPECTED: &str = "5 + (2 ^ 3) - 5";
     let input: &str = "5 + 2 ^ 3 - 5";
     default_test(input, EXPECTED);
 }
@@ -149,7 +176,7 @@ fn binary_expression_order_of_operations_test_5() {
  * Test that *,/,% can take precedence over ^ using parentheses.
  */
 fn binary_expression_order_of_operations_test_6() {
-    const EXPECTED: &str = "[0 % 1] ^ [2 / 3]";
+    const EXPECTED: &str = "(0 % 1) ^ (2 / 3)";
     let input: &str = "(0 % 1) ^ (2 / 3)";
     default_test(input, EXPECTED);
 }
@@ -159,7 +186,7 @@ fn binary_expression_order_of_operations_test_6() {
  * Test that +/- can take precedence over *,/,% using parentheses.
  */
 fn binary_expression_order_of_operations_test_7() {
-    const EXPECTED: &str = "[[0 + 1] * 2] - 3";
+    const EXPECTED: &str = "((0 + 1) * 2) - 3";
     let input: &str = "(0 + 1) * 2 - 3";
     default_test(input, EXPECTED);
 }
@@ -169,7 +196,7 @@ fn binary_expression_order_of_operations_test_7() {
  * Test that +/- can take precedence over ^ using parentheses.
  */
 fn binary_expression_order_of_operations_test_8() {
-    const EXPECTED: &str = "5 + [2 ^ [3 - 5]]";
+    const EXPECTED: &str = "5 + (2 ^ (3 - 5))";
     let input: &str = "5 + 2 ^ (3 - 5)";
     default_test(input, EXPECTED);
 }
@@ -199,7 +226,7 @@ fn unary_suffix_expression() {
  * Test that unary prefixes in binary expressions are read in
  */
 fn unary_prefix_in_binary_expression() {
-    const EXPECTED: &str = "[-5] + [-6]";
+    const EXPECTED: &str = "(-5) + (-6)";
     let input: &str = "-5 + -6";
     default_test(input, EXPECTED);
 }
@@ -209,7 +236,7 @@ fn unary_prefix_in_binary_expression() {
  * Test that unary suffixes in binary expressions are read in
  */
 fn unary_suffix_in_binary_expression() {
-    const EXPECTED: &str = "[5!] / [6!]";
+    const EXPECTED: &str = "(5!) / (6!)";
     let input: &str = "5! / 6!";
     default_test(input, EXPECTED);
 }
@@ -220,7 +247,7 @@ fn unary_suffix_in_binary_expression() {
  * to the same expression
  */
 fn unary_prefix_and_suffix_in_expression() {
-    const EXPECTED: &str = "-[5!]";
+    const EXPECTED: &str = "-(5!)";
     let input: &str = "-5!";
     default_test(input, EXPECTED);
 }
@@ -250,7 +277,7 @@ fn unary_repeated_suffix() {
  * Test that both unary prefixes and suffixes can be repeated
  */
 fn unary_repeated_prefix_and_suffix() {
-    const EXPECTED: &str = "---[5!!]";
+    const EXPECTED: &str = "---(5!!)";
     let input: &str = "---5!!";
     default_test(input, EXPECTED);
 }
@@ -261,7 +288,7 @@ fn unary_repeated_prefix_and_suffix() {
  * seperately in the same binary expression
  */
 fn unary_prefix_and_suffix_in_binary_expression() {
-    const EXPECTED: &str = "[-5] ^ [3!!]";
+    const EXPECTED: &str = "(-5) ^ (3!!)";
     let input: &str = "-5 ^ 3!!";
     default_test(input, EXPECTED);
 }
@@ -272,7 +299,7 @@ fn unary_prefix_and_suffix_in_binary_expression() {
  * expressions
  */
 fn unary_prefix_parentheses() {
-    const EXPECTED: &str = "-[1 + 2]";
+    const EXPECTED: &str = "-(1 + 2)";
     let input: &str = "-(1+2)";
     default_test(input, EXPECTED);
 }
@@ -283,7 +310,7 @@ fn unary_prefix_parentheses() {
  * expressions
  */
 fn unary_suffixes_parentheses() {
-    const EXPECTED: &str = "[1 + 2]!";
+    const EXPECTED: &str = "(1 + 2)!";
     let input: &str = "(1+2)!";
     default_test(input, EXPECTED);
 }
@@ -294,7 +321,7 @@ fn unary_suffixes_parentheses() {
  * Test that unary prefixes work in an exponent
  */
 fn unary_prefix_in_exponent() {
-    const EXPECTED: &str = "1 ^ [-3]";
+    const EXPECTED: &str = "1 ^ (-3)";
     let input: &str = "1^-3";
     default_test(input, EXPECTED);
 }
@@ -304,7 +331,7 @@ fn unary_prefix_in_exponent() {
  * Test that unary suffixes work in an exponent
  */
 fn unary_suffix_in_exponent() {
-    const EXPECTED: &str = "1 ^ [3!]";
+    const EXPECTED: &str = "1 ^ (3!)";
     let input: &str = "1^3!";
     default_test(input, EXPECTED);
 }
@@ -335,7 +362,7 @@ fn one_arg_function_0() {
  * when the arg is a more complex expression
  */
 fn one_arg_function_1() {
-    const EXPECTED: &str = "fx([5 ^ 2] - [5 + 1])";
+    const EXPECTED: &str = "fx((5 ^ 2) - (5 + 1))";
     let input: &str = "fx(5^2-(5+1))";
     default_test(input, EXPECTED);
 }
@@ -356,7 +383,7 @@ fn multiple_arg_function_0() {
  * when the some args are a more complex expressions
  */
 fn multiple_arg_function_1() {
-    const EXPECTED: &str = "fx(1, 2 + 3, 4, 3 - [1 ^ [6 - 2]])";
+    const EXPECTED: &str = "fx(1, 2 + 3, 4, 3 - (1 ^ (6 - 2)))";
     let input: &str = "fx(1, 2 + 3, 4, 3 - 1^(6-2))";
     default_test(input, EXPECTED);
 }
@@ -376,7 +403,7 @@ fn infix_function_0() {
  * Test that infix functions are parsed properly
  */
 fn infix_function_1() {
-    const EXPECTED: &str = "[5 * 2] add [3 ^ 1]";
+    const EXPECTED: &str = "(5 * 2) add (3 ^ 1)";
     let input: &str = "5 * 2 add 3 ^ 1";
     default_test(input, EXPECTED);
 }
@@ -386,7 +413,7 @@ fn infix_function_1() {
  * Test that infix functions are parsed properly
  */
 fn infix_function_2() {
-    const EXPECTED: &str = "5 * [[2 add 3] ^ 1]";
+    const EXPECTED: &str = "5 * ((2 add 3) ^ 1)";
     let input: &str = "5 * (2 add 3) ^ 1";
     default_test(input, EXPECTED);
 }
@@ -500,7 +527,7 @@ fn memory_access_1() {
  * Test that memory assignment parses as intended
  */
 fn memory_assign_0() {
-    const EXPECTED: &str = "$m0:[5]";
+    const EXPECTED: &str = "$m0:(5)";
     let input: &str = "$m0: 5";
 
     default_test(input, EXPECTED);
@@ -511,7 +538,7 @@ fn memory_assign_0() {
  * Test that memory assignment parses as intended
  */
 fn memory_assign_1() {
-    const EXPECTED: &str = "$m0:[5 ^ [$1 + $m0]]";
+    const EXPECTED: &str = "$m0:(5 ^ ($1 + $m0))";
     let input: &str = "$m0: 5 ^ ($1 + $m0)";
 
     default_test(input, EXPECTED);
@@ -635,4 +662,623 @@ fn invalid_syntax_10() {
 fn invalid_syntax_11() {
     let input: &str = "-!";
     default_test(input, "");
-}
\ No newline at end of file
+}
+#[test]
+/**
+ * Test that dice notation is read in
+ */
+fn dice_0() {
+    const EXPECTED: &str = "3d6";
+    let input: &str = "3d6";
+    default_test(input, EXPECTED);
+}
+
+#[test]
+/**
+ * Test that dice notation without a count
+ * rolls a single die
+ */
+fn dice_1() {
+    const EXPECTED: &str = "1d20";
+    let input: &str = "d20";
+    default_test(input, EXPECTED);
+}
+
+#[test]
+/**
+ * Test that dice notation can keep or
+ * drop the highest or lowest dice
+ */
+fn dice_2() {
+    let inputs: Vec<(&str, &str)> = vec![
+        ("2d20kh1", "2d20kh1"),
+        ("2D20KL1", "2d20kl1"),
+        ("4d6dl1", "4d6dl1"),
+        ("4d6dh2", "4d6dh2")
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+}
+
+#[test]
+/**
+ * Test that exploding dice are read in,
+ * and may be combined with keeping dice
+ */
+fn dice_3() {
+    const EXPECTED: &str = "4d6!kh3";
+    let input: &str = "4d6!kh3";
+    default_test(input, EXPECTED);
+}
+
+#[test]
+/**
+ * Test that dice can be used within
+ * larger expressions
+ */
+fn dice_4() {
+    const EXPECTED: &str = "3d6 + (2d4 * 2)";
+    let input: &str = "3d6 + 2d4 * 2";
+    default_test(input, EXPECTED);
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that dice notation requires a
+ * number of sides
+ */
+fn dice_5() {
+    let input: &str = "3d";
+    default_test(input, "");
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that keeping dice requires a
+ * number of dice to keep
+ */
+fn dice_6() {
+    let input: &str = "4d6kh";
+    default_test(input, "");
+}
+
+#[test]
+/**
+ * Test that lists are read in
+ */
+fn list_0() {
+    let inputs: Vec<(&str, &str)> = vec![
+        ("[1,2, 3]", "[1, 2, 3]"),
+        ("[ ]", "[]"),
+        ("[1 + 2, sqrt(4)]", "[1 + 2, sqrt(4)]"),
+        ("[1, 2] * 2", "[1, 2] * 2")
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+}
+
+#[test]
+/**
+ * Test that ranges are read in, with lower
+ * priority than other operators
+ */
+fn range_0() {
+    let inputs: Vec<(&str, &str)> = vec![
+        ("1..10", "1..10"),
+        ("1.5..2.5", "1.5..2.5"),
+        ("1 .. 2 * 3", "1..(2 * 3)")
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+}
+
+#[test]
+/**
+ * Test that indexes are read in
+ */
+fn index_0() {
+    let inputs: Vec<(&str, &str)> = vec![
+        ("[1, 2][0]", "[1, 2][0]"),
+        ("$0[-1]", "$0[-1]"),
+        ("(1..3)[1]!", "(1..3)[1]!")
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that lists must be closed
+ */
+fn list_1() {
+    let input: &str = "[1, 2";
+    default_test(input, "");
+}
+
+#[test]
+/**
+ * Test that lists of lists are parsed as matrix literals
+ */
+fn matrix_0() {
+    let input: &str = "[[1, 2], [3,4]] * [[5, 6],[7, 8]]";
+    default_test(input, "[[1, 2], [3, 4]] * [[5, 6], [7, 8]]");
+}
+
+#[test]
+#[should_panic]
+/**
+ * Test that ranges must have an end
+ */
+fn range_1() {
+    let input: &str = "1..";
+    default_test(input, "");
+}
+
+#[test]
+/**
+ * Test that names which aren't followed by parentheses are read as variables
+ */
+fn variable_0() {
+    let inputs: Vec<(&str, &str)> = vec![
+        ("x", "x"),
+        ("rate_2 * (1 + x)", "rate_2 * (1 + x)"),
+        ("x max y", "x max y"),
+        ("pi * r^2", "PI * (r ^ 2)"),
+        ("d6 + d", "1d6 + d")
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+
+    assert!(matches!(parser::Parser::default().parse("total"), Ok(expression::Expr::ExprPrime(expr)) if matches!(*expr, expression::ExprPrime::Id(_))));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+/**
+ * Test that expressions serialize to JSON, with each node tagged by its type
+ */
+fn serde_0() {
+    let parsed = parser::Parser::default().parse("-1 + sqrt($m2)").unwrap();
+    let json = serde_json::to_string(&parsed).unwrap();
+
+    assert_eq!(json, r#"{"type":"ExprPrime","value":{"type":"BinaryInfixExpression","value":[{"type":"UnopPrefixesExpression","value":[["Neg"],{"type":"Number","value":{"value":1.0}}]},[["Add",{"type":"Func","value":{"type":"FuncWithArgs","value":[{"value":"sqrt"},[{"type":"AccessMem","value":{"value":2}}]]}}]]]}}"#);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+/**
+ * Test that expressions read back from JSON are unchanged
+ */
+fn serde_1() {
+    let inputs = vec![
+        "",
+        "1 + 2 * 3 ^ 4 mod 5",
+        "max(1, $0, pi, e())!",
+        "$m3: 4d6!kh3 + [1, 2][0]",
+        "sum(1..10) / (2 - 3)"
+    ];
+
+    for input in inputs {
+        let parsed = parser::Parser::default().parse(input).unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        let deserialized: expression::Expr = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, deserialized, "Testing round trip of {json}");
+    }
+}
+
+#[test]
+#[cfg(feature = "serde")]
+/**
+ * Test that number literals too large to represent are written by name, and read back from JSON unchanged
+ */
+fn serde_2() {
+    let parsed = parser::Parser::default().parse(format!("1{} + 2", "0".repeat(400)).as_str()).unwrap();
+    let json = serde_json::to_string(&parsed).unwrap();
+
+    assert!(json.contains(r#"{"type":"Number","value":{"value":"inf"}}"#), "Testing {json}");
+    assert_eq!(serde_json::from_str::<expression::Expr>(&json).unwrap(), parsed);
+}
+
+#[test]
+/**
+ * Test that expressions are displayed as input which evaluates to the same result, grouping with parentheses rather than brackets
+ */
+fn display_0() {
+    use crate::calculator::Calculator;
+
+    let calculator = Calculator::default();
+
+    for input in ["1 + 2 * 3", "(1 + 2) * 3", "2 ^ 3 ^ 2", "-2 ^ 2", "-3!", "1..2 * 2", "[1, 2][1] * 3!", "$m0: 2 * 3 add 4"] {
+        let displayed = parser::Parser::default().parse(input).unwrap().to_string();
+        assert_eq!(calculator.evaluate(&displayed), calculator.evaluate(input), "Testing '{input}' displayed as '{displayed}'");
+    }
+}
+
+#[test]
+/**
+ * Test that postfix input is parsed into the same tree as the equivalent infix input
+ */
+fn postfix_0() {
+    let postfix = parser::Parser::new(parser::ParserSettings::new().with_notation(parser::Notation::Postfix));
+    let infix = parser::Parser::default();
+
+    let inputs = vec![
+        ("3 4 + 2 *", "(3 + 4) * 2"),
+        ("3 4 2 * +", "3 + 4 * 2"),
+        ("1 2 + 3 - 4 +", "1 + 2 - 3 + 4"),
+        ("1 2 3 - -", "1 - (2 - 3)"),
+        ("2 3 4 ^ ^", "2 ^ 3 ^ 4"),
+        ("2 3 ^ 4 ^", "(2 ^ 3) ^ 4"),
+        ("-3 2 ^", "-3 ^ 2"),
+        ("3 ! !", "3!!"),
+        ("1 2 + !", "(1 + 2)!"),
+        ("-3 !", "(-3)!"),
+        ("2 sqrt", "sqrt(2)"),
+        ("1 2 3 max#3", "max(1, 2, 3)"),
+        ("random#0", "random()"),
+        ("pi 2 /", "pi / 2"),
+        ("max(1, 2) 3 *", "max(1, 2) * 3"),
+        ("(x) 2d6 + [1, 2][0] -", "(x) + 2d6 - [1, 2][0]"),
+        ("5 1 + $m0:", "$m0: 5 + 1"),
+        ("$m0 3 %", "$m0 % 3")
+    ];
+
+    for (postfix_input, infix_input) in inputs {
+        assert_eq!(postfix.parse(postfix_input), infix.parse(infix_input), "Testing '{postfix_input}'");
+
+        //Postfix input is written in infix notation
+        round_trip_test(&postfix.parse(postfix_input).unwrap());
+    }
+}
+
+#[test]
+/**
+ * Test that missing operands are read from history, and that every value left on the stack is kept
+ */
+fn postfix_1() {
+    let postfix = parser::Parser::new(parser::ParserSettings::new().with_notation(parser::Notation::Postfix));
+    let infix = parser::Parser::default();
+
+    assert_eq!(postfix.parse("+"), infix.parse("$1 + $0"));
+    assert_eq!(postfix.parse("2 *"), infix.parse("$0 * 2"));
+    assert_eq!(postfix.parse("+ +"), infix.parse("$2 + ($1 + $0)"));
+
+    let stack = postfix.parse_postfix_stack("3 + 4 5").unwrap();
+    let expected: Vec<expression::ExprPrime> = ["$0 + 3", "4", "5"].iter()
+        .map(|input| match infix.parse(input) {
+            Ok(expression::Expr::ExprPrime(expr)) => *expr,
+            _ => panic!("Failed to parse '{input}'")
+        })
+        .collect();
+
+    assert_eq!(stack.values, expected);
+    assert_eq!(stack.history_operands, 1);
+}
+
+#[test]
+/**
+ * Test that invalid postfix input is rejected
+ */
+fn postfix_2() {
+    let postfix = parser::Parser::new(parser::ParserSettings::new().with_notation(parser::Notation::Postfix));
+
+    let inputs = vec![
+        "3 4",
+        "3 4+",
+        "max#",
+        "3 )"
+    ];
+
+    for input in inputs {
+        assert!(postfix.parse(input).is_err(), "Testing '{input}'");
+    }
+
+    assert_eq!(postfix.parse(""), Ok(expression::Expr::None));
+}
+
+#[test]
+/**
+ * Test that constants are separated from the next item by whitespace, the same as numbers
+ */
+fn postfix_3() {
+    let postfix = parser::Parser::new(parser::ParserSettings::new().with_notation(parser::Notation::Postfix));
+    let infix = parser::Parser::default();
+
+    assert_eq!(postfix.parse("pi 2 *"), infix.parse("pi * 2"));
+    assert_eq!(postfix.parse("e  pi +"), infix.parse("e + pi"));
+    assert_eq!(postfix.parse_postfix_stack("pi 2").unwrap().values.len(), 2);
+}
+
+#[test]
+/**
+ * Test that postfix functions can't take more operands than a list can hold, rather than building them all
+ */
+fn postfix_4() {
+    use crate::calculator::calculator_interpreter::interpreter::value::MAX_LIST_LEN;
+
+    let postfix = parser::Parser::new(parser::ParserSettings::new().with_notation(parser::Notation::Postfix));
+
+    assert!(postfix.parse("1 max#99999999999").is_err());
+    assert!(postfix.parse(format!("1 max#{}", MAX_LIST_LEN + 1).as_str()).is_err());
+    assert!(postfix.parse("1 2 3 max#3").is_ok());
+}
+
+#[test]
+/**
+ * Test that postfix input pushes onto history, and that operators consume the results of history they take as operands
+ */
+fn postfix_stack_0() {
+    use crate::calculator::{Calculator, calculator_interpreter::interpreter::value::Value};
+
+    let mut calculator = Calculator::default();
+
+    assert_eq!(calculator.evaluate_postfix_stack("3 4").unwrap(), vec![Value::Number(3_f64), Value::Number(4_f64)]);
+    assert_eq!(calculator.evaluate_postfix_stack("5").unwrap(), vec![Value::Number(5_f64)]);
+    assert_eq!(calculator.evaluate_postfix_stack("+").unwrap(), vec![Value::Number(9_f64)]);
+    assert_eq!(calculator.clone_current_state().history, vec![Value::Number(3_f64), Value::Number(9_f64)]);
+
+    //Equal results are each kept on the stack
+    assert_eq!(calculator.evaluate_postfix_stack("2 2 $0").unwrap(), vec![Value::Number(2_f64), Value::Number(2_f64), Value::Number(9_f64)]);
+    assert_eq!(calculator.evaluate_postfix_stack("max#5 $m0:").unwrap(), vec![Value::Number(9_f64)]);
+    assert_eq!(calculator.clone_current_state().history, vec![Value::Number(9_f64)]);
+    assert_eq!(calculator.evaluate("$m0").unwrap(), Value::Number(9_f64));
+}
+
+#[test]
+/**
+ * Test that failed postfix input leaves history and memory unchanged, and that postfix notation can be used for single expressions
+ */
+fn postfix_stack_1() {
+    use crate::calculator::{Calculator, calculator_interpreter::interpreter::value::Value};
+
+    let mut calculator = Calculator::default();
+    calculator.evaluate_postfix_stack("1 2").unwrap();
+
+    let state = calculator.clone_current_state();
+
+    for input in ["* * *", "$m0: (y) +", "3 4+", "(y)"] {
+        assert!(calculator.evaluate_postfix_stack(input).is_err(), "Testing '{input}'");
+        assert_eq!(calculator.clone_current_state(), state, "Testing '{input}'");
+    }
+
+    calculator.set_notation(parser::Notation::Postfix);
+    assert_eq!(calculator.notation(), parser::Notation::Postfix);
+    assert_eq!(calculator.evaluate("3 4 + 2 *").unwrap(), Value::Number(14_f64));
+    assert!(calculator.evaluate("3 4").is_err());
+
+    //Function bodies are always infix
+    calculator.define_function("double", &["x"], "x * 2").unwrap();
+    assert_eq!(calculator.evaluate("4 double").unwrap(), Value::Number(8_f64));
+}
+
+#[test]
+/**
+ * Test that integers written in hexadecimal, octal and binary are read in
+ */
+fn radix_integer() {
+    let inputs = vec![
+        ("0xFF", "255"),
+        ("0Xff + 0x10", "255 + 16"),
+        ("0o17", "15"),
+        ("0b1010 * 2", "10 * 2"),
+        ("-0b1", "-1"),
+        ("0", "0"),
+        ("0.5", "0.5")
+    ];
+
+    for (input, expected) in inputs {
+        default_test(input, expected);
+    }
+
+    for input in ["0x", "0b102", "0xG", "0o8"] {
+        assert!(parser::Parser::default().parse(input).is_err(), "Testing '{input}'");
+    }
+}
+
+/**
+ * The provided input should parse into an Expr whose MathML, without the enclosing 'math' element, equals the expected string, and which round trips through its canonical string.
+ */
+fn mathml_test(input: &str, expected: &str) {
+    match parser::Parser::default().parse(input) {
+        Ok(result) => {
+            assert_eq!(format!("<math display=\"block\">{expected}</math>"), result.to_mathml(), "Testing '{input}'");
+            round_trip_test(&result);
+        },
+        Err(err) => panic!("{err} (at {0})", err.lah())
+    };
+}
+
+#[test]
+/**
+ * Test that divisions are typeset as fractions, exponents as superscripts, and square roots and absolute values with their symbols
+ */
+fn mathml_0() {
+    let inputs = vec![
+        ("(1 + 2) / 3", "<mfrac><mrow><mn>1</mn><mo>+</mo><mn>2</mn></mrow><mn>3</mn></mfrac>"),
+        ("a * b / c * d", "<mrow><mfrac><mrow><mi>a</mi><mo>×</mo><mi>b</mi></mrow><mi>c</mi></mfrac><mo>×</mo><mi>d</mi></mrow>"),
+        ("2^3^4", "<msup><mn>2</mn><msup><mn>3</mn><mn>4</mn></msup></msup>"),
+        ("sqrt(x + 1)", "<msqrt><mrow><mi>x</mi><mo>+</mo><mn>1</mn></mrow></msqrt>"),
+        ("abs(-3)", "<mrow><mo>|</mo><mrow><mo>−</mo><mn>3</mn></mrow><mo>|</mo></mrow>"),
+        ("pi * $0", "<mrow><mi>π</mi><mo>×</mo><mi>$0</mi></mrow>"),
+        ("[[1, 2], [3, 4]]", "<mrow><mo>[</mo><mtable><mtr><mtd><mn>1</mn></mtd><mtd><mn>2</mn></mtd></mtr><mtr><mtd><mn>3</mn></mtd><mtd><mn>4</mn></mtd></mtr></mtable><mo>]</mo></mrow>"),
+        ("max(1, 2)", "<mrow><mi>max</mi><mrow><mo>(</mo><mrow><mn>1</mn><mo>,</mo><mn>2</mn></mrow><mo>)</mo></mrow></mrow>"),
+        ("[1, 2, 3][0]", "<msub><mrow><mo>[</mo><mrow><mn>1</mn><mo>,</mo><mn>2</mn><mo>,</mo><mn>3</mn></mrow><mo>]</mo></mrow><mn>0</mn></msub>")
+    ];
+
+    for (input, expected) in inputs {
+        mathml_test(input, expected);
+    }
+
+    assert_eq!(expression::Expr::None.to_mathml(), "<math display=\"block\"></math>");
+}
+
+#[test]
+/**
+ * Test that only the parentheses needed by the priority of the operators are kept
+ */
+fn mathml_1() {
+    let inputs = vec![
+        ("(1 + 2) + 3", "<mrow><mrow><mn>1</mn><mo>+</mo><mn>2</mn></mrow><mo>+</mo><mn>3</mn></mrow>"),
+        ("1 - (2 - 3)", "<mrow><mn>1</mn><mo>−</mo><mrow><mo>(</mo><mrow><mn>2</mn><mo>−</mo><mn>3</mn></mrow><mo>)</mo></mrow></mrow>"),
+        ("(1 + 2) * 3", "<mrow><mrow><mo>(</mo><mrow><mn>1</mn><mo>+</mo><mn>2</mn></mrow><mo>)</mo></mrow><mo>×</mo><mn>3</mn></mrow>"),
+        ("2 * (3 / 4)", "<mrow><mn>2</mn><mo>×</mo><mfrac><mn>3</mn><mn>4</mn></mfrac></mrow>"),
+        ("-2^2", "<msup><mrow><mo>(</mo><mrow><mo>−</mo><mn>2</mn></mrow><mo>)</mo></mrow><mn>2</mn></msup>"),
+        ("-(2^2)", "<mrow><mo>−</mo><msup><mn>2</mn><mn>2</mn></msup></mrow>"),
+        ("(2^3)!", "<mrow><mrow><mo>(</mo><msup><mn>2</mn><mn>3</mn></msup><mo>)</mo></mrow><mo>!</mo></mrow>"),
+        ("6 mod (4 + 1)", "<mrow><mn>6</mn><mo>mod</mo><mrow><mo>(</mo><mrow><mn>4</mn><mo>+</mo><mn>1</mn></mrow><mo>)</mo></mrow></mrow>"),
+        ("-7 mod 3", "<mrow><mrow><mo>−</mo><mn>7</mn></mrow><mo>mod</mo><mn>3</mn></mrow>"),
+        ("-7 % 3", "<mrow><mrow><mo>−</mo><mn>7</mn></mrow><mo>%</mo><mn>3</mn></mrow>")
+    ];
+
+    for (input, expected) in inputs {
+        mathml_test(input, expected);
+    }
+}
+
+/**
+ * The provided input should parse into an Expr whose LaTeX equals the expected string, and which round trips through its canonical string.
+ */
+fn latex_test(input: &str, expected: &str) {
+    match parser::Parser::default().parse(input) {
+        Ok(result) => {
+            assert_eq!(expected, result.to_latex(), "Testing '{input}'");
+            round_trip_test(&result);
+        },
+        Err(err) => panic!("{err} (at {0})", err.lah())
+    };
+}
+
+#[test]
+/**
+ * Test that expressions are exported as LaTeX the same way as they are typeset as MathML
+ */
+fn latex_0() {
+    let inputs = vec![
+        ("(1 + 2) / 3", "\\frac{1 + 2}{3}"),
+        ("2^3^4", "{2}^{{3}^{4}}"),
+        ("-2^2", "{\\left(- 2\\right)}^{2}"),
+        ("sqrt(x + 1) * abs(-3)", "\\sqrt{x + 1} \\times \\left|- 3\\right|"),
+        ("1 - (2 - 3)", "1 - \\left(2 - 3\\right)"),
+        ("floor(x_1)", "\\left\\lfloor\\operatorname{x\\_1}\\right\\rfloor"),
+        ("[[1, 2], [3, 4]]", "\\left[\\begin{matrix}1 & 2 \\\\ 3 & 4\\end{matrix}\\right]"),
+        ("6 mod 4", "6 \\bmod 4"),
+        ("-7 mod 3", "- 7 \\bmod 3"),
+        ("-7 % 3", "- 7 \\% 3"),
+        ("[1, 2, 3][0] * 2", "{\\left[1, 2, 3\\right]}_{0} \\times 2")
+    ];
+
+    for (input, expected) in inputs {
+        latex_test(input, expected);
+    }
+
+    assert_eq!(expression::Expr::None.to_latex(), "");
+}
+
+#[test]
+/**
+ * Test that known functions, constants, and references are written as LaTeX commands and escaped
+ */
+fn latex_1() {
+    let inputs = vec![
+        ("sin(x)^2 + cos(x)^2", "{\\sin\\left(x\\right)}^{2} + {\\cos\\left(x\\right)}^{2}"),
+        ("pi * e", "\\pi \\times e"),
+        ("max(1, 2)!", "\\max\\left(1, 2\\right)!"),
+        ("myfunc(2, 3)", "\\operatorname{myfunc}\\left(2, 3\\right)"),
+        ("$m0: 1 + 2", "\\operatorname{\\$m0} := 1 + 2"),
+        ("2d6 + $0", "\\text{2d6} + \\operatorname{\\$0}")
+    ];
+
+    for (input, expected) in inputs {
+        latex_test(input, expected);
+    }
+}
+
+#[test]
+/**
+ * Test that expressions are written with consistent spacing, and keep the parentheses they were written with
+ */
+fn canonical_0() {
+    let inputs = vec![
+        ("1+2*3", "1 + 2 * 3"),
+        ("(1+2)*3", "(1 + 2) * 3"),
+        ("((1))", "((1))"),
+        ("2^3^4", "2 ^ 3 ^ 4"),
+        ("-2^-x!", "-2 ^ -x!"),
+        ("max( 1 ,2,pi ,E )", "max(1, 2, pi, e)"),
+        ("random( )", "random()"),
+        ("6 mod 4+1", "6 mod 4 + 1"),
+        ("1 .. 5", "1..5"),
+        ("[ [1,2] , [3 ,4] ][0][1]", "[[1, 2], [3, 4]][0][1]"),
+        ("[ ]", "[]"),
+        ("$m0:1+$m1:  2", "$m0: 1 + $m1: 2"),
+        ("-$m0:3", "-$m0: 3"),
+        ("4d6!kh3 + d8 - 0xFF", "4d6!kh3 + 1d8 - 0xFF"),
+        ("$0*$12 /0.5", "$0 * $12 / 0.5")
+    ];
+
+    for (input, expected) in inputs {
+        canonical_test(input, expected);
+    }
+
+    assert_eq!(expression::Expr::None.to_canonical_string(), "");
+}
+
+#[test]
+/**
+ * Test that parentheses are added where an expression which wasn't parsed needs them to keep its meaning
+ */
+fn canonical_1() {
+    use expression::{BinopInfix, ExprPrime, MemoryToken, NumberToken, UnopPrefix, UnopSuffix};
+
+    let n = |value: f64| Box::new(ExprPrime::Number(NumberToken::new(value)));
+    let sum = ExprPrime::BinaryInfixExpression(n(1.0), vec![(BinopInfix::Add, n(2.0))]);
+    let store = ExprPrime::StoreMem(MemoryToken::new(0), n(1.0));
+
+    let inputs = vec![
+        (ExprPrime::BinaryInfixExpression(Box::new(sum.clone()), vec![(BinopInfix::Mult, n(3.0))]), "(1 + 2) * 3"),
+        (ExprPrime::BinaryInfixExpression(n(3.0), vec![(BinopInfix::Sub, Box::new(sum.clone()))]), "3 - (1 + 2)"),
+        (ExprPrime::UnopPrefixesExpression(vec![UnopPrefix::Neg], Box::new(sum.clone())), "-(1 + 2)"),
+        (ExprPrime::UnopSuffixesExpression(Box::new(sum.clone()), vec![UnopSuffix::Fac]), "(1 + 2)!"),
+        (ExprPrime::Index(Box::new(sum), n(0.0)), "(1 + 2)[0]"),
+        (ExprPrime::BinaryInfixExpression(Box::new(store.clone()), vec![(BinopInfix::Add, n(2.0))]), "($m0: 1) + 2"),
+        (ExprPrime::BinaryInfixExpression(n(2.0), vec![(BinopInfix::Add, Box::new(store))]), "2 + $m0: 1")
+    ];
+
+    for (expr, expected) in inputs {
+        assert_eq!(expr.to_canonical_string(), expected);
+    }
+}
+
+#[test]
+/**
+ * Test that numbers are written as they were, keeping their radix and digits, and stay the same when formatted again
+ */
+fn canonical_2() {
+    let inputs = vec![
+        ("0xFF+0o17*0B101", "0xFF + 0o17 * 0B101"),
+        ("12345678901234567890123", "12345678901234567890123"),
+        ("1.50 - 007", "1.50 - 007"),
+        ("[0x1F,2.0]", "[0x1F, 2.0]")
+    ];
+
+    for (input, expected) in inputs {
+        canonical_test(input, expected);
+
+        let reparsed = parser::Parser::default().parse(expected).unwrap();
+        assert_eq!(reparsed.to_canonical_string(), expected, "Testing '{expected}' formatted again");
+    }
+}
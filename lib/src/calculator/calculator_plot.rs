@@ -0,0 +1,484 @@
+use super::{Calculator, calculator_err::CalculatorErr, calculator_interpreter::interpreter::{Interpreter, value::Value}, calculator_parser::expression::{Expr, ExprPrime}};
+
+#[cfg(test)]
+pub mod tests;
+
+/**
+ * The variable of a function which doesn't use any undefined variables, i.e. '2 * pi'
+ */
+pub const DEFAULT_VARIABLE: &str = "x";
+
+/**
+ * The most times the space between two samples is halved to follow the curve where it bends
+ */
+const MAX_DEPTH: u32 = 6;
+
+/**
+ * How many times the space between two samples is halved to tell a jump from a steep part of the curve
+ */
+const JUMP_STEPS: u32 = 16;
+
+/**
+ * How many times the space around a root or extremum is narrowed to locate it
+ */
+const REFINE_STEPS: u32 = 48;
+
+/**
+ * The share of the values furthest from the rest that are left out when fitting the viewport to a function
+ */
+const FIT_OUTLIERS: f64 = 0.05_f64;
+
+/**
+ * The most ticks asked for on an axis; more could never be labelled legibly
+ */
+pub const MAX_TICKS: usize = 100;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExtremumKind {
+    Minimum,
+    Maximum
+}
+
+/**
+ * A point where the curve turns, which is the lowest or highest point around it
+ */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Extremum {
+    pub point: Point,
+    pub kind: ExtremumKind
+}
+
+/**
+ * The region of the plane shown by a plot
+ */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Viewport {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64
+}
+
+impl Viewport {
+    pub fn new(x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Self {
+        Self {
+            x_min,
+            x_max,
+            y_min,
+            y_max
+        }
+    }
+
+    /**
+     * The viewport across the x range, with the y range fitted to the values so that they can all be seen.
+     * If a few values are much further out than the rest, such as near an asymptote, they are left out so that the rest aren't flattened.
+     */
+    pub fn fit(x_min: f64, x_max: f64, values: impl IntoIterator<Item = f64>) -> Self {
+        let mut values: Vec<f64> = values.into_iter()
+            .filter(|y| y.is_finite())
+            .collect();
+
+        if values.is_empty() {
+            return Self::new(x_min, x_max, -1_f64, 1_f64);
+        }
+
+        values.sort_by(f64::total_cmp);
+
+        let last = values.len() - 1;
+        let outliers = (last as f64 * FIT_OUTLIERS).round() as usize;
+
+        let (mut y_min, mut y_max) = (values[0], values[last]);
+        let (trimmed_min, trimmed_max) = (values[outliers], values[last - outliers]);
+
+        if y_max - y_min > (trimmed_max - trimmed_min) * 4_f64 {
+            (y_min, y_max) = (trimmed_min, trimmed_max);
+        }
+
+        //Leave a margin around the values, or some space around a flat line
+        let margin = match y_max - y_min {
+            height if height > f64::EPSILON * y_max.abs().max(1_f64) => height * 0.1_f64,
+            _ => y_max.abs().max(1_f64)
+        };
+
+        Self::new(x_min, x_max, y_min - margin, y_max + margin)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f64 {
+        self.y_max - self.y_min
+    }
+
+    /**
+     * Zoom around the point by the factor, where a factor greater than 1 zooms out
+     */
+    pub fn zoom(&self, factor: f64, center: Point) -> Self {
+        Self::new(
+            center.x + (self.x_min - center.x) * factor,
+            center.x + (self.x_max - center.x) * factor,
+            center.y + (self.y_min - center.y) * factor,
+            center.y + (self.y_max - center.y) * factor
+        )
+    }
+
+    /**
+     * Move by the distance in each direction
+     */
+    pub fn pan(&self, dx: f64, dy: f64) -> Self {
+        Self::new(self.x_min + dx, self.x_max + dx, self.y_min + dy, self.y_max + dy)
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new(-10_f64, 10_f64, -10_f64, 10_f64)
+    }
+}
+
+/**
+ * A sampled function, as the pieces which the curve is continuous over, with its roots and extrema
+ */
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Curve {
+    pub segments: Vec<Vec<Point>>,
+    pub roots: Vec<Point>,
+    pub extrema: Vec<Extremum>
+}
+
+/**
+ * An expression of a single variable, parsed once so that it can be evaluated for many values of the variable
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlotFunction {
+    expression: ExprPrime,
+    variable: String
+}
+
+impl PlotFunction {
+    /**
+     * Parse an expression to plot or tabulate. Its variable is the only variable it uses which isn't defined, or 'x' if there isn't one.
+     */
+    pub fn parse(calculator: &Calculator, expression: &str) -> Result<Self, CalculatorErr> {
+        let expression = match calculator.parse(expression)? {
+            Expr::ExprPrime(expr) => *expr,
+            Expr::None => return Err(CalculatorErr::eval_err("Expected an expression."))
+        };
+
+        let undefined: Vec<&str> = expression.variables().into_iter()
+            .filter(|name| calculator.interpreter().get_variable(name).is_none())
+            .collect();
+
+        let variable = match undefined.as_slice() {
+            [] => String::from(DEFAULT_VARIABLE),
+            [variable] => variable.to_string(),
+            _ => return Err(CalculatorErr::eval_err(format!("Expected an expression of one variable; found '{}'.", undefined.join("', '")).as_str()))
+        };
+
+        Ok(Self {
+            expression,
+            variable
+        })
+    }
+
+    pub fn variable(&self) -> &str {
+        &self.variable
+    }
+
+    pub fn expression(&self) -> &ExprPrime {
+        &self.expression
+    }
+
+    /**
+     * The value of the function, if it is a finite number. Evaluating it leaves memory and the source of randomness unchanged.
+     */
+    pub fn evaluate(&self, interpreter: &Interpreter, x: f64) -> Option<f64> {
+        interpreter.with_scratch_state(|| self.value(interpreter, x))
+    }
+
+    pub fn sample(&self, interpreter: &Interpreter, viewport: &Viewport, resolution: usize) -> Curve {
+        interpreter.with_scratch_state(|| sample(|x| self.value(interpreter, x), viewport, resolution))
+    }
+
+    /**
+     * The viewport across the x range which fits the values of all of the functions
+     */
+    pub fn fit(functions: &[PlotFunction], interpreter: &Interpreter, x_min: f64, x_max: f64, resolution: usize) -> Viewport {
+        let resolution = resolution.max(1);
+
+        let values = interpreter.with_scratch_state(|| functions.iter()
+            .flat_map(|function| (0..=resolution)
+                .filter_map(move |i| function.value(interpreter, x_min + (x_max - x_min) * i as f64 / resolution as f64)))
+            .collect::<Vec<f64>>());
+
+        Viewport::fit(x_min, x_max, values)
+    }
+
+    /**
+     * The value of the function, if it is a finite number, changing memory and the source of randomness as any evaluation does
+     */
+    fn value(&self, interpreter: &Interpreter, x: f64) -> Option<f64> {
+        match interpreter.evaluate_with_variables(&self.expression, &[(&self.variable, Value::Number(x))]) {
+            Ok(Value::Number(y)) if y.is_finite() => Some(y),
+            _ => None
+        }
+    }
+}
+
+/**
+ * A round value to mark on an axis, with its label
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tick {
+    pub value: f64,
+    pub label: String
+}
+
+/**
+ * Evenly spaced round values between min and max to mark on an axis, about 'count' of them, spaced by 1, 2 or 5 times a power of 10.
+ * Labels have as many decimal places as the spacing needs, i.e. '0.25' rather than '0.25000000000000006'.
+ */
+pub fn ticks(min: f64, max: f64, count: usize) -> Vec<Tick> {
+    let span = max - min;
+
+    if count == 0 || !span.is_finite() || span <= 0_f64 {
+        return Vec::new();
+    }
+
+    let rough = span / count.min(MAX_TICKS) as f64;
+    let magnitude = 10.0_f64.powf(rough.log10().floor());
+
+    //The spacing nearest to spreading the ticks evenly
+    let step = [1_f64, 2_f64, 5_f64, 10_f64].into_iter()
+        .map(|multiple| multiple * magnitude)
+        .min_by(|a, b| (a / rough).ln().abs().total_cmp(&(b / rough).ln().abs()))
+        .unwrap_or(magnitude);
+
+    let decimals = (-step.log10().floor()).max(0_f64) as usize;
+
+    //Allow for rounding, so that a tick on either end isn't left out
+    let (first, last) = ((min / step - 1e-9).ceil() as i64, (max / step + 1e-9).floor() as i64);
+
+    //A spacing too small to represent would saturate the range, rather than give about 'count' ticks
+    if last.saturating_sub(first) > 4 * MAX_TICKS as i64 {
+        return Vec::new();
+    }
+
+    (first..=last)
+        .map(|i| {
+            let value = i as f64 * step;
+
+            Tick {
+                value,
+                //Avoid labelling zero as '-0'
+                label: format!("{:.decimals$}", if value == 0_f64 { 0_f64 } else { value })
+            }
+        })
+        .collect()
+}
+
+/**
+ * Sample a function across the viewport, at 'resolution' evenly spaced values of x and more wherever the curve bends.
+ *
+ * The curve is split where the function is undefined, and where it jumps, such as at the asymptotes of 'tan(x)' or the steps of 'floor(x)'.
+ * Its roots and extrema are located within each continuous piece.
+ */
+pub fn sample(f: impl Fn(f64) -> Option<f64>, viewport: &Viewport, resolution: usize) -> Curve {
+    let resolution = resolution.max(2);
+
+    //Bends smaller than a fraction of the height between samples can't be seen
+    let tolerance = viewport.height().abs() / resolution as f64 / 2_f64;
+
+    let mut points: Vec<(f64, Option<f64>)> = vec![(viewport.x_min, f(viewport.x_min))];
+
+    for i in 1..=resolution {
+        let x = viewport.x_min + viewport.width() * i as f64 / resolution as f64;
+        let next = (x, f(x));
+
+        refine(&f, points[points.len() - 1], next, tolerance, 0, &mut points);
+        points.push(next);
+    }
+
+    let mut curve = Curve::default();
+    let mut segment: Vec<Point> = Vec::new();
+
+    for (x, y) in points {
+        let point = match y {
+            Some(y) => Point::new(x, y),
+            None => {
+                curve.segments.extend(Some(std::mem::take(&mut segment)).filter(|segment| !segment.is_empty()));
+                continue;
+            }
+        };
+
+        //Only steep changes need to be checked for being a jump
+        if let Some(previous) = segment.last() {
+            if (point.y - previous.y).abs() > tolerance * 8_f64 && is_jump(&f, *previous, point) {
+                curve.segments.push(std::mem::take(&mut segment));
+            }
+        }
+
+        segment.push(point);
+    }
+
+    curve.segments.extend(Some(segment).filter(|segment| !segment.is_empty()));
+
+    for segment in &curve.segments {
+        find_roots(&f, segment, &mut curve.roots);
+        find_extrema(&f, segment, tolerance, &mut curve.roots, &mut curve.extrema);
+    }
+
+    curve.roots.sort_by(|a, b| a.x.total_cmp(&b.x));
+    curve.roots.dedup_by(|a, b| (a.x - b.x).abs() <= viewport.width().abs() / resolution as f64 * f64::EPSILON.sqrt());
+
+    curve
+}
+
+/**
+ * Add samples between two samples where the curve between them bends, or where the function stops being defined
+ */
+fn refine(f: &impl Fn(f64) -> Option<f64>, start: (f64, Option<f64>), end: (f64, Option<f64>), tolerance: f64, depth: u32, points: &mut Vec<(f64, Option<f64>)>) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+
+    let x = (start.0 + end.0) / 2_f64;
+    let middle = (x, f(x));
+
+    let bends = match (start.1, middle.1, end.1) {
+        (Some(start), Some(middle), Some(end)) => (middle - (start + end) / 2_f64).abs() > tolerance,
+        (None, None, None) => false,
+        //Follow the edge of where the function is defined
+        _ => true
+    };
+
+    if bends {
+        refine(f, start, middle, tolerance, depth + 1, points);
+        points.push(middle);
+        refine(f, middle, end, tolerance, depth + 1, points);
+    }
+}
+
+/**
+ * Check if the function jumps between two points, rather than being steep.
+ * Following the half of the interval which changes most, the change of a continuous function shrinks, while a jump stays the same or grows.
+ */
+fn is_jump(f: &impl Fn(f64) -> Option<f64>, mut start: Point, mut end: Point) -> bool {
+    let change = (end.y - start.y).abs();
+
+    for _ in 0..JUMP_STEPS {
+        let x = (start.x + end.x) / 2_f64;
+
+        let middle = match f(x) {
+            Some(y) => Point::new(x, y),
+            None => return true
+        };
+
+        if (middle.y - start.y).abs() > (end.y - middle.y).abs() {
+            end = middle;
+        }
+        else {
+            start = middle;
+        }
+    }
+
+    (end.y - start.y).abs() > change / 2_f64
+}
+
+/**
+ * Locate the roots in a continuous piece of the curve, where it touches or crosses zero
+ */
+fn find_roots(f: &impl Fn(f64) -> Option<f64>, segment: &[Point], roots: &mut Vec<Point>) {
+    roots.extend(segment.iter().filter(|point| point.y == 0_f64));
+
+    for pair in segment.windows(2) {
+        let (mut start, mut end) = (pair[0], pair[1]);
+
+        if start.y * end.y >= 0_f64 {
+            continue;
+        }
+
+        for _ in 0..REFINE_STEPS {
+            let x = (start.x + end.x) / 2_f64;
+
+            match f(x) {
+                Some(y) if (y < 0_f64) == (start.y < 0_f64) => start = Point::new(x, y),
+                Some(y) => end = Point::new(x, y),
+                None => break
+            }
+        }
+
+        roots.push(Point::new((start.x + end.x) / 2_f64, 0_f64));
+    }
+}
+
+/**
+ * Locate the extrema in a continuous piece of the curve, where it turns from rising to falling or from falling to rising.
+ * An extremum which touches zero is also a root.
+ */
+fn find_extrema(f: &impl Fn(f64) -> Option<f64>, segment: &[Point], tolerance: f64, roots: &mut Vec<Point>, extrema: &mut Vec<Extremum>) {
+    //The change leading up to the current point, and where it started, skipping flat stretches
+    let mut previous: Option<(f64, f64)> = None;
+
+    for pair in segment.windows(2) {
+        let change = pair[1].y - pair[0].y;
+
+        if change == 0_f64 {
+            continue;
+        }
+
+        let turn = previous.filter(|(previous_change, _)| previous_change.signum() != change.signum());
+        let start = previous.map_or(pair[0].x, |(previous_change, previous_start)| if previous_change.signum() == change.signum() { previous_start } else { pair[0].x });
+
+        if let Some((previous_change, previous_start)) = turn {
+            let kind = if previous_change > 0_f64 { ExtremumKind::Maximum } else { ExtremumKind::Minimum };
+
+            if let Some(extremum) = locate_extremum(f, (previous_start, pair[1].x), kind) {
+                if extremum.point.y.abs() <= tolerance * f64::EPSILON.sqrt() {
+                    roots.push(Point::new(extremum.point.x, 0_f64));
+                }
+
+                extrema.push(extremum);
+            }
+        }
+
+        previous = Some((change, start));
+    }
+}
+
+/**
+ * Narrow down the highest or lowest point between the values of x, using a golden section search
+ */
+fn locate_extremum(f: &impl Fn(f64) -> Option<f64>, (mut start, mut end): (f64, f64), kind: ExtremumKind) -> Option<Extremum> {
+    let ratio = (5_f64.sqrt() - 1_f64) / 2_f64;
+    let sign = if kind == ExtremumKind::Maximum { 1_f64 } else { -1_f64 };
+
+    for _ in 0..REFINE_STEPS {
+        let (lower, upper) = (end - ratio * (end - start), start + ratio * (end - start));
+
+        match (f(lower), f(upper)) {
+            (Some(lower_value), Some(upper_value)) if lower_value * sign >= upper_value * sign => end = upper,
+            (Some(_), Some(_)) => start = lower,
+            _ => break
+        }
+    }
+
+    let x = (start + end) / 2_f64;
+    f(x).map(|y| Extremum { point: Point::new(x, y), kind })
+}
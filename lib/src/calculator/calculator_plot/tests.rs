@@ -0,0 +1,229 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::calculator::{Calculator, calculator_interpreter::interpreter::value::Value, calculator_plot::{Curve, ExtremumKind, PlotFunction, Point, Viewport, sample, ticks, MAX_TICKS}};
+
+/**
+ * Sample the expression across the viewport
+ */
+fn sample_expression(expression: &str, viewport: Viewport) -> Curve {
+    let calculator = Calculator::default();
+
+    match PlotFunction::parse(&calculator, expression) {
+        Ok(function) => function.sample(calculator.interpreter(), &viewport, 200),
+        Err(err) => panic!("{err}")
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "Expected {expected}, found {actual}");
+}
+
+#[test]
+/**
+ * Test that the roots of sine are found at each multiple of pi
+ */
+fn roots_0() {
+    let curve = sample_expression("sin(x)", Viewport::new(-7_f64, 7_f64, -1.5_f64, 1.5_f64));
+    let roots: Vec<f64> = curve.roots.iter().map(|root| root.x).collect();
+
+    assert_eq!(roots.len(), 5, "Roots: {roots:?}");
+
+    for (root, k) in roots.iter().zip(-2..=2) {
+        assert_close(*root, k as f64 * PI);
+    }
+
+    assert_eq!(curve.segments.len(), 1);
+}
+
+#[test]
+/**
+ * Test that the curve of tangent is split at its asymptotes, without roots where it jumps from positive to negative
+ */
+fn segments_0() {
+    let curve = sample_expression("tan(x)", Viewport::new(-3_f64, 3_f64, -10_f64, 10_f64));
+
+    assert_eq!(curve.segments.len(), 3);
+
+    for (segment, asymptote) in curve.segments.iter().zip([-FRAC_PI_2, FRAC_PI_2]) {
+        assert!(segment.last().unwrap().x < asymptote);
+    }
+
+    let roots: Vec<f64> = curve.roots.iter().map(|root| root.x).collect();
+    assert_eq!(roots.len(), 1, "Roots: {roots:?}");
+    assert_close(roots[0], 0_f64);
+}
+
+#[test]
+/**
+ * Test that the curve of floor is split at each step
+ */
+fn segments_1() {
+    let curve = sample_expression("floor(x)", Viewport::new(0.5_f64, 3.5_f64, -1_f64, 4_f64));
+
+    assert_eq!(curve.segments.len(), 4);
+
+    for segment in &curve.segments {
+        assert!(segment.iter().all(|point| point.y == segment[0].y));
+    }
+
+    assert!(curve.extrema.is_empty());
+}
+
+#[test]
+/**
+ * Test that the curve is only drawn where the function is defined
+ */
+fn segments_2() {
+    let curve = sample_expression("sqrt(x)", Viewport::new(-4_f64, 4_f64, -1_f64, 3_f64));
+
+    assert_eq!(curve.segments.len(), 1);
+    assert!(curve.segments[0][0].x >= 0_f64 && curve.segments[0][0].x < 0.1_f64);
+    assert_close(curve.segments[0].last().unwrap().x, 4_f64);
+}
+
+#[test]
+/**
+ * Test that minima and maxima are found, and that a minimum touching zero is a root
+ */
+fn extrema_0() {
+    let curve = sample_expression("x^2", Viewport::new(-3_f64, 2_f64, -1_f64, 9_f64));
+
+    assert_eq!(curve.extrema.len(), 1);
+    assert_eq!(curve.extrema[0].kind, ExtremumKind::Minimum);
+    assert_close(curve.extrema[0].point.x, 0_f64);
+    assert_eq!(curve.roots.len(), 1);
+    assert_close(curve.roots[0].x, 0_f64);
+
+    let curve = sample_expression("cos(t)", Viewport::new(-4_f64, 4_f64, -1.5_f64, 1.5_f64));
+    let kinds: Vec<ExtremumKind> = curve.extrema.iter().map(|extremum| extremum.kind).collect();
+
+    assert_eq!(kinds, vec![ExtremumKind::Minimum, ExtremumKind::Maximum, ExtremumKind::Minimum]);
+    assert_close(curve.extrema[1].point.x, 0_f64);
+    assert_close(curve.extrema[1].point.y, 1_f64);
+}
+
+#[test]
+/**
+ * Test that a function which only touches zero between samples has a root
+ */
+fn extrema_1() {
+    let curve = sample(|x| Some((x - 0.123_f64).powi(2)), &Viewport::new(-1_f64, 1_f64, -1_f64, 1_f64), 10);
+
+    assert_eq!(curve.roots.len(), 1);
+    assert!((curve.roots[0].x - 0.123_f64).abs() < 1e-4);
+}
+
+#[test]
+/**
+ * Test which variable is plotted
+ */
+fn variable_0() {
+    let mut calculator = Calculator::default();
+
+    let variable = |calculator: &Calculator, expression: &str| PlotFunction::parse(calculator, expression).map(|function| function.variable().to_string());
+
+    assert_eq!(variable(&calculator, "x^2 + 1"), Ok(String::from("x")));
+    assert_eq!(variable(&calculator, "sin(t) * pi"), Ok(String::from("t")));
+    assert_eq!(variable(&calculator, "2 * pi"), Ok(String::from("x")));
+    assert!(variable(&calculator, "a * x").is_err());
+    assert!(variable(&calculator, "").is_err());
+
+    calculator.set_variable("a", Value::Number(2_f64)).unwrap();
+    assert_eq!(variable(&calculator, "a * x"), Ok(String::from("x")));
+
+    let function = PlotFunction::parse(&calculator, "a * x").unwrap();
+    assert_eq!(function.evaluate(calculator.interpreter(), 3_f64), Some(6_f64));
+    assert_eq!(PlotFunction::parse(&calculator, "1 / x").unwrap().evaluate(calculator.interpreter(), 0_f64), None);
+}
+
+#[test]
+/**
+ * Test that evaluating with variables bound leaves the variables and history as they were
+ */
+fn bindings_0() {
+    let mut calculator = Calculator::default();
+    calculator.set_variable("x", Value::Number(5_f64)).unwrap();
+
+    let function = PlotFunction::parse(&calculator, "x + 1").unwrap();
+
+    assert_eq!(function.evaluate(calculator.interpreter(), 1_f64), Some(2_f64));
+    assert_eq!(calculator.interpreter().get_variable("x"), Some(Value::Number(5_f64)));
+    assert_eq!(function.evaluate(calculator.interpreter(), 2_f64), Some(3_f64));
+    assert!(!calculator.has_history());
+
+    let function = PlotFunction::parse(&Calculator::default(), "y * 2").unwrap();
+
+    assert_eq!(function.evaluate(calculator.interpreter(), 4_f64), Some(8_f64));
+    assert_eq!(calculator.interpreter().get_variable("y"), None);
+}
+
+#[test]
+/**
+ * Test that sampling a function leaves memory and the source of randomness as they were
+ */
+fn bindings_1() {
+    let mut calculator = Calculator::default();
+    calculator.seed(1);
+
+    let function = PlotFunction::parse(&calculator, "$m0: x + rand() + 1d6").unwrap();
+
+    assert!(function.evaluate(calculator.interpreter(), 1_f64).is_some());
+    assert!(!function.sample(calculator.interpreter(), &Viewport::default(), 20).segments.is_empty());
+    PlotFunction::fit(&[function], calculator.interpreter(), -1_f64, 1_f64, 20);
+
+    let mut expected = Calculator::default();
+    expected.seed(1);
+
+    assert_eq!(calculator.evaluate("$m0").unwrap(), Value::Number(0_f64));
+    assert_eq!(calculator.evaluate("rand()").unwrap(), expected.evaluate("rand()").unwrap());
+}
+
+#[test]
+/**
+ * Test fitting, zooming and panning the viewport
+ */
+fn viewport_0() {
+    let viewport = Viewport::fit(0_f64, 1_f64, [0_f64, 10_f64, f64::NAN]);
+    assert_eq!(viewport, Viewport::new(0_f64, 1_f64, -1_f64, 11_f64));
+
+    //A single value far from the rest is left out
+    let mut values = vec![1e9];
+    values.extend((0..=100).map(|i| i as f64 / 10_f64));
+    let viewport = Viewport::fit(0_f64, 1_f64, values);
+    assert!(viewport.y_max < 20_f64);
+
+    let viewport = Viewport::fit(0_f64, 1_f64, [3_f64, 3_f64]);
+    assert_eq!(viewport, Viewport::new(0_f64, 1_f64, 0_f64, 6_f64));
+    assert_eq!(Viewport::fit(0_f64, 1_f64, []), Viewport::new(0_f64, 1_f64, -1_f64, 1_f64));
+
+    let viewport = Viewport::default().zoom(0.5_f64, Point::new(2_f64, 0_f64));
+    assert_eq!(viewport, Viewport::new(-4_f64, 6_f64, -5_f64, 5_f64));
+    assert_eq!(viewport.pan(1_f64, -1_f64), Viewport::new(-3_f64, 7_f64, -6_f64, 4_f64));
+    assert_eq!(viewport.width(), 10_f64);
+    assert_eq!(viewport.height(), 10_f64);
+}
+
+#[test]
+/**
+ * Test the values marked on an axis, and their labels
+ */
+fn ticks_0() {
+    let labels = |min: f64, max: f64, count: usize| ticks(min, max, count).into_iter().map(|tick| tick.label).collect::<Vec<String>>();
+
+    assert_eq!(labels(-10_f64, 10_f64, 4), vec!["-10", "-5", "0", "5", "10"]);
+    assert_eq!(labels(0.05_f64, 1_f64, 4), vec!["0.2", "0.4", "0.6", "0.8", "1.0"]);
+    assert_eq!(labels(-0.3_f64, 0.3_f64, 6), vec!["-0.3", "-0.2", "-0.1", "0.0", "0.1", "0.2", "0.3"]);
+    assert_eq!(labels(120_f64, 480_f64, 3), vec!["200", "300", "400"]);
+    assert!(ticks(1_f64, 1_f64, 4).is_empty());
+    assert!(ticks(0_f64, 1_f64, 0).is_empty());
+}
+
+#[test]
+/**
+ * Test that asking for more ticks than could be labelled, or for a spacing too small to represent, gives a bounded number of ticks
+ */
+fn ticks_1() {
+    assert_eq!(ticks(-1_f64, 1_f64, usize::MAX).len(), ticks(-1_f64, 1_f64, MAX_TICKS).len());
+    assert!(ticks(-1_f64, 1_f64, usize::MAX).len() <= 4 * MAX_TICKS);
+    assert!(ticks(-f64::MIN_POSITIVE, f64::MIN_POSITIVE, usize::MAX).len() <= 4 * MAX_TICKS);
+}
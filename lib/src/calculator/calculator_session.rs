@@ -0,0 +1,255 @@
+use super::{
+    CalculatorState,
+    calculator_err::CalculatorErr,
+    calculator_interpreter::interpreter::{function::UserFunction, value::{Value, matrix::Matrix}},
+    calculator_parser::{expression::{Expr, ExprPrime}, parser::Parser}
+};
+
+#[cfg(test)]
+pub mod tests;
+
+/**
+ * The version of the session format written by this version of the calculator
+ */
+pub const SESSION_VERSION: u32 = 2;
+
+/**
+ * The first version of the session format with variables and user defined functions
+ */
+const DEFINITIONS_VERSION: u32 = 2;
+
+/**
+ * The first word of every session, followed by its version
+ */
+const SESSION_HEADER: &str = "calculator-session";
+
+const HISTORY_RECORD: &str = "history";
+const MEMORY_RECORD: &str = "memory";
+const VARIABLE_RECORD: &str = "variable";
+const FUNCTION_RECORD: &str = "function";
+
+/**
+ * Separates the parameters of a function from its body
+ */
+const FUNCTION_BODY_SEPARATOR: &str = "=";
+
+const NUMBER_VALUE: &str = "number";
+const LIST_VALUE: &str = "list";
+const MATRIX_VALUE: &str = "matrix";
+
+/*
+ * A session is a plain text format shared by every front end, so that sessions can be moved between them.
+ *
+ * The first line is the header and version, i.e. 'calculator-session 2', followed by a record on each line.
+ * Empty lines and lines starting with '#' are ignored.
+ *
+ * Records:
+ *  - 'history {value}': a result in history, from oldest to most recent
+ *  - 'memory {index} {value}': the value stored in memory at the index; memory which isn't listed holds 0
+ *  - 'variable {name} {value}': the value of a variable, from version 2
+ *  - 'function {name} {param}... = {body}': a user defined function, with its body written as an expression, from version 2
+ *
+ * Values:
+ *  - 'number {n}'
+ *  - 'list {n}...': the elements of the list
+ *  - 'matrix {rows} {cols} {n}...': the elements of the matrix, row by row
+ *
+ * Numbers are written so that they are read back exactly, including 'NaN', 'inf' and '-inf'.
+ */
+
+impl CalculatorState {
+    /**
+     * Write the history, memory, variables and user defined functions to the session format; rolls are not saved
+     */
+    pub fn to_session_string(&self) -> String {
+        let mut lines: Vec<String> = vec![format!("{SESSION_HEADER} {SESSION_VERSION}")];
+
+        lines.extend(self.history.iter()
+            .map(|value| format!("{HISTORY_RECORD} {}", write_value(value))));
+
+        //Only memory that has been assigned to needs to be saved
+        lines.extend(self.memory.iter()
+            .enumerate()
+            .filter(|(_, value)| **value != Value::default())
+            .map(|(i, value)| format!("{MEMORY_RECORD} {i} {}", write_value(value))));
+
+        lines.extend(self.variables.iter()
+            .map(|(name, value)| format!("{VARIABLE_RECORD} {name} {}", write_value(value))));
+
+        lines.extend(self.functions.iter()
+            .map(|(name, function)| format!("{FUNCTION_RECORD} {name} {}", write_function(function))));
+
+        let mut session = lines.join("\n");
+        session.push('\n');
+        session
+    }
+
+    /**
+     * Read history, memory, variables and user defined functions from the session format
+     */
+    pub fn from_session_str(session: &str) -> Result<Self, CalculatorErr> {
+        let mut lines = session.lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let version = match lines.next() {
+            Some((n, line)) => {
+                let mut words = line.split_whitespace();
+
+                if words.next() != Some(SESSION_HEADER) {
+                    return Err(session_err(n, format!("Expected '{SESSION_HEADER}' header.").as_str()));
+                }
+
+                words.next()
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .ok_or_else(|| session_err(n, "Expected a session version."))?
+            },
+            None => return Err(CalculatorErr::session_err("Session is empty."))
+        };
+
+        if version > SESSION_VERSION {
+            return Err(CalculatorErr::session_err(format!("Session version {version} is newer than the supported version {SESSION_VERSION}.").as_str()));
+        }
+
+        let mut history: Vec<Value> = Vec::new();
+        let mut memory: Vec<Value> = vec![Value::default(); u8::MAX as usize];
+        let mut variables: Vec<(String, Value)> = Vec::new();
+        let mut functions: Vec<(String, UserFunction)> = Vec::new();
+
+        for (n, line) in lines {
+            let mut words = line.split_whitespace();
+
+            match words.next() {
+                Some(HISTORY_RECORD) => history.push(read_value(n, &mut words)?),
+                Some(MEMORY_RECORD) => {
+                    let index = words.next()
+                        .and_then(|i| i.parse::<usize>().ok())
+                        .filter(|i| *i < memory.len())
+                        .ok_or_else(|| session_err(n, format!("Expected a memory index below {}.", memory.len()).as_str()))?;
+
+                    memory[index] = read_value(n, &mut words)?;
+                },
+                Some(VARIABLE_RECORD) if version >= DEFINITIONS_VERSION => {
+                    let name = read_name(n, words.next())?;
+                    variables.push((name, read_value(n, &mut words)?));
+                },
+                Some(FUNCTION_RECORD) if version >= DEFINITIONS_VERSION => {
+                    let name = read_name(n, words.next())?;
+                    functions.push((name, read_function(n, &mut words)?));
+                },
+                Some(record) => return Err(session_err(n, format!("Unknown record '{record}'.").as_str())),
+                None => {}
+            }
+        }
+
+        Ok(Self::new(memory, history).with_definitions(variables, functions))
+    }
+}
+
+fn write_value(value: &Value) -> String {
+    fn join(numbers: &[f64]) -> String {
+        numbers.iter()
+            .map(|n| format!(" {n}"))
+            .collect()
+    }
+
+    match value {
+        Value::Number(n) => format!("{NUMBER_VALUE} {n}"),
+        Value::List(list) => format!("{LIST_VALUE}{}", join(list)),
+        Value::Matrix(matrix) => format!("{MATRIX_VALUE} {} {}{}", matrix.rows(), matrix.cols(), join(matrix.data()))
+    }
+}
+
+fn read_value<'a, I>(line: usize, words: &mut I) -> Result<Value, CalculatorErr>
+    where I : Iterator<Item = &'a str>
+{
+    let kind = words.next();
+
+    let mut read_numbers = || -> Result<Vec<f64>, CalculatorErr> {
+        words.map(|word| word.parse::<f64>().map_err(|_| session_err(line, format!("Invalid number '{word}'.").as_str())))
+            .collect()
+    };
+
+    match kind {
+        Some(NUMBER_VALUE) => {
+            let numbers = read_numbers()?;
+
+            if numbers.len() != 1 {
+                return Err(session_err(line, format!("Expected a single number; got {}.", numbers.len()).as_str()));
+            }
+
+            Ok(Value::Number(numbers[0]))
+        },
+        Some(LIST_VALUE) => Ok(Value::List(read_numbers()?)),
+        Some(MATRIX_VALUE) => {
+            let numbers = read_numbers()?;
+
+            if numbers.len() < 2 {
+                return Err(session_err(line, "Expected the dimensions of the matrix."));
+            }
+
+            let (rows, cols) = (numbers[0], numbers[1]);
+
+            if rows.fract() != 0_f64 || cols.fract() != 0_f64 || rows < 1_f64 || cols < 1_f64 {
+                return Err(session_err(line, format!("Invalid matrix dimensions '{rows}x{cols}'.").as_str()));
+            }
+
+            Matrix::new(rows as usize, cols as usize, numbers[2..].to_vec())
+                .map(Value::Matrix)
+                .map_err(|e| session_err(line, e.to_string().as_str()))
+        },
+        Some(kind) => Err(session_err(line, format!("Unknown value type '{kind}'.").as_str())),
+        None => Err(session_err(line, "Expected a value."))
+    }
+}
+
+fn write_function(function: &UserFunction) -> String {
+    let params: String = function.params.iter()
+        .map(|param| format!("{param} "))
+        .collect();
+
+    format!("{params}{FUNCTION_BODY_SEPARATOR} {}", function.body.to_canonical_string())
+}
+
+/**
+ * Read the parameters of a function up to the separator, and the rest of the line as its body
+ */
+fn read_function<'a, I>(line: usize, words: &mut I) -> Result<UserFunction, CalculatorErr>
+    where I : Iterator<Item = &'a str>
+{
+    let mut params: Vec<String> = Vec::new();
+
+    loop {
+        match words.next() {
+            Some(FUNCTION_BODY_SEPARATOR) => break,
+            Some(param) => params.push(read_name(line, Some(param))?),
+            None => return Err(session_err(line, format!("Expected '{FUNCTION_BODY_SEPARATOR}' before the body of the function.").as_str()))
+        }
+    }
+
+    let body = words.collect::<Vec<&str>>().join(" ");
+
+    match Parser::default().parse(&body) {
+        Ok(Expr::ExprPrime(expr)) => Ok(UserFunction::new(params, *expr)),
+        Ok(Expr::None) => Err(session_err(line, "Expected the body of the function.")),
+        Err(err) => Err(session_err(line, format!("Invalid function body '{body}': {err}").as_str()))
+    }
+}
+
+/**
+ * Read the name of a variable, function or parameter, which is only valid if it parses as a name
+ */
+fn read_name(line: usize, word: Option<&str>) -> Result<String, CalculatorErr> {
+    match word {
+        Some(name) => match Parser::default().parse(name) {
+            Ok(Expr::ExprPrime(expr)) if matches!(&*expr, ExprPrime::Id(id) if id.value == name) => Ok(name.to_string()),
+            _ => Err(session_err(line, format!("Invalid name '{name}'.").as_str()))
+        },
+        None => Err(session_err(line, "Expected a name."))
+    }
+}
+
+fn session_err(line: usize, message: &str) -> CalculatorErr {
+    CalculatorErr::session_err(format!("Invalid session at line {line}: {message}").as_str())
+}
@@ -0,0 +1,229 @@
+use crate::calculator::{Calculator, CalculatorState, calculator_interpreter::interpreter::value::{Value, matrix::Matrix}};
+
+/**
+ * Memory with the given values stored at the given indexes
+ */
+fn memory_with(values: Vec<(usize, Value)>) -> Vec<Value> {
+    let mut memory = vec![Value::default(); u8::MAX as usize];
+
+    for (i, value) in values {
+        memory[i] = value;
+    }
+
+    memory
+}
+
+/**
+ * The provided state should be written and read back unchanged
+ */
+fn round_trip_test(state: CalculatorState) {
+    let session = state.to_session_string();
+
+    match CalculatorState::from_session_str(&session) {
+        Ok(result) => assert_eq!(result, state, "Testing round trip of session:\n{session}"),
+        Err(err) => panic!("{err}")
+    }
+}
+
+#[test]
+/**
+ * Test that history and memory of every type of value are read back unchanged
+ */
+fn session_0() {
+    let history = vec![
+        Value::Number(1.5_f64),
+        Value::Number(-0.1_f64),
+        Value::Number(1e300_f64),
+        Value::List(vec![1_f64, 2_f64, 3_f64]),
+        Value::List(Vec::new()),
+        Value::Matrix(Matrix::from_rows(vec![vec![1_f64, 2_f64], vec![3_f64, 4_f64]]).unwrap())
+    ];
+
+    let memory = memory_with(vec![
+        (0, Value::Number(std::f64::consts::PI)),
+        (254, Value::List(vec![0.1_f64, 0.2_f64]))
+    ]);
+
+    round_trip_test(CalculatorState::new(memory, history));
+}
+
+#[test]
+/**
+ * Test that infinite and NaN numbers are read back
+ */
+fn session_1() {
+    let session = CalculatorState::new(memory_with(Vec::new()), vec![Value::Number(f64::INFINITY), Value::Number(f64::NEG_INFINITY), Value::Number(f64::NAN)])
+        .to_session_string();
+
+    let history = CalculatorState::from_session_str(&session).unwrap().history;
+
+    assert_eq!(history[0], Value::Number(f64::INFINITY));
+    assert_eq!(history[1], Value::Number(f64::NEG_INFINITY));
+    assert!(history[2].as_number().unwrap().is_nan());
+}
+
+#[test]
+/**
+ * Test that the written format has a versioned header, and only includes memory that has been assigned
+ */
+fn session_2() {
+    let state = CalculatorState::new(memory_with(vec![(5, Value::Number(6_f64))]), vec![Value::Number(2_f64), Value::List(vec![1_f64, 2_f64])]);
+
+    assert_eq!(state.to_session_string(), "calculator-session 2\nhistory number 2\nhistory list 1 2\nmemory 5 number 6\n");
+}
+
+#[test]
+/**
+ * Test that empty lines and comments are ignored
+ */
+fn session_3() {
+    let session = "# Saved session\n\ncalculator-session 1\n  history number 4\n\n# Memory\nmemory 1 matrix 1 2 5 6\n";
+    let state = CalculatorState::from_session_str(session).unwrap();
+
+    assert_eq!(state.history, vec![Value::Number(4_f64)]);
+    assert_eq!(state.memory[1], Value::Matrix(Matrix::new(1, 2, vec![5_f64, 6_f64]).unwrap()));
+    assert_eq!(state.memory.len(), u8::MAX as usize);
+}
+
+#[test]
+/**
+ * Test that invalid sessions fail to be read
+ */
+fn session_4() {
+    let invalid = vec![
+        "",
+        "history number 1",
+        "calculator-session",
+        "calculator-session 3\nhistory number 1",
+        "calculator-session 1\nvariable x number 1",
+        "calculator-session 2\nvariable number 1",
+        "calculator-session 2\nvariable 2x number 1",
+        "calculator-session 2\nvariable x",
+        "calculator-session 2\nfunction f x 2 * x",
+        "calculator-session 2\nfunction f x =",
+        "calculator-session 2\nfunction f x = 2 *",
+        "calculator-session 2\nfunction f $0 = 2",
+        "calculator-session 1\nhistory number",
+        "calculator-session 1\nhistory number 1 2",
+        "calculator-session 1\nhistory number one",
+        "calculator-session 1\nhistory set 1 2",
+        "calculator-session 1\nmemory 255 number 1",
+        "calculator-session 1\nmemory number 1",
+        "calculator-session 1\nhistory matrix 2 2 1 2 3",
+        "calculator-session 1\nhistory matrix 0 0",
+        "calculator-session 1\nhistory matrix 1.5 2 1 2 3"
+    ];
+
+    for session in invalid {
+        assert!(CalculatorState::from_session_str(session).is_err(), "Expected session to be invalid:\n{session}");
+    }
+}
+
+#[test]
+/**
+ * Test that a calculator created from a saved state can use its history and memory
+ */
+fn session_5() {
+    let calculator = Calculator::default();
+
+    calculator.evaluate("$m3: [1, 2, 3]").unwrap();
+    calculator.evaluate("10").unwrap();
+
+    let session = calculator.clone_current_state().to_session_string();
+    let restored = Calculator::from_state(CalculatorState::from_session_str(&session).unwrap());
+
+    assert_eq!(restored.evaluate("$0 + sum($m3)").unwrap(), Value::Number(16_f64));
+    assert_eq!(restored.evaluate("$2").unwrap(), Value::List(vec![1_f64, 2_f64, 3_f64]));
+}
+
+#[test]
+/**
+ * Test that variables and user defined functions are saved and loaded, and can be used by the calculator they are loaded into
+ */
+fn session_6() {
+    let mut calculator = Calculator::default();
+
+    calculator.set_variable("rate", Value::Number(0.25_f64)).unwrap();
+    calculator.set_variable("xs", Value::List(vec![1_f64, 2_f64])).unwrap();
+    calculator.define_function("hyp", &["a", "b"], "sqrt(a^2 + b^2)").unwrap();
+    calculator.define_function("tax", &["x"], "(x + 0xF) * rate").unwrap();
+    calculator.define_function("seven", &[], "7").unwrap();
+
+    let state = calculator.clone_current_state();
+    let session = state.to_session_string();
+
+    assert!(session.contains("\nvariable rate number 0.25\n"), "Testing session:\n{session}");
+    assert!(session.contains("\nfunction hyp a b = sqrt(a ^ 2 + b ^ 2)\n"), "Testing session:\n{session}");
+    assert!(session.contains("\nfunction seven = 7\n"), "Testing session:\n{session}");
+    round_trip_test(state);
+
+    let restored = Calculator::from_state(CalculatorState::from_session_str(&session).unwrap());
+
+    assert_eq!(restored.evaluate("hyp(3, 4) + tax(1) + seven()").unwrap(), Value::Number(16_f64));
+    assert_eq!(restored.evaluate("sum(xs)").unwrap(), Value::Number(3_f64));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+/**
+ * Test that states, options and errors read back from JSON are unchanged
+ */
+fn serde_0() {
+    use crate::calculator::{EvaluateOptions, CalculatorErr, calculator_interpreter::interpreter::EvaluateOptions as InterpreterOptions};
+
+    let mut calculator = Calculator::default();
+    calculator.seed(1);
+    calculator.evaluate("$m1: [[1, 2], [3, 4]]").unwrap();
+    calculator.set_variable("a", Value::Number(2_f64)).unwrap();
+    calculator.define_function("f", &["x"], "x * a").unwrap();
+    let (_, state) = calculator.evaluate_with_options("2d6 + 1..3", EvaluateOptions::default()).unwrap();
+
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(serde_json::from_str::<CalculatorState>(&json).unwrap(), state);
+
+    let options = EvaluateOptions::new(InterpreterOptions::new(true));
+    assert_eq!(serde_json::from_str::<EvaluateOptions>(&serde_json::to_string(&options).unwrap()).unwrap(), options);
+
+    let err = CalculatorErr::parse_err("Unexpected token.", true, 3);
+    assert_eq!(serde_json::to_string(&err).unwrap(), r#"{"type":"ParseErr","value":{"message":"Unexpected token.","propagate":true,"lah":3}}"#);
+    assert_eq!(serde_json::from_str::<CalculatorErr>(&serde_json::to_string(&err).unwrap()).unwrap(), err);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+/**
+ * Test that matrices with inconsistent dimensions are rejected when deserializing
+ */
+fn serde_1() {
+    let json = r#"{"type":"Matrix","value":{"rows":2,"cols":2,"data":[1.0,2.0,3.0]}}"#;
+    assert!(serde_json::from_str::<Value>(json).is_err());
+
+    let json = r#"{"type":"Matrix","value":{"rows":1,"cols":3,"data":[1.0,2.0,3.0]}}"#;
+    assert_eq!(serde_json::from_str::<Value>(json).unwrap(), Value::Matrix(Matrix::new(1, 3, vec![1_f64, 2_f64, 3_f64]).unwrap()));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+/**
+ * Test that NaN and infinities in numbers, lists and matrices read back from JSON unchanged
+ */
+fn serde_2() {
+    let json = serde_json::to_string(&Value::Number(f64::INFINITY)).unwrap();
+    assert_eq!(json, r#"{"type":"Number","value":"inf"}"#);
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), Value::Number(f64::INFINITY));
+
+    let json = serde_json::to_string(&Value::List(vec![1_f64, f64::NEG_INFINITY, f64::INFINITY])).unwrap();
+    assert_eq!(json, r#"{"type":"List","value":[1.0,"-inf","inf"]}"#);
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), Value::List(vec![1_f64, f64::NEG_INFINITY, f64::INFINITY]));
+
+    let json = serde_json::to_string(&Value::Number(f64::NAN)).unwrap();
+    assert!(matches!(serde_json::from_str::<Value>(&json).unwrap(), Value::Number(value) if value.is_nan()));
+
+    let matrix = Value::Matrix(Matrix::new(1, 3, vec![f64::NAN, 2_f64, f64::INFINITY]).unwrap());
+    let json = serde_json::to_string(&matrix).unwrap();
+    let data = serde_json::from_str::<Value>(&json).unwrap().into_matrix().unwrap().data().to_vec();
+    assert!(data[0].is_nan());
+    assert_eq!(data[1..], [2_f64, f64::INFINITY]);
+
+    assert!(serde_json::from_str::<Value>(r#"{"type":"Number","value":"two"}"#).is_err());
+}
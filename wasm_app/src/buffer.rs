@@ -0,0 +1,291 @@
+#[cfg(test)]
+pub mod tests;
+
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/**
+ * The most edits which can be undone
+ */
+const UNDO_LIMIT: usize = 100;
+
+/**
+ * Where a cursor movement goes
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CursorMove {
+    Left,
+    Right,
+    /**
+     * To the start of the token before the cursor
+     */
+    TokenLeft,
+    /**
+     * To the end of the token after the cursor
+     */
+    TokenRight,
+    Start,
+    End
+}
+
+/**
+ * The expression being entered, edited by grapheme, the same as it is tokenized by the parser.
+ * The cursor and selection are positions between graphemes, and each edit can be undone.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Buffer {
+    text: String,
+    cursor: usize,
+    /**
+     * The other end of the selection from the cursor, if anything is selected
+     */
+    anchor: Option<usize>,
+    undo: Vec<(String, usize)>,
+    redo: Vec<(String, usize)>
+}
+
+impl Buffer {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn graphemes(&self) -> Vec<&str> {
+        self.text.graphemes(true).collect()
+    }
+
+    /**
+     * The number of graphemes
+     */
+    pub fn len(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /**
+     * The graphemes selected, if any
+     */
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.anchor
+            .filter(|anchor| *anchor != self.cursor)
+            .map(|anchor| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    /**
+     * The text before the cursor, or before the selection if there is one
+     */
+    pub fn before_cursor(&self) -> &str {
+        let start = self.selection().map_or(self.cursor, |selection| selection.start);
+        &self.text[..self.byte_offset(start)]
+    }
+
+    /**
+     * Insert the content at the cursor, replacing the selection. If trimming, whitespace before the cursor is removed first.
+     */
+    pub fn insert(&mut self, content: &str, trim: bool) {
+        let mut range = self.selection().unwrap_or(self.cursor..self.cursor);
+
+        if trim {
+            range.start -= self.whitespace_before(range.start);
+        }
+
+        if range.is_empty() && content.is_empty() {
+            return;
+        }
+
+        self.checkpoint();
+        self.replace(range, content);
+    }
+
+    /**
+     * Delete the selection, or else the grapheme before or after the cursor. Deleting backwards also deletes any whitespace before it.
+     */
+    pub fn delete(&mut self, forward: bool) {
+        let range = match self.selection() {
+            Some(selection) => selection,
+            None if forward => self.cursor..(self.cursor + 1).min(self.len()),
+            None => {
+                let start = self.cursor - self.whitespace_before(self.cursor);
+                start.saturating_sub(1)..self.cursor
+            }
+        };
+
+        if !range.is_empty() {
+            self.checkpoint();
+            self.replace(range, "");
+        }
+    }
+
+    /**
+     * Surround the selection, or else the whole buffer, with the text before and after it, leaving the cursor after it
+     */
+    pub fn surround(&mut self, before: &str, after: &str) {
+        let range = self.selection().unwrap_or(0..self.len());
+        let (start, end) = (self.byte_offset(range.start), self.byte_offset(range.end));
+        let content = format!("{before}{}{after}", &self.text[start..end]);
+
+        self.checkpoint();
+        self.replace(range, &content);
+    }
+
+    /**
+     * Replace the whole buffer, leaving the cursor at the end
+     */
+    pub fn set(&mut self, text: &str) {
+        if self.text != text {
+            self.checkpoint();
+            self.replace(0..self.len(), text);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.set("");
+    }
+
+    /**
+     * Move the cursor, extending the selection if selecting, or else collapsing it
+     */
+    pub fn move_cursor(&mut self, movement: CursorMove, select: bool) {
+        let selection = self.selection();
+
+        let position = match (movement, selection, select) {
+            //Moving without selecting goes to the edge of the selection
+            (CursorMove::Left, Some(selection), false) => selection.start,
+            (CursorMove::Right, Some(selection), false) => selection.end,
+            (CursorMove::Left, ..) => self.cursor.saturating_sub(1),
+            (CursorMove::Right, ..) => (self.cursor + 1).min(self.len()),
+            (CursorMove::TokenLeft, ..) => self.token_boundary(false),
+            (CursorMove::TokenRight, ..) => self.token_boundary(true),
+            (CursorMove::Start, ..) => 0,
+            (CursorMove::End, ..) => self.len()
+        };
+
+        self.anchor = if select { Some(self.anchor.unwrap_or(self.cursor)) } else { None };
+        self.cursor = position;
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.len();
+    }
+
+    /**
+     * Undo the last edit, returning whether there was one
+     */
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(state) => {
+                let current = self.restore(state);
+                self.redo.push(current);
+                true
+            },
+            None => false
+        }
+    }
+
+    /**
+     * Redo the last edit which was undone, returning whether there was one
+     */
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(state) => {
+                let current = self.restore(state);
+                self.undo.push(current);
+                true
+            },
+            None => false
+        }
+    }
+
+    /**
+     * Save the current state so that the next edit can be undone
+     */
+    fn checkpoint(&mut self) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+
+        self.undo.push((self.text.clone(), self.cursor));
+        self.redo.clear();
+    }
+
+    fn restore(&mut self, (text, cursor): (String, usize)) -> (String, usize) {
+        let current = (std::mem::replace(&mut self.text, text), self.cursor);
+        self.cursor = cursor.min(self.len());
+        self.anchor = None;
+        current
+    }
+
+    /**
+     * Replace the graphemes in the range, leaving the cursor after the content
+     */
+    fn replace(&mut self, range: Range<usize>, content: &str) {
+        let (start, end) = (self.byte_offset(range.start), self.byte_offset(range.end));
+
+        self.text.replace_range(start..end, content);
+        self.cursor = range.start + content.graphemes(true).count();
+        self.anchor = None;
+    }
+
+    /**
+     * The byte offset of the grapheme at the position, or the length of the text after the last one
+     */
+    fn byte_offset(&self, position: usize) -> usize {
+        self.text.grapheme_indices(true)
+            .nth(position)
+            .map_or(self.text.len(), |(offset, _)| offset)
+    }
+
+    /**
+     * The number of whitespace graphemes directly before the position
+     */
+    fn whitespace_before(&self, position: usize) -> usize {
+        self.graphemes()[..position].iter()
+            .rev()
+            .take_while(|g| is_whitespace(g))
+            .count()
+    }
+
+    /**
+     * The position the cursor moves to by a token; skipping whitespace, then either a word or a single symbol
+     */
+    fn token_boundary(&self, forward: bool) -> usize {
+        let graphemes = self.graphemes();
+        let mut position = self.cursor;
+
+        let next = |position: usize| if forward { graphemes.get(position) } else { position.checked_sub(1).and_then(|p| graphemes.get(p)) };
+        let step = |position: usize| if forward { position + 1 } else { position - 1 };
+
+        while next(position).is_some_and(|g| is_whitespace(g)) {
+            position = step(position);
+        }
+
+        if next(position).is_some_and(|g| is_word(g)) {
+            while next(position).is_some_and(|g| is_word(g)) {
+                position = step(position);
+            }
+        }
+        else if next(position).is_some() {
+            position = step(position);
+        }
+
+        position
+    }
+}
+
+pub fn is_whitespace(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/**
+ * Check if a grapheme is part of a word; a name, number or reference such as '$m0'
+ */
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '$'))
+}
@@ -0,0 +1,179 @@
+use super::{Buffer, CursorMove};
+
+/**
+ * A buffer holding the text, with the cursor at the end
+ */
+fn buffer(text: &str) -> Buffer {
+    let mut buffer = Buffer::default();
+    buffer.set(text);
+    buffer
+}
+
+#[test]
+/**
+ * Test that graphemes of several bytes or characters are edited and counted as one
+ */
+fn grapheme_0() {
+    //'é' written as 'e' and a combining accent, and a thumbs up with a skin tone
+    let mut buffer = buffer("π + e\u{301} + 👍🏽");
+
+    assert_eq!(buffer.len(), 9);
+    assert_eq!(buffer.cursor(), 9);
+
+    buffer.move_cursor(CursorMove::Left, false);
+    buffer.delete(false);
+    assert_eq!(buffer.as_str(), "π + e\u{301} 👍🏽");
+
+    buffer.move_cursor(CursorMove::Left, false);
+    buffer.delete(false);
+    assert_eq!(buffer.as_str(), "π +  👍🏽");
+    assert_eq!(buffer.cursor(), 4);
+
+    buffer.move_cursor(CursorMove::Start, false);
+    buffer.delete(true);
+    buffer.insert("√", false);
+    assert_eq!(buffer.as_str(), "√ +  👍🏽");
+    assert_eq!(buffer.before_cursor(), "√");
+}
+
+#[test]
+/**
+ * Test that inserting replaces the selection, whichever end the cursor is at, and that trimming removes the whitespace before it
+ */
+fn selection_0() {
+    let mut buffer = buffer("1 + 23 * 4");
+
+    buffer.move_cursor(CursorMove::Start, false);
+    for _ in 0..4 {
+        buffer.move_cursor(CursorMove::Right, false);
+    }
+    buffer.move_cursor(CursorMove::Right, true);
+    buffer.move_cursor(CursorMove::Right, true);
+    assert_eq!(buffer.selection(), Some(4..6));
+    assert_eq!(buffer.before_cursor(), "1 + ");
+
+    buffer.insert("5", false);
+    assert_eq!(buffer.as_str(), "1 + 5 * 4");
+    assert_eq!(buffer.selection(), None);
+    assert_eq!(buffer.cursor(), 5);
+
+    buffer.move_cursor(CursorMove::Start, true);
+    assert_eq!(buffer.selection(), Some(0..5));
+    buffer.insert("6", false);
+    assert_eq!(buffer.as_str(), "6 * 4");
+
+    buffer.move_cursor(CursorMove::Right, false);
+    buffer.move_cursor(CursorMove::Right, false);
+    buffer.move_cursor(CursorMove::End, true);
+    buffer.insert("!", true);
+    assert_eq!(buffer.as_str(), "6 *!");
+}
+
+#[test]
+/**
+ * Test that moving without selecting goes to the edge of the selection, and that selecting all selects the whole buffer
+ */
+fn selection_1() {
+    let mut buffer = buffer("123");
+
+    buffer.select_all();
+    assert_eq!(buffer.selection(), Some(0..3));
+    buffer.move_cursor(CursorMove::Left, false);
+    assert_eq!((buffer.cursor(), buffer.selection()), (0, None));
+
+    buffer.move_cursor(CursorMove::End, true);
+    buffer.move_cursor(CursorMove::Right, false);
+    assert_eq!((buffer.cursor(), buffer.selection()), (3, None));
+
+    buffer.select_all();
+    buffer.delete(false);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+/**
+ * Test that backspace deletes a multi-character token one grapheme at a time, along with the whitespace before each grapheme
+ */
+fn delete_0() {
+    let mut buffer = buffer("2 sqrt");
+
+    buffer.delete(false);
+    assert_eq!(buffer.as_str(), "2 sqr");
+
+    for _ in 0..3 {
+        buffer.delete(false);
+    }
+    assert_eq!(buffer.as_str(), "2 ");
+
+    //The whitespace goes with the grapheme before it
+    buffer.delete(false);
+    assert!(buffer.is_empty());
+
+    //Nothing to delete, so nothing to undo
+    buffer.delete(false);
+    buffer.delete(true);
+    buffer.undo();
+    assert_eq!(buffer.as_str(), "2 ");
+}
+
+#[test]
+/**
+ * Test that the cursor moves by token over whitespace, then a whole word or a single symbol
+ */
+fn token_move_0() {
+    let mut buffer = buffer("sqrt(x1) + $m0");
+
+    let mut positions = vec![];
+    while buffer.cursor() > 0 {
+        buffer.move_cursor(CursorMove::TokenLeft, false);
+        positions.push(buffer.cursor());
+    }
+    assert_eq!(positions, vec![11, 9, 7, 5, 4, 0]);
+
+    positions.clear();
+    while buffer.cursor() < buffer.len() {
+        buffer.move_cursor(CursorMove::TokenRight, false);
+        positions.push(buffer.cursor());
+    }
+    assert_eq!(positions, vec![4, 5, 7, 8, 10, 14]);
+
+    buffer.move_cursor(CursorMove::TokenLeft, true);
+    buffer.move_cursor(CursorMove::TokenLeft, true);
+    assert_eq!(buffer.selection(), Some(9..14));
+}
+
+#[test]
+/**
+ * Test that edits are undone and redone in order, with the cursor where it was, and that a new edit clears what can be redone
+ */
+fn undo_0() {
+    let mut buffer = Buffer::default();
+    assert!(!buffer.undo());
+
+    buffer.insert("1", false);
+    buffer.insert(" + ", false);
+    buffer.insert("2", false);
+    buffer.move_cursor(CursorMove::Start, false);
+
+    assert!(buffer.undo());
+    assert_eq!((buffer.as_str(), buffer.cursor()), ("1 + ", 4));
+    assert!(buffer.undo());
+    assert_eq!((buffer.as_str(), buffer.cursor()), ("1", 1));
+
+    assert!(buffer.redo());
+    assert_eq!((buffer.as_str(), buffer.cursor()), ("1 + ", 4));
+    assert!(buffer.redo());
+    assert_eq!(buffer.as_str(), "1 + 2");
+    assert!(!buffer.redo());
+
+    buffer.undo();
+    buffer.undo();
+    buffer.insert("0", false);
+    assert_eq!(buffer.as_str(), "10");
+    assert!(!buffer.redo());
+
+    //Setting the same text isn't an edit
+    buffer.set("10");
+    assert!(buffer.undo());
+    assert_eq!(buffer.as_str(), "1");
+}
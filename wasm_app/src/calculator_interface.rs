@@ -1,8 +1,41 @@
-use std::ops::RangeBounds;
+#[cfg(test)]
+pub mod tests;
+
+use std::ops::Range;
 use yew::prelude::*;
 use bitflags::bitflags;
+use wasm_bindgen::JsCast;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::buffer::{self, Buffer, CursorMove};
+use super::keypad::{self, KeyAction, Modifiers};
+use super::plot::PlotView;
+use super::calculator::{*, calculator_parser, calculator_interpreter::interpreter::{EvaluateOptions as InterpreterOptions, random_source::Roll, value::Value}, calculator_err::CalculatorErr};
+
+/**
+ * The key the session is automatically saved under in the browser's local storage
+ */
+const SESSION_STORAGE_KEY: &str = "calculator-session";
+
+/**
+ * The name of the file an exported session is downloaded as
+ */
+const SESSION_FILE_NAME: &str = "calculator-session.txt";
+
+/**
+ * The key the open side panels are saved under in the browser's local storage
+ */
+const PANELS_STORAGE_KEY: &str = "calculator-panels";
+
+/**
+ * The key the keypad layout is saved under in the browser's local storage
+ */
+const LAYOUT_STORAGE_KEY: &str = "calculator-layout";
 
-use super::calculator::{*, calculator_parser, calculator_interpreter::interpreter::EvaluateOptions as InterpreterOptions, calculator_err::CalculatorErr};
+/**
+ * The symbols which are inserted as typed on the keyboard
+ */
+const KEY_SYMBOLS: &str = "0123456789.+-*/^%!()[],$:";
 
 bitflags! {
     pub struct ClearType: u32 {
@@ -12,6 +45,17 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /**
+     * The side panels which are open
+     */
+    #[derive(Default)]
+    pub struct Panels: u32 {
+        const HISTORY = 0b00000001;
+        const MEMORY = 0b00000010;
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub enum CalculatorAction {
@@ -19,9 +63,59 @@ pub enum CalculatorAction {
     Insert(String, bool),
     Surround { prefix: Option<String>, open: String, close: Option<String>, suffix: Option<String>, preview: bool },
     Backspace(bool),
+    /**
+     * Delete the selection, or else the character after the cursor
+     */
+    Delete(bool),
     Clear(ClearType),
-    Cursor(bool),
-    Submit
+    /**
+     * Move the cursor, extending the selection if true
+     */
+    Cursor(CursorMove, bool),
+    SelectAll,
+    Undo,
+    Redo,
+    Submit,
+    LoadSession(String),
+    /**
+     * Open the function picker, or close it if it's open
+     */
+    ToggleFunctions,
+    SearchFunctions(String),
+    /**
+     * Close the function picker, and insert a call to the function
+     */
+    PickFunction(String),
+    /**
+     * A key pressed on the keyboard, which is translated into the other actions
+     */
+    Key { key: String, ctrl: bool, shift: bool },
+    TogglePanels(Panels),
+    /**
+     * Remove the result at the position in history, 0 being the most recent
+     */
+    RemoveHistory(usize),
+    /**
+     * Store the result at the position in history in the first memory slot which hasn't been assigned to
+     */
+    StoreHistory(usize),
+    ClearMemorySlot(usize),
+    /**
+     * A key of the keypad pressed, which is translated into the other actions
+     */
+    Keypad(KeyAction),
+    /**
+     * Switch to the keypad layout at the position in the list of layouts
+     */
+    SetLayout(usize),
+    /**
+     * Copy the expression being entered to the clipboard as LaTeX, followed by its result if it has one
+     */
+    CopyLatex,
+    /**
+     * Open the plot in place of the keypad, plotting the entry, or close it if it's open
+     */
+    TogglePlot
 }
 
 #[allow(dead_code)]
@@ -29,10 +123,10 @@ pub enum CalculatorAction {
 pub enum CalculatorResult {
     #[default] None,
     RefreshDisplay,
-    Number(f64),
+    Number(Value),
     State(CalculatorState),
-    NumberAndState(f64, CalculatorState),
-    PreviewNumberAndState(Result<(f64, CalculatorState), CalculatorErr>)
+    NumberAndState(Value, CalculatorState),
+    PreviewNumberAndState(Result<(Value, CalculatorState), CalculatorErr>)
 }
 
 #[derive(Properties, Default, Debug, PartialEq, Clone)]
@@ -52,6 +146,13 @@ impl Component for CalculatorButton {
         }
     }
 
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        //Buttons are reused when the keypad layout or modifiers change
+        self.callback_click = ctx.props().callback_click.clone();
+        self.display = ctx.props().display.clone();
+        true
+    }
+
     fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
         log::info!("InnerUpdate");
 
@@ -79,110 +180,73 @@ impl Component for CalculatorButton {
 #[derive(Properties, Default, Debug, PartialEq, Clone)]
 pub struct CalculatorBase {
     calculator: Calculator,
-    buffer: String,
-    cursor: usize,
-    result: Option<f64>,
-    preview: Option<Result<(f64, CalculatorState), CalculatorErr>>
+    buffer: Buffer,
+    result: Option<Value>,
+    rolls: Vec<Roll>,
+    preview: Option<Result<(Value, CalculatorState), CalculatorErr>>,
+    /**
+     * The search of the function picker, if it is open
+     */
+    function_search: Option<String>,
+    /**
+     * Whether the plot is shown in place of the keypad
+     */
+    plotting: bool,
+    /**
+     * The letters of a name being typed on the keyboard, which is inserted once the name is complete
+     */
+    typed_name: String,
+    panels: Panels,
+    /**
+     * The position of the keypad layout in the list of layouts
+     */
+    layout: usize,
+    modifiers: Modifiers,
+    /**
+     * The values entered in the statistics layout
+     */
+    data: Vec<f64>,
+    calculator_ref: NodeRef
 }
 
 impl CalculatorBase {
-    fn buffer_append(&mut self, content: &str) {
-        self.buffer.push_str(content);
-    }
+    /**
+     * Save the calculator's history, memory, variables and functions to local storage, so that they are restored when the page is next opened
+     */
+    fn autosave(&self) {
+        let session = self.calculator.clone_current_state().to_session_string();
 
-    fn buffer_insert_at_cursor(&mut self, content: &str) {
-        if self.cursor == self.buffer.len() {
-            if !content.is_empty() {
-                self.buffer_append(content);
-                self.cursor += content.len();
-            }
-        }
-        else if content.is_empty() {
-            self.buffer.remove(self.cursor);
-            if self.cursor > self.buffer.len() {
-                self.cursor = self.buffer.len();
-            }
-        }
-        else {
-            let before: &str = if self.cursor == 0_usize {
-                ""
-            }
-            else {
-                &self.buffer[0..self.cursor]
-            };
-
-            let after: &str = if self.cursor + content.len() >= self.buffer.len() {
-                ""
-            }
-            else {
-                &self.buffer[self.cursor + content.len() + 1..self.buffer.len()]
-            };
-
-            self.buffer = format!("{before}{content}{after}");
-            self.cursor = self.cursor + content.len() + 1;
-        }
-    }
-
-    fn buffer_clear(&mut self) {
-        self.buffer.clear();
-        self.cursor = 0;
-    }
-
-    fn buffer_remove_range<R: RangeBounds<usize>>(&mut self, range: R) {
-        //Extract range start and end
-        let mut start_bound = match range.start_bound() {
-            std::ops::Bound::Unbounded => None,
-            std::ops::Bound::Excluded(n) => Some(*n + 1),
-            std::ops::Bound::Included(n) => Some(*n)
-        };
-
-        let mut end_bound = match range.start_bound() {
-            std::ops::Bound::Unbounded => None,
-            std::ops::Bound::Excluded(n) => Some(*n - 1),
-            std::ops::Bound::Included(n) => Some(*n)
-        };
-
-        //Reverse range direction if necessary
-        if start_bound.is_some() && end_bound.is_some() && start_bound.unwrap() > end_bound.unwrap() {
-            std::mem::swap(&mut start_bound, &mut end_bound)
-        }
-
-        //Get the inverse of the range
-        let start_range = start_bound.map(|n| 0_usize..=n);
-        let end_range = end_bound.map(|n| n..self.buffer.len());
-
-        //Get the substrings corresponding to the start and end range
-        let start = start_range.map_or("",|r| &self.buffer[r]);
-        let end = end_range.map_or("", |r| &self.buffer[r]);
-
-        //Concatenate substrings
-        self.buffer = format!("{start}{end}");
-    }
-
-    fn buffer_remove_end(&mut self, count: usize, trim_end: bool) {
-        if trim_end {
-            let trimmed = self.buffer.trim_end();
-            self.buffer = String::from(trimmed);
-        }
-
-        if count == 0 {}
-        else if count == 1 {
-            self.buffer.pop();
-        }
-        else {
-            self.buffer_remove_range(self.buffer.len() - count..)
+        match local_storage() {
+            Some(storage) => {
+                if let Err(err) = storage.set_item(SESSION_STORAGE_KEY, &session) {
+                    log::error!("Failed to save session: {err:?}");
+                }
+            },
+            None => log::warn!("Local storage is unavailable; session not saved.")
         }
     }
 
     fn evaluate_buffer(&self) -> Result<CalculatorResult, calculator_err::CalculatorErr> {
         self.calculator
-            .evaluate_with_options(&self.buffer, EvaluateOptions::default())
+            .evaluate_with_options(self.buffer.as_str(), EvaluateOptions::default())
             .map(|(n, state)| CalculatorResult::NumberAndState(n, state))
     }
 
     fn evaluate_buffer_preview(&self) -> CalculatorResult {
         CalculatorResult::PreviewNumberAndState(self.calculator
-            .evaluate_with_options(&self.buffer, EvaluateOptions::new(InterpreterOptions::new(true))))
+            .evaluate_with_options(self.buffer.as_str(), EvaluateOptions::new(InterpreterOptions::new(true))))
+    }
+
+    /**
+     * Preview the buffer once the cursor has moved, which only needs showing if something has been entered
+     */
+    fn evaluate_buffer_preview_if_entered(&self) -> CalculatorResult {
+        if self.buffer.is_empty() {
+            CalculatorResult::None
+        }
+        else {
+            self.evaluate_buffer_preview()
+        }
     }
 }
 
@@ -191,17 +255,35 @@ impl Component for CalculatorBase {
     type Properties = CalculatorBase;
 
     fn create(ctx: &Context<Self>) -> Self {
+        let mut calculator = ctx.props().calculator.clone();
+
+        //Continue from where the previous session left off
+        if let Some(session) = local_storage().and_then(|storage| storage.get_item(SESSION_STORAGE_KEY).ok().flatten()) {
+            match CalculatorState::from_session_str(&session) {
+                Ok(state) => calculator.restore_state(state),
+                Err(err) => log::error!("Failed to restore session: {err}")
+            }
+        }
+
         Self {
-            calculator: ctx.props().calculator.clone(),
+            calculator,
             buffer: ctx.props().buffer.clone(),
-            cursor: ctx.props().cursor,
-            result: ctx.props().result,
-            preview: ctx.props().preview.clone()
+            result: ctx.props().result.clone(),
+            rolls: ctx.props().rolls.clone(),
+            preview: ctx.props().preview.clone(),
+            function_search: ctx.props().function_search.clone(),
+            plotting: ctx.props().plotting,
+            typed_name: ctx.props().typed_name.clone(),
+            panels: load_panels(),
+            layout: load_layout(),
+            modifiers: ctx.props().modifiers,
+            data: ctx.props().data.clone(),
+            calculator_ref: NodeRef::default()
         }
     }
     
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        log::info!("Update; Buffer: {}; Cursor: {}", &self.buffer, self.cursor);
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        log::info!("Update; Buffer: {}; Cursor: {}", self.buffer.as_str(), self.buffer.cursor());
         log::debug!("{msg:?}");
 
         let result = match msg {
@@ -209,57 +291,11 @@ impl Component for CalculatorBase {
             CalculatorAction::Insert(symbol, preview) => {
                 log::info!("Insert '{symbol}'; preview: {}", preview);
 
-                let mut new_content = symbol;
-                
-                let parser = self.calculator.parser();
-                //let interpreter = self.calculator.interpreter();
-
-                /*
-                 * If buffer is empty, and history is not, and content is an infix or suffix operator,
-                 * prepend with $0.
-                 */
-                if self.buffer.is_empty() && self.calculator.has_history()
-                    && parser.parse_expression::<calculator_parser::expression::UnopPrefix>(new_content.as_str()).is_err() {
-                    if parser.parse_expression::<calculator_parser::expression::BinopInfix>(new_content.as_str()).is_ok() {
-                        new_content = format!("$0 {new_content} ");
-                    }
-                    else if parser.parse_expression::<calculator_parser::expression::UnopSuffix>(new_content.as_str()).is_ok() {
-                        new_content = format!("$0{new_content}");
-                    }
-                    // else if let Ok(id) = parser.parse_expression::<calculator_parser::expression::IdToken>(new_content.as_str()) {
-                    //     let func = interpreter.get_func_by_name(id.value.as_str());
-
-                    //     if func.is_some() {
-                    //         new_content = format!("$0 {new_content} ");
-                    //     }
-                    // }
-                }
-                /*
-                 * If buffer isn't empty, and appending to end of buffer, and content is an infix operator,
-                 * surround operator with spaces
-                 */
-                else if !self.buffer.is_empty() && self.cursor == self.buffer.len() 
-                    && (parser.parse_expression::<calculator_parser::expression::BinopInfix>(new_content.as_str()).is_ok() 
-                        || parser.parse_expression::<calculator_parser::expression::IdToken>(new_content.as_str()).is_ok()) {
-                    new_content = format!("{}{new_content} ", if self.buffer.ends_with(' ') { "" } else { " " });
-                }
-                /*
-                 * If buffer isn't empty, and appending to end of buffer, and content is a suffix operator,
-                 * put a space after it, and remove any spaces before it
-                 */
-                else if !self.buffer.is_empty() && self.cursor == self.buffer.len() 
-                    && parser.parse_expression::<calculator_parser::expression::UnopSuffix>(new_content.as_str()).is_ok() {
-                        if self.buffer.ends_with(' ') {
-                            let trimmed = self.buffer.trim_end();
-                            self.buffer = String::from(trimmed);
-                            self.cursor = self.cursor.min(self.buffer.len());
-                        }
-                        new_content = format!("{new_content} ");
-                    }
+                let (new_content, trim) = spaced_insertion(&self.calculator, &self.buffer, symbol);
 
-                self.buffer_insert_at_cursor(&new_content);
+                self.buffer.insert(&new_content, trim);
 
-                log::info!("Buffer: {}; Cursor: {}", &self.buffer, self.cursor);
+                log::info!("Buffer: {}; Cursor: {}", self.buffer.as_str(), self.buffer.cursor());
                 
                 if preview {
                     self.evaluate_buffer_preview()
@@ -276,12 +312,10 @@ impl Component for CalculatorBase {
                 log::info!("Surround; {prefix}, {open}, {close}, {suffix}; preview: {}", preview);
 
                 if self.buffer.is_empty() && self.calculator.has_history() {
-                    self.buffer = format!("{prefix}{open}$0{close}{suffix}");
-                    self.cursor = self.buffer.len();
+                    self.buffer.set(&format!("{prefix}{open}$0{close}{suffix}"));
                 }
                 else {
-                    self.buffer = format!("{prefix}{open}{}{close}{suffix}", self.buffer);
-                    self.cursor = (self.cursor + prefix.len() + open.len() + close.len() + suffix.len()).min(self.buffer.len());
+                    self.buffer.surround(&format!("{prefix}{open}"), &format!("{close}{suffix}"));
                 }
 
                 if preview {
@@ -291,20 +325,18 @@ impl Component for CalculatorBase {
                     CalculatorResult::RefreshDisplay
                 }
             },
-            CalculatorAction::Cursor(direction) => {
-                if direction {
-                    self.cursor = (self.cursor + 1).min(self.buffer.len());
-                }
-                else {
-                    self.cursor = (self.cursor.max(1) - 1).min(self.buffer.len());
-                }
-
-                CalculatorResult::None
+            CalculatorAction::Cursor(movement, select) => {
+                self.buffer.move_cursor(movement, select);
+                self.evaluate_buffer_preview_if_entered()
+            },
+            CalculatorAction::SelectAll => {
+                self.buffer.select_all();
+                self.evaluate_buffer_preview_if_entered()
             },
-            CalculatorAction::Backspace(preview) => {
-                log::info!("Backspace; preview: {}", preview);
-                self.buffer_remove_end(1, true);
-                self.cursor = (self.cursor.max(1) - 1).min(self.buffer.len());
+            CalculatorAction::Backspace(preview) | CalculatorAction::Delete(preview) => {
+                let forward = matches!(msg, CalculatorAction::Delete(_));
+                log::info!("{}; preview: {}", if forward { "Delete" } else { "Backspace" }, preview);
+                self.buffer.delete(forward);
                 
                 if preview {
                     self.evaluate_buffer_preview()
@@ -313,12 +345,22 @@ impl Component for CalculatorBase {
                     CalculatorResult::RefreshDisplay
                 }
             },
+            CalculatorAction::Undo | CalculatorAction::Redo => {
+                let changed = if msg == CalculatorAction::Undo { self.buffer.undo() } else { self.buffer.redo() };
+
+                if changed {
+                    self.evaluate_buffer_preview()
+                }
+                else {
+                    CalculatorResult::None
+                }
+            },
             CalculatorAction::Clear(clear_type) => {
                 log::info!("Clear; {}", clear_type.bits);
 
                 if clear_type.contains(ClearType::ENTRY) {
                     log::info!("Clearing buffer.");
-                    self.buffer_clear();
+                    self.buffer.clear();
                 }
 
                 if clear_type.contains(ClearType::HISTORY) {
@@ -331,6 +373,10 @@ impl Component for CalculatorBase {
                     self.calculator.clear_mem();
                 }
 
+                if clear_type.intersects(ClearType::HISTORY | ClearType::MEMORY) {
+                    self.autosave();
+                }
+
                 CalculatorResult::RefreshDisplay
             },
             CalculatorAction::Submit => {
@@ -339,6 +385,7 @@ impl Component for CalculatorBase {
 
                 match result {
                     Ok(evaluated) => {
+                        self.autosave();
                         evaluated
                     },
                     Err(err) => {
@@ -346,6 +393,136 @@ impl Component for CalculatorBase {
                         CalculatorResult::RefreshDisplay
                     }
                 }
+            },
+            CalculatorAction::LoadSession(session) => {
+                log::info!("Load Session");
+
+                match CalculatorState::from_session_str(&session) {
+                    Ok(state) => {
+                        self.calculator.restore_state(state);
+                        self.autosave();
+                        CalculatorResult::RefreshDisplay
+                    },
+                    Err(err) => {
+                        log::error!("{err}");
+                        CalculatorResult::PreviewNumberAndState(Err(err))
+                    }
+                }
+            },
+            CalculatorAction::ToggleFunctions => {
+                self.function_search = match self.function_search {
+                    Some(_) => None,
+                    None => Some(String::new())
+                };
+
+                self.plotting = false;
+
+                CalculatorResult::RefreshDisplay
+            },
+            CalculatorAction::SearchFunctions(search) => {
+                self.function_search = Some(search);
+                CalculatorResult::RefreshDisplay
+            },
+            CalculatorAction::PickFunction(name) => {
+                log::info!("Pick Function '{name}'");
+                self.function_search = None;
+                self.plotting = false;
+
+                ctx.link().send_message(CalculatorAction::Insert(format!("{}(", name.to_lowercase()), true));
+                CalculatorResult::RefreshDisplay
+            },
+            CalculatorAction::TogglePlot => {
+                self.plotting = !self.plotting;
+                self.function_search = None;
+                CalculatorResult::RefreshDisplay
+            },
+            CalculatorAction::CopyLatex => {
+                if let Some(latex) = self.buffer_latex() {
+                    write_clipboard(latex);
+                }
+
+                CalculatorResult::None
+            },
+            CalculatorAction::TogglePanels(panels) => {
+                self.panels.toggle(panels);
+
+                if let Some(storage) = local_storage() {
+                    if let Err(err) = storage.set_item(PANELS_STORAGE_KEY, &self.panels.bits.to_string()) {
+                        log::error!("Failed to save panels: {err:?}");
+                    }
+                }
+
+                CalculatorResult::RefreshDisplay
+            },
+            CalculatorAction::RemoveHistory(position) => {
+                let mut state = self.calculator.clone_current_state();
+
+                match state.history.len().checked_sub(position + 1) {
+                    Some(index) => {
+                        state.history.remove(index);
+                        CalculatorResult::State(state)
+                    },
+                    None => CalculatorResult::None
+                }
+            },
+            CalculatorAction::StoreHistory(position) => {
+                match store_history(self.calculator.clone_current_state(), position) {
+                    Ok(state) => CalculatorResult::State(state),
+                    Err(err) => {
+                        log::error!("{err}");
+                        CalculatorResult::PreviewNumberAndState(Err(err))
+                    }
+                }
+            },
+            CalculatorAction::ClearMemorySlot(slot) => {
+                let mut state = self.calculator.clone_current_state();
+
+                match state.memory.get_mut(slot) {
+                    Some(value) => {
+                        *value = Value::default();
+                        CalculatorResult::State(state)
+                    },
+                    None => CalculatorResult::None
+                }
+            },
+            CalculatorAction::Keypad(action) => {
+                log::info!("Keypad {action:?}");
+
+                let modifiers = self.modifiers;
+                let data = self.data.clone();
+                ctx.link().send_message_batch(self.keypad_actions(action));
+
+                if self.modifiers != modifiers || self.data != data {
+                    CalculatorResult::RefreshDisplay
+                }
+                else {
+                    CalculatorResult::None
+                }
+            },
+            CalculatorAction::SetLayout(layout) => {
+                self.layout = layout.min(keypad::LAYOUTS.len() - 1);
+                self.modifiers = Modifiers::empty();
+
+                if let Some(storage) = local_storage() {
+                    if let Err(err) = storage.set_item(LAYOUT_STORAGE_KEY, keypad::LAYOUTS[self.layout].name) {
+                        log::error!("Failed to save layout: {err:?}");
+                    }
+                }
+
+                CalculatorResult::RefreshDisplay
+            },
+            CalculatorAction::Key { key, ctrl, shift } => {
+                log::info!("Key '{key}'; ctrl: {ctrl}; shift: {shift}");
+
+                let typed_name = self.typed_name.clone();
+                ctx.link().send_message_batch(self.key_actions(&key, ctrl, shift));
+
+                if self.typed_name != typed_name {
+                    CalculatorResult::RefreshDisplay
+                }
+                else {
+                    CalculatorResult::None
+                }
             }
         };
 
@@ -357,6 +534,7 @@ impl Component for CalculatorBase {
             CalculatorResult::RefreshDisplay => {
                 log::info!("Refresh Display");
                 self.result = None;
+                self.rolls.clear();
                 self.preview = None;
                 true
             },
@@ -364,18 +542,21 @@ impl Component for CalculatorBase {
                 log::info!("Number ({n}).");
                 self.result = Some(n);
                 self.preview = None;
-                self.buffer_clear();
+                self.buffer.clear();
                 true
             },
-            CalculatorResult::State(_state) => {
+            CalculatorResult::State(state) => {
                 log::info!("State");
+                self.calculator.restore_state(state);
+                self.autosave();
                 true
             },
-            CalculatorResult::NumberAndState(n, _state) => {
+            CalculatorResult::NumberAndState(n, state) => {
                 log::info!("Number ({n}) And State");
                 self.result = Some(n);
+                self.rolls = state.rolls;
                 self.preview = None;
-                self.buffer_clear();
+                self.buffer.clear();
                 true
             },
             CalculatorResult::PreviewNumberAndState(preview) => {
@@ -400,30 +581,47 @@ impl Component for CalculatorBase {
         rerender
     }
 
-    fn view(&self, ctx: &Context<Self>) -> Html {
-        let buffer = if self.buffer.is_empty() {
-            self.result.as_ref().map(|n| n.to_string())
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        //Keep the calculator focused, so that it receives keyboard input, unless the function picker's search is being typed in
+        if self.function_search.is_none() {
+            if let Some(calculator) = self.calculator_ref.cast::<web_sys::HtmlElement>() {
+                let active = web_sys::window()
+                    .and_then(|window| window.document())
+                    .and_then(|document| document.active_element());
+
+                if !active.is_some_and(|active| calculator.contains(Some(&active))) {
+                    _ = calculator.focus();
+                }
+            }
         }
-        else {
-            Some(self.buffer.clone())
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        //Matrices are shown as a table rather than as editable text
+        let result_matrix = match &self.result {
+            Some(result) if self.buffer.is_empty() && result.is_matrix() => Some(value_html(result)),
+            _ => None
         };
 
-        let buffer_segments: Option<Vec<yew::virtual_dom::VNode>> = buffer.as_ref().map(|buf| buf.split_whitespace()
-            .map(|segment| {
-                let tooltip = match self.calculator.evaluate_with_options(segment, EvaluateOptions::new(InterpreterOptions::new(true))) {
-                    Err(_) => None,
-                    Ok((n, _)) => Some(n.to_string())
-                };
+        //The expression being entered is also typeset as math, once it can be parsed
+        let typeset = match self.calculator.parser().parse(self.buffer.as_str()) {
+            Ok(expr @ calculator_parser::expression::Expr::ExprPrime(_)) => Some(Html::from_html_unchecked(AttrValue::from(expr.to_mathml()))),
+            _ => None
+        };
 
-                html! {
-                    <span>
-                        <span title={tooltip}>{ segment }</span><span>{ " " }</span>
-                    </span>
-                }
-            })
-            .collect());
+        //Only an entry which can be typeset can be copied as LaTeX
+        let copy_disabled = typeset.is_none();
 
-        let buffer_segment_iter = buffer_segments.map(|bs| bs.into_iter());
+        //The buffer is shown with the caret, or the result once it has been evaluated
+        let buffer_segments = if self.buffer.is_empty() {
+            match self.result.as_ref().filter(|n| !n.is_matrix()) {
+                Some(n) => self.buffer_segments_html(&n.to_string().graphemes(true).collect::<Vec<&str>>(), None, None),
+                None => self.buffer_segments_html(&[], Some(0), None)
+            }
+        }
+        else {
+            self.buffer_segments_html(&self.buffer.graphemes(), Some(self.buffer.cursor()), self.buffer.selection())
+        };
 
         let preview = match &self.preview {
             None => None,
@@ -447,90 +645,659 @@ impl Component for CalculatorBase {
             }
         };
 
+        //Show the individual dice rolls that made up the result
+        let roll_rows = self.rolls.iter().map(|roll| {
+            html! {
+                <div class="roll">{ roll.to_string() }</div>
+            }
+        });
+
         let calculator_state = self.calculator.clone_current_state();
-        let history = calculator_state.history;
+        let history = &calculator_state.history;
 
         let history_rows = history.iter().rev().map(|n| {
             //format!("<div class=\"history\">= {n}</div>")
             html! {
-                <div class="history">{"= "}{n}</div>
+                <div class="history">{"= "}{value_html(n)}</div>
+            }
+        });
+
+        //Sessions are exported and imported as files, in the same format as the console
+        let session_href = format!("data:text/plain;charset=utf-8,{}", String::from(js_sys::encode_uri_component(&calculator_state.to_session_string())));
+
+        let link = ctx.link().clone();
+        let import_session = Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                link.send_future(async move {
+                    match wasm_bindgen_futures::JsFuture::from(file.text()).await {
+                        Ok(text) => CalculatorAction::LoadSession(text.as_string().unwrap_or_default()),
+                        Err(err) => {
+                            log::error!("Failed to read session file: {err:?}");
+                            CalculatorAction::None
+                        }
+                    }
+                });
+            }
+
+            //Allow the same file to be imported again
+            input.set_value("");
+        });
+
+        let function_picker = self.function_search.as_ref().map(|search| self.function_picker_html(ctx, search));
+        let panels = (!self.panels.is_empty()).then(|| self.panels_html(ctx, &calculator_state));
+
+        let data = (!self.data.is_empty()).then(|| html! {
+            <div id="data" title="Statistics data">{ Value::List(self.data.clone()).to_string() }</div>
+        });
+
+        let onkeydown = ctx.link().batch_callback(|e: KeyboardEvent| {
+            //Leave shortcuts other than editing ones, and typing in the function picker's search, to the browser
+            let in_input = e.target_dyn_into::<web_sys::HtmlInputElement>().is_some();
+
+            let ctrl = e.ctrl_key() || e.meta_key();
+
+            if e.alt_key() || (in_input && e.key() != "Escape") || !is_calculator_key(&e.key(), ctrl) {
+                return None;
+            }
+
+            e.prevent_default();
+            Some(CalculatorAction::Key { key: e.key(), ctrl, shift: e.shift_key() })
+        });
+
+        //Pasting inserts the whole expression
+        let onpaste = ctx.link().batch_callback(|e: Event| {
+            if e.target_dyn_into::<web_sys::HtmlInputElement>().is_some() {
+                return None;
+            }
+
+            let text = clipboard_data(&e)
+                .and_then(|data| data.get_data("text/plain").ok())?;
+
+            e.prevent_default();
+            Some(CalculatorAction::Insert(text.split_whitespace().collect::<Vec<&str>>().join(" "), true))
+        });
+
+        //Copying without a selection copies the current result
+        let copied = self.result.as_ref().or(history.last()).map(|n| n.to_string());
+
+        let oncopy = Callback::from(move |e: Event| {
+            let selection = web_sys::window()
+                .and_then(|window| window.get_selection().ok().flatten())
+                .map(|selection| String::from(selection.to_string()))
+                .unwrap_or_default();
+
+            if let (true, Some(text), Some(data)) = (selection.is_empty(), &copied, clipboard_data(&e)) {
+                if data.set_data("text/plain", text).is_ok() {
+                    e.prevent_default();
+                }
             }
         });
 
         html! {
             <>
-                <div id="calculator" class="calculator">
+                <div id="calculator" class="calculator" tabindex="0" ref={self.calculator_ref.clone()} {onkeydown} {onpaste} {oncopy}>
                     <div class="calculator-screen">
                         <div class="calculator-screen-inner">
+                            <div id="typeset">{ for typeset }</div>
                             <div id="buffer">             
-                            if let Some(iter) = buffer_segment_iter {
-                                { for iter }
+                            if let Some(table) = result_matrix {
+                                { table }
                             }
+                            { for buffer_segments }
                             </div>
                             <div id="preview">{ preview }</div>
+                            <div id="rolls">{ for roll_rows }</div>
+                            { for data }
                             { for history_rows }
                         </div>
                     </div>
-                    <table class="calculator-buttons">
-                         <tr>
-                            <td></td>
-                            <td><CalculatorButton display="π" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("pi"), true))} /></td>
-                            <td><CalculatorButton display="e" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("e"), true))} /></td>
-                            <td><CalculatorButton display="C" callback_click={ctx.link().callback(move |_| CalculatorAction::Clear(ClearType::all()))} /></td>
-                            <td><CalculatorButton display="CE" callback_click={ctx.link().callback(move |_| CalculatorAction::Clear(ClearType::ENTRY))} /></td>
-                            <td><CalculatorButton display="BK" callback_click={ctx.link().callback(move |_| CalculatorAction::Backspace(true))} /></td>
-                         </tr>
-                        <tr>
-                            <td><CalculatorButton display="(" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("("), true))} /></td>
-                            <td><CalculatorButton display=")" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from(")"), true))} /></td>
-                            <td><CalculatorButton display="(x)" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: None, open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="$" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("$"), true))} /></td>
-                            <td><CalculatorButton display="$m" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("$m"), true))} /></td>
-                            <td><CalculatorButton display=":" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from(":"), true))} /></td>
-                        </tr>
-                        <tr>
-                            <td><CalculatorButton display="|x|" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("abs")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="-x" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("-")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="^" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("^"), true))} /></td>
-                            <td><CalculatorButton display="%" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("%"), true))} /></td>
-                            <td><CalculatorButton display="!" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("!"), true))} /></td>
-                            <td><CalculatorButton display="÷" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("/"), true))} /></td>
-                        </tr>
-                        <tr>
-                            <td><CalculatorButton display="√x" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("sqrt")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="1/x" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("1 / ")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="7" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("7"), true))} /></td>
-                            <td><CalculatorButton display="8" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("8"), true))} /></td>
-                            <td><CalculatorButton display="9" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("9"), true))} /></td>
-                            <td><CalculatorButton display="×" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("*"), true))} /></td>
-                        </tr>
-                        <tr>
-                            <td><CalculatorButton display="x^2" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: None, open: String::from("("), close: Some(String::from(")")), suffix: Some(String::from(" ^ 2")), preview: true })} /></td>
-                            <td><CalculatorButton display="2^x" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("2 ^ ")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="4" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("4"), true))} /></td>
-                            <td><CalculatorButton display="5" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("5"), true))} /></td>
-                            <td><CalculatorButton display="6" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("6"), true))} /></td>
-                            <td><CalculatorButton display="-" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("-"), true))} /></td>
-                        </tr>
-                        <tr>
-                            <td><CalculatorButton display="e^x" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("e ^ ")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="10^x" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("10 ^ ")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="1" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("1"), true))} /></td>
-                            <td><CalculatorButton display="2" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("2"), true))} /></td>
-                            <td><CalculatorButton display="3" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("3"), true))} /></td>
-                            <td><CalculatorButton display="+" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("+"), true))} /></td>
-                        </tr>
-                        <tr>
-                            <td><CalculatorButton display="log" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("log")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td><CalculatorButton display="ln" callback_click={ctx.link().callback(move |_| CalculatorAction::Surround { prefix: Some(String::from("ln")), open: String::from("("), close: Some(String::from(")")), suffix: None, preview: true })} /></td>
-                            <td></td>
-                            <td><CalculatorButton display="0" callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("0"), true))} /></td>
-                            <td><CalculatorButton display="." callback_click={ctx.link().callback(move |_| CalculatorAction::Insert(String::from("."), true))} /></td>
-                            <td><CalculatorButton display="=" callback_click={ctx.link().callback(move |_| CalculatorAction::Submit)} /></td>
-                        </tr>
-                    </table>
+                    <div class="calculator-body">
+                    if let Some(picker) = function_picker {
+                        { picker }
+                    }
+                    else if self.plotting {
+                        <PlotView calculator={self.calculator.clone()} entry={AttrValue::from(self.buffer.as_str().to_string())} />
+                    }
+                    else {
+                        { self.keypad_html(ctx) }
+                    }
+                    { for panels }
+                    </div>
+                    <div class="calculator-session">
+                        <button class="session-button" onclick={ctx.link().callback(|_: MouseEvent| CalculatorAction::TogglePanels(Panels::HISTORY))}>{ "History" }</button>
+                        <button class="session-button" onclick={ctx.link().callback(|_: MouseEvent| CalculatorAction::TogglePanels(Panels::MEMORY))}>{ "Memory" }</button>
+                        <button class="session-button" onclick={ctx.link().callback(|_: MouseEvent| CalculatorAction::TogglePlot)}>{ if self.plotting { "Keypad" } else { "Plot" } }</button>
+                        <button class="session-button" disabled={copy_disabled} onclick={ctx.link().callback(|_: MouseEvent| CalculatorAction::CopyLatex)}>{ "Copy as LaTeX" }</button>
+                        <a class="session-button" download={SESSION_FILE_NAME} href={session_href}>{ "Export session" }</a>
+                        <label class="session-button">
+                            { "Import session" }
+                            <input type="file" accept=".txt,text/plain" onchange={import_session} />
+                        </label>
+                    </div>
                 </div>   
             </>
         }
     }
-}
\ No newline at end of file
+}
+
+impl CalculatorBase {
+    /**
+     * The expression being entered as LaTeX, followed by ' = ' and its result if it evaluates to something other than a matrix
+     */
+    fn buffer_latex(&self) -> Option<String> {
+        let latex = match self.calculator.parser().parse(self.buffer.as_str()) {
+            Ok(expr @ calculator_parser::expression::Expr::ExprPrime(_)) => expr.to_latex(),
+            _ => return None
+        };
+
+        match &self.preview {
+            Some(Ok((n, _))) if !n.is_matrix() => Some(format!("{latex} = {n}")),
+            _ => Some(latex)
+        }
+    }
+
+    /**
+     * Translate a key pressed on the keyboard into actions. Letters are collected into the name being typed,
+     * which is inserted when a key that can't be part of it is pressed, or as a call if that key is '('.
+     * With control, the arrows move by token, and 'z', 'y' and 'a' undo, redo and select all; with shift, the cursor keys select.
+     */
+    fn key_actions(&mut self, key: &str, ctrl: bool, shift: bool) -> Vec<CalculatorAction> {
+        let is_name_char = !ctrl && matches!(key.chars().collect::<Vec<char>>().as_slice(),
+            [c] if c.is_ascii_alphabetic() || *c == '_' || (c.is_ascii_digit() && !self.typed_name.is_empty()));
+
+        if is_name_char {
+            self.typed_name.push_str(key);
+            return Vec::new();
+        }
+
+        match key {
+            "Backspace" if !self.typed_name.is_empty() => {
+                self.typed_name.pop();
+                return Vec::new();
+            },
+            "Escape" if self.function_search.is_some() => return vec![CalculatorAction::ToggleFunctions],
+            "Escape" if self.plotting => return vec![CalculatorAction::TogglePlot],
+            "Escape" => {
+                self.typed_name.clear();
+                return vec![CalculatorAction::Clear(ClearType::ENTRY)];
+            },
+            //Undoing discards the name being typed, rather than inserting it first
+            "z" | "Z" | "y" if ctrl => {
+                self.typed_name.clear();
+                return vec![if key == "z" && !shift { CalculatorAction::Undo } else { CalculatorAction::Redo }];
+            },
+            "(" if !self.typed_name.is_empty() => return vec![CalculatorAction::Insert(format!("{}(", std::mem::take(&mut self.typed_name)), true)],
+            _ => {}
+        }
+
+        let mut actions = Vec::new();
+
+        if !self.typed_name.is_empty() {
+            actions.push(CalculatorAction::Insert(std::mem::take(&mut self.typed_name), true));
+        }
+
+        let action = match key {
+            "a" if ctrl => CalculatorAction::SelectAll,
+            "Enter" | "=" => CalculatorAction::Submit,
+            "Backspace" => CalculatorAction::Backspace(true),
+            "Delete" => CalculatorAction::Delete(true),
+            "ArrowLeft" => CalculatorAction::Cursor(if ctrl { CursorMove::TokenLeft } else { CursorMove::Left }, shift),
+            "ArrowRight" => CalculatorAction::Cursor(if ctrl { CursorMove::TokenRight } else { CursorMove::Right }, shift),
+            "Home" => CalculatorAction::Cursor(CursorMove::Start, shift),
+            "End" => CalculatorAction::Cursor(CursorMove::End, shift),
+            symbol if KEY_SYMBOLS.contains(symbol) && !symbol.is_empty() => CalculatorAction::Insert(String::from(symbol), true),
+            _ => CalculatorAction::None
+        };
+
+        actions.push(action);
+        actions
+    }
+
+    /**
+     * Render the graphemes of the buffer, split into segments at whitespace, each showing its value when hovered.
+     * The name being typed is shown at the caret, or at the end if there is no caret.
+     */
+    fn buffer_segments_html(&self, graphemes: &[&str], cursor: Option<usize>, selection: Option<Range<usize>>) -> Vec<Html> {
+        let typed_name = (!self.typed_name.is_empty()).then(|| html! {
+            <span class="typed-name">{ &self.typed_name }</span>
+        });
+
+        let caret = |position: usize| (cursor == Some(position)).then(|| html! {
+            <>
+                <span class="caret"></span>
+                { for typed_name.clone() }
+            </>
+        });
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+
+        while start < graphemes.len() {
+            let word_end = start + graphemes[start..].iter().take_while(|g| !buffer::is_whitespace(g)).count();
+            let end = word_end + graphemes[word_end..].iter().take_while(|g| buffer::is_whitespace(g)).count();
+
+            let word = graphemes[start..word_end].concat();
+            let tooltip = match self.calculator.evaluate_with_options(&word, EvaluateOptions::new(InterpreterOptions::new(true))) {
+                Ok((n, _)) if !word.is_empty() => Some(n.to_string()),
+                _ => None
+            };
+
+            //Split the segment where the caret and selection are
+            let mut bounds: Vec<usize> = [Some(start), Some(end), cursor, selection.as_ref().map(|s| s.start), selection.as_ref().map(|s| s.end)]
+                .into_iter()
+                .flatten()
+                .filter(|bound| (start..=end).contains(bound))
+                .collect();
+            bounds.sort_unstable();
+            bounds.dedup();
+
+            let parts = bounds.windows(2).map(|bound| {
+                let class = selection.as_ref().filter(|s| s.contains(&bound[0])).map(|_| "selected");
+
+                html! {
+                    <>
+                        { for caret(bound[0]) }
+                        <span {class}>{ graphemes[bound[0]..bound[1]].concat() }</span>
+                    </>
+                }
+            });
+
+            segments.push(html! {
+                <span title={tooltip}>{ for parts }</span>
+            });
+
+            start = end;
+        }
+
+        segments.extend(caret(graphemes.len()));
+
+        if cursor.is_none() {
+            segments.extend(typed_name);
+        }
+
+        segments
+    }
+
+    /**
+     * Translate a key of the keypad into actions. Trigonometric keys depend on the modifiers, which are reset once used.
+     */
+    fn keypad_actions(&mut self, action: KeyAction) -> Vec<CalculatorAction> {
+        let surround = |prefix: &str, suffix: &str| CalculatorAction::Surround {
+            prefix: (!prefix.is_empty()).then(|| String::from(prefix)),
+            open: String::from("("),
+            close: Some(String::from(")")),
+            suffix: (!suffix.is_empty()).then(|| String::from(suffix)),
+            preview: true
+        };
+
+        let action = match action {
+            KeyAction::Insert(content) => CalculatorAction::Insert(String::from(content), true),
+            KeyAction::Call(name) => CalculatorAction::Insert(format!("{name}("), true),
+            KeyAction::Surround(prefix, suffix) => surround(prefix, suffix),
+            KeyAction::Trig { name, hyperbolic } => {
+                let name = keypad::trig_name(name, hyperbolic, self.modifiers);
+                self.modifiers = Modifiers::empty();
+                CalculatorAction::Insert(format!("{name}("), true)
+            },
+            KeyAction::Modifier(modifier) => {
+                self.modifiers.toggle(modifier);
+                CalculatorAction::None
+            },
+            KeyAction::Backspace => CalculatorAction::Backspace(true),
+            KeyAction::Clear(clear_type) => CalculatorAction::Clear(clear_type),
+            KeyAction::Submit => CalculatorAction::Submit,
+            KeyAction::ToggleFunctions => CalculatorAction::ToggleFunctions,
+            KeyAction::AddData => {
+                let value = if self.buffer.is_empty() {
+                    self.result.clone().or_else(|| self.calculator.clone_current_state().history.last().cloned())
+                }
+                else {
+                    self.calculator.evaluate_with_options(self.buffer.as_str(), EvaluateOptions::new(InterpreterOptions::new(true)))
+                        .map_err(|err| log::error!("{err}"))
+                        .ok()
+                        .map(|(n, _)| n)
+                };
+
+                match value {
+                    Some(Value::Number(n)) => self.data.push(n),
+                    Some(Value::List(list)) => self.data.extend(list),
+                    Some(Value::Matrix(_)) => {
+                        log::error!("A matrix can't be added to the statistics data.");
+                        return Vec::new();
+                    },
+                    None => return Vec::new()
+                }
+
+                CalculatorAction::Clear(ClearType::ENTRY)
+            },
+            KeyAction::ClearData => {
+                self.data.clear();
+                CalculatorAction::None
+            },
+            KeyAction::DataCall(name) if self.data.is_empty() => CalculatorAction::Insert(format!("{name}("), true),
+            KeyAction::DataCall(name) => CalculatorAction::Insert(format!("{name}({})", Value::List(self.data.clone())), true)
+        };
+
+        vec![action]
+    }
+
+    /**
+     * Render the keys of the active keypad layout, and the tabs which switch between layouts
+     */
+    fn keypad_html(&self, ctx: &Context<Self>) -> Html {
+        let tabs = keypad::LAYOUTS.iter().enumerate().map(|(position, layout)| {
+            let class = classes!("keypad-layout", (position == self.layout).then_some("active"));
+
+            html! {
+                <button {class} onclick={ctx.link().callback(move |_: MouseEvent| CalculatorAction::SetLayout(position))}>{ layout.name }</button>
+            }
+        });
+
+        let rows = keypad::LAYOUTS[self.layout].rows.iter().map(|row| {
+            let cells = row.iter().map(|key| match key.action {
+                Some(action) => {
+                    //Modifier keys are highlighted while active
+                    let class = match action {
+                        KeyAction::Modifier(modifier) if self.modifiers.contains(modifier) => Some("active"),
+                        _ => None
+                    };
+
+                    html! {
+                        <td {class}><CalculatorButton display={key.display(self.modifiers)} callback_click={ctx.link().callback(move |_| CalculatorAction::Keypad(action))} /></td>
+                    }
+                },
+                None => html! {
+                    <td></td>
+                }
+            });
+
+            html! {
+                <tr>{ for cells }</tr>
+            }
+        });
+
+        html! {
+            <div class="keypad">
+                <div class="keypad-layouts">{ for tabs }</div>
+                <table class="calculator-buttons">{ for rows }</table>
+            </div>
+        }
+    }
+
+    /**
+     * Render the open side panels; history, most recent first, and the memory slots which have been assigned to.
+     * Clicking an entry inserts a reference to it.
+     */
+    fn panels_html(&self, ctx: &Context<Self>, state: &CalculatorState) -> Html {
+        let history_panel = self.panels.contains(Panels::HISTORY).then(|| {
+            let rows = state.history.iter().rev().enumerate().map(|(position, n)| {
+                let reference = format!("${position}");
+                let insert = ctx.link().callback(move |_: MouseEvent| CalculatorAction::Insert(reference.clone(), true));
+
+                html! {
+                    <div class="panel-entry">
+                        <button class="panel-value" title={n.to_string()} onclick={insert}>
+                            <span class="panel-reference">{ format!("${position}") }</span>{ value_html(n) }
+                        </button>
+                        <button title="Store in the first memory slot holding 0" onclick={ctx.link().callback(move |_: MouseEvent| CalculatorAction::StoreHistory(position))}>{ "M" }</button>
+                        <button title="Remove" onclick={ctx.link().callback(move |_: MouseEvent| CalculatorAction::RemoveHistory(position))}>{ "×" }</button>
+                    </div>
+                }
+            });
+
+            html! {
+                <div class="panel">
+                    <div class="panel-heading">{ "History" }</div>
+                    <div class="panel-entries">{ for rows }</div>
+                </div>
+            }
+        });
+
+        let memory_panel = self.panels.contains(Panels::MEMORY).then(|| {
+            let rows = state.memory.iter().enumerate()
+                .filter(|(_, n)| **n != Value::default())
+                .map(|(slot, n)| {
+                    let reference = format!("$m{slot}");
+                    let insert = ctx.link().callback(move |_: MouseEvent| CalculatorAction::Insert(reference.clone(), true));
+
+                    html! {
+                        <div class="panel-entry">
+                            <button class="panel-value" title={n.to_string()} onclick={insert}>
+                                <span class="panel-reference">{ format!("$m{slot}") }</span>{ value_html(n) }
+                            </button>
+                            <button title="Clear" onclick={ctx.link().callback(move |_: MouseEvent| CalculatorAction::ClearMemorySlot(slot))}>{ "×" }</button>
+                        </div>
+                    }
+                });
+
+            html! {
+                <div class="panel">
+                    <div class="panel-heading">
+                        { "Memory" }
+                        <button title="Store the last result in the first memory slot holding 0" disabled={state.history.is_empty()} onclick={ctx.link().callback(|_: MouseEvent| CalculatorAction::StoreHistory(0))}>{ "Store" }</button>
+                    </div>
+                    <div class="panel-entries">{ for rows }</div>
+                </div>
+            }
+        });
+
+        html! {
+            <div class="calculator-panels">
+                { for history_panel }
+                { for memory_panel }
+            </div>
+        }
+    }
+
+    /**
+     * Render the list of functions matching the search, by category, each inserting a call to the function when clicked
+     */
+    fn function_picker_html(&self, ctx: &Context<Self>, search: &str) -> Html {
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            CalculatorAction::SearchFunctions(input.value())
+        });
+
+        let mut category = None;
+
+        let rows = self.calculator.interpreter().search_funcs(search).into_iter().map(|(name, function)| {
+            let heading = (category != Some(function.info.category)).then(|| {
+                category = Some(function.info.category);
+
+                html! {
+                    <div class="function-category">{ function.info.category.to_string() }</div>
+                }
+            });
+
+            let name = name.clone();
+            let signature = format!("{name}{function}");
+            let title = match function.info.domain {
+                Some(domain) => format!("{} e.g. {}", domain, function.info.example),
+                None => format!("e.g. {}", function.info.example)
+            };
+            let onclick = ctx.link().callback(move |_: MouseEvent| CalculatorAction::PickFunction(name.clone()));
+
+            html! {
+                <>
+                    { for heading }
+                    <button class="function" {title} {onclick}>
+                        <span class="function-signature">{ signature }</span>
+                        <span class="function-description">{ function.info.description }</span>
+                    </button>
+                </>
+            }
+        });
+
+        html! {
+            <div class="function-picker">
+                <div class="function-picker-header">
+                    <input type="search" placeholder="Search functions" value={search.to_string()} {oninput} />
+                    <button onclick={ctx.link().callback(|_: MouseEvent| CalculatorAction::ToggleFunctions)}>{ "Close" }</button>
+                </div>
+                <div class="function-list">{ for rows }</div>
+            </div>
+        }
+    }
+}
+
+/**
+ * Render a result, showing matrices as a table with a row for each row of the matrix
+ */
+fn value_html(value: &Value) -> Html {
+    match value {
+        Value::Matrix(matrix) => {
+            let rows = (0..matrix.rows()).map(|row| {
+                let cells = matrix.row(row).into_iter().map(|n| html! {
+                    <td>{ n.to_string() }</td>
+                });
+
+                html! {
+                    <tr>{ for cells }</tr>
+                }
+            });
+
+            html! {
+                <table class="matrix">{ for rows }</table>
+            }
+        },
+        value => html! {
+            { value.to_string() }
+        }
+    }
+}
+
+/**
+ * Store the result at the given position back in history in the first memory slot holding 0, which is the value of unused memory.
+ * Fails if every slot holds a value, rather than overwriting one.
+ */
+fn store_history(mut state: CalculatorState, position: usize) -> Result<CalculatorState, CalculatorErr> {
+    let value = state.history.len().checked_sub(position + 1)
+        .map(|index| state.history[index].clone())
+        .ok_or_else(|| CalculatorErr::interp_err(format!("There is no result ${position} to store.").as_str()))?;
+
+    let slot = state.memory.iter()
+        .position(|value| *value == Value::default())
+        .ok_or_else(|| CalculatorErr::interp_err(format!("Memory is full; clear a memory slot to store ${position}.").as_str()))?;
+
+    log::info!("Store ${position} in $m{slot}");
+    state.memory[slot] = value;
+    Ok(state)
+}
+
+/**
+ * Space out a symbol to insert at the cursor of the buffer, returning the content to insert and whether to trim the whitespace before it.
+ * Infix operators and names are surrounded by spaces, and suffix operators are followed by one, with the spaces before them trimmed.
+ * If the buffer is empty, infix and suffix operators apply to the last result.
+ */
+fn spaced_insertion(calculator: &Calculator, buffer: &Buffer, symbol: String) -> (String, bool) {
+    let before = buffer.before_cursor();
+    let parser = calculator.parser();
+
+    let is_infix = parser.parse_expression::<calculator_parser::expression::BinopInfix>(symbol.as_str()).is_ok();
+    let is_suffix = parser.parse_expression::<calculator_parser::expression::UnopSuffix>(symbol.as_str()).is_ok();
+    let is_name = parser.parse_expression::<calculator_parser::expression::IdToken>(symbol.as_str()).is_ok();
+
+    if buffer.is_empty() && calculator.has_history()
+        && parser.parse_expression::<calculator_parser::expression::UnopPrefix>(symbol.as_str()).is_err() {
+        if is_infix {
+            return (format!("$0 {symbol} "), false);
+        }
+        else if is_suffix {
+            return (format!("$0{symbol}"), false);
+        }
+    }
+    else if !before.is_empty() {
+        //Letters following a number, letter or '$' are part of it, such as dice '2d6', hexadecimal '0xFF' or memory '$m0'; names of functions, such as 'and', are not
+        let is_function = calculator.interpreter().get_func_by_name(&symbol).is_some() || calculator.interpreter().get_user_func_by_name(&symbol).is_some();
+        let is_joined = before.ends_with(|c: char| c.is_ascii_alphanumeric() || c == '$') && (symbol.chars().count() == 1 || !is_function);
+
+        if is_infix || (is_name && !is_joined) {
+            return (format!("{}{symbol} ", if before.ends_with(' ') { "" } else { " " }), false);
+        }
+        else if is_suffix {
+            return (format!("{symbol} "), true);
+        }
+    }
+
+    (symbol, false)
+}
+
+/**
+ * Check if a key pressed on the keyboard does something in the calculator, with control held or not
+ */
+fn is_calculator_key(key: &str, ctrl: bool) -> bool {
+    if ctrl {
+        return matches!(key, "z" | "Z" | "y" | "a" | "ArrowLeft" | "ArrowRight");
+    }
+
+    let is_char = matches!(key.chars().collect::<Vec<char>>().as_slice(), [c] if c.is_ascii_alphanumeric() || *c == '_' || *c == ' ' || KEY_SYMBOLS.contains(*c));
+
+    is_char || matches!(key, "Enter" | "=" | "Backspace" | "Delete" | "Escape" | "ArrowLeft" | "ArrowRight" | "Home" | "End")
+}
+
+/**
+ * The data being copied or pasted by a clipboard event. 'ClipboardEvent' is an unstable API in web-sys, so its data is read as a property.
+ */
+fn clipboard_data(e: &Event) -> Option<web_sys::DataTransfer> {
+    js_sys::Reflect::get(e, &wasm_bindgen::JsValue::from_str("clipboardData")).ok()
+        .and_then(|data| data.dyn_into::<web_sys::DataTransfer>().ok())
+}
+
+/**
+ * Write the text to the clipboard. 'Clipboard' is an unstable API in web-sys, so 'navigator.clipboard.writeText' is called through its properties.
+ */
+fn write_clipboard(text: String) {
+    let property = |target: &wasm_bindgen::JsValue, name: &str| js_sys::Reflect::get(target, &wasm_bindgen::JsValue::from_str(name)).ok()
+        .filter(|value| !value.is_undefined());
+
+    let clipboard = web_sys::window().and_then(|window| property(&window, "navigator"))
+        .and_then(|navigator| property(&navigator, "clipboard"));
+
+    let write_text = clipboard.as_ref()
+        .and_then(|clipboard| property(clipboard, "writeText"))
+        .and_then(|write_text| write_text.dyn_into::<js_sys::Function>().ok());
+
+    let promise = match (clipboard, write_text) {
+        (Some(clipboard), Some(write_text)) => write_text.call1(&clipboard, &wasm_bindgen::JsValue::from_str(&text)),
+        _ => {
+            log::warn!("The clipboard is unavailable; nothing copied.");
+            return;
+        }
+    };
+
+    match promise {
+        Ok(promise) => wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err) = wasm_bindgen_futures::JsFuture::from(promise.unchecked_into::<js_sys::Promise>()).await {
+                log::error!("Failed to copy to the clipboard: {err:?}");
+            }
+        }),
+        Err(err) => log::error!("Failed to copy to the clipboard: {err:?}")
+    }
+}
+
+/**
+ * The side panels which were open when the page was last used
+ */
+fn load_panels() -> Panels {
+    local_storage()
+        .and_then(|storage| storage.get_item(PANELS_STORAGE_KEY).ok().flatten())
+        .and_then(|bits| bits.parse::<u32>().ok())
+        .map(Panels::from_bits_truncate)
+        .unwrap_or_default()
+}
+
+/**
+ * The keypad layout which was used when the page was last used, defaulting to the first
+ */
+fn load_layout() -> usize {
+    local_storage()
+        .and_then(|storage| storage.get_item(LAYOUT_STORAGE_KEY).ok().flatten())
+        .and_then(|name| keypad::LAYOUTS.iter().position(|layout| layout.name == name))
+        .unwrap_or_default()
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|window| window.local_storage().ok().flatten())
+}
@@ -0,0 +1,76 @@
+use super::{spaced_insertion, Buffer, Calculator};
+
+/**
+ * Insert each symbol in turn, spaced as it would be from the keypad or keyboard, and return the buffer
+ */
+fn insert_all(calculator: &Calculator, symbols: &[&str]) -> String {
+    let mut buffer = Buffer::default();
+
+    for symbol in symbols {
+        let (content, trim) = spaced_insertion(calculator, &buffer, String::from(*symbol));
+        buffer.insert(&content, trim);
+    }
+
+    buffer.as_str().to_string()
+}
+
+#[test]
+/**
+ * Test that function names are spaced out from the number before them, rather than being read as part of it
+ */
+fn spaced_insertion_0() {
+    let calculator = Calculator::default();
+
+    let entered = insert_all(&calculator, &["0x", "F", "F", "and", "0x", "F"]);
+    assert_eq!(entered, "0xFF and 0xF");
+    assert_eq!(calculator.evaluate(&entered).unwrap().to_string(), "15");
+
+    assert_eq!(insert_all(&calculator, &["12", "mod", "5"]), "12 mod 5");
+    assert_eq!(insert_all(&calculator, &["1", "shl", "4", "xor", "3"]), "1 shl 4 xor 3");
+    assert_eq!(insert_all(&calculator, &["7", "+", "2", "!"]), "7 + 2! ");
+}
+
+#[test]
+/**
+ * Test that letters which are part of the word before them are not spaced out from it
+ */
+fn spaced_insertion_1() {
+    let calculator = Calculator::default();
+
+    assert_eq!(insert_all(&calculator, &["0x", "A", "B"]), "0xAB");
+    assert_eq!(insert_all(&calculator, &["2", "d6"]), "2d6");
+    assert_eq!(insert_all(&calculator, &["0", "xFF"]), "0xFF");
+    assert_eq!(insert_all(&calculator, &["$", "m0"]), "$m0");
+}
+
+#[test]
+/**
+ * Test that operators entered first apply to the last result
+ */
+fn spaced_insertion_2() {
+    let calculator = Calculator::default();
+    calculator.evaluate("2").unwrap();
+
+    assert_eq!(insert_all(&calculator, &["+", "3"]), "$0 + 3");
+    assert_eq!(insert_all(&calculator, &["!"]), "$0!");
+    assert_eq!(insert_all(&calculator, &["-", "3"]), "-3");
+}
+
+#[test]
+/**
+ * Test that results are stored in the first memory slot holding 0, and that storing fails once every slot holds a value
+ */
+fn store_history_0() {
+    use super::{CalculatorState, Value, store_history};
+
+    let state = CalculatorState::new(vec![Value::Number(1_f64), Value::default(), Value::default()], vec![Value::Number(5_f64), Value::Number(7_f64)]);
+
+    let state = store_history(state, 0).unwrap();
+    assert_eq!(state.memory, vec![Value::Number(1_f64), Value::Number(7_f64), Value::default()]);
+
+    let state = store_history(state, 1).unwrap();
+    assert_eq!(state.memory, vec![Value::Number(1_f64), Value::Number(7_f64), Value::Number(5_f64)]);
+
+    assert!(store_history(state.clone(), 0).is_err());
+    assert!(store_history(state, 2).is_err());
+}
@@ -0,0 +1,188 @@
+use bitflags::bitflags;
+
+use super::calculator_interface::ClearType;
+
+bitflags! {
+    /**
+     * The modifiers which change what the trigonometric keys call
+     */
+    #[derive(Default)]
+    pub struct Modifiers: u32 {
+        const INVERSE = 0b00000001;
+        const HYPERBOLIC = 0b00000010;
+    }
+}
+
+/**
+ * What a key of the keypad does when pressed
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum KeyAction {
+    Insert(&'static str),
+    /**
+     * Insert a call to the function, i.e. 'sqrt('
+     */
+    Call(&'static str),
+    /**
+     * Surround the entry in parentheses, with a prefix and suffix, i.e. 'sqrt' and ''
+     */
+    Surround(&'static str, &'static str),
+    /**
+     * Insert a call to a trigonometric function, which is its inverse and/or hyperbolic version depending on the modifiers.
+     * Only some functions have a hyperbolic version.
+     */
+    Trig { name: &'static str, hyperbolic: bool },
+    Modifier(Modifiers),
+    Backspace,
+    Clear(ClearType),
+    Submit,
+    ToggleFunctions,
+    /**
+     * Add the entry, or the most recent result, to the statistics data
+     */
+    AddData,
+    ClearData,
+    /**
+     * Insert a call to the function with the statistics data as its argument
+     */
+    DataCall(&'static str)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Key {
+    pub label: &'static str,
+    pub action: Option<KeyAction>
+}
+
+impl Key {
+    /**
+     * A gap in the keypad
+     */
+    pub const EMPTY: Self = Self { label: "", action: None };
+
+    const fn new(label: &'static str, action: KeyAction) -> Self {
+        Self {
+            label,
+            action: Some(action)
+        }
+    }
+
+    /**
+     * The label of the key, given the modifiers which are active
+     */
+    pub fn display(&self, modifiers: Modifiers) -> String {
+        match self.action {
+            Some(KeyAction::Trig { name, hyperbolic }) => trig_name(name, hyperbolic, modifiers),
+            _ => String::from(self.label)
+        }
+    }
+}
+
+/**
+ * A set of keys, laid out in rows
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Layout {
+    pub name: &'static str,
+    pub rows: &'static [[Key; 6]]
+}
+
+/**
+ * The name of the trigonometric function called, i.e. 'asinh' for 'sin' with both modifiers
+ */
+pub fn trig_name(name: &str, hyperbolic: bool, modifiers: Modifiers) -> String {
+    let inverse = if modifiers.contains(Modifiers::INVERSE) { "a" } else { "" };
+    let hyperbolic = if hyperbolic && modifiers.contains(Modifiers::HYPERBOLIC) { "h" } else { "" };
+
+    format!("{inverse}{name}{hyperbolic}")
+}
+
+const fn insert(label: &'static str) -> Key {
+    Key::new(label, KeyAction::Insert(label))
+}
+
+const fn insert_as(label: &'static str, content: &'static str) -> Key {
+    Key::new(label, KeyAction::Insert(content))
+}
+
+const fn call(label: &'static str, name: &'static str) -> Key {
+    Key::new(label, KeyAction::Call(name))
+}
+
+const fn surround(label: &'static str, prefix: &'static str, suffix: &'static str) -> Key {
+    Key::new(label, KeyAction::Surround(prefix, suffix))
+}
+
+const fn trig(name: &'static str, hyperbolic: bool) -> Key {
+    Key::new(name, KeyAction::Trig { name, hyperbolic })
+}
+
+const fn data_call(label: &'static str, name: &'static str) -> Key {
+    Key::new(label, KeyAction::DataCall(name))
+}
+
+const FUNCTIONS: Key = Key::new("f(x)", KeyAction::ToggleFunctions);
+const CLEAR: Key = Key::new("C", KeyAction::Clear(ClearType::all()));
+const CLEAR_ENTRY: Key = Key::new("CE", KeyAction::Clear(ClearType::ENTRY));
+const BACKSPACE: Key = Key::new("BK", KeyAction::Backspace);
+const SUBMIT: Key = Key::new("=", KeyAction::Submit);
+const DIV: Key = Key::new("÷", KeyAction::Insert("/"));
+const MULT: Key = Key::new("×", KeyAction::Insert("*"));
+const NEG: Key = surround("-x", "-", "");
+
+pub const BASIC: Layout = Layout {
+    name: "Basic",
+    rows: &[
+        [FUNCTIONS, insert_as("π", "pi"), insert("e"), CLEAR, CLEAR_ENTRY, BACKSPACE],
+        [insert("("), insert(")"), surround("(x)", "", ""), insert("$"), insert("$m"), insert(":")],
+        [surround("|x|", "abs", ""), NEG, insert("^"), insert("%"), insert("!"), DIV],
+        [surround("√x", "sqrt", ""), surround("1/x", "1 / ", ""), insert("7"), insert("8"), insert("9"), MULT],
+        [surround("x^2", "", " ^ 2"), surround("2^x", "2 ^ ", ""), insert("4"), insert("5"), insert("6"), insert("-")],
+        [surround("e^x", "e ^ ", ""), surround("10^x", "10 ^ ", ""), insert("1"), insert("2"), insert("3"), insert("+")],
+        [surround("log", "log", ""), surround("ln", "ln", ""), Key::EMPTY, insert("0"), insert("."), SUBMIT]
+    ]
+};
+
+pub const SCIENTIFIC: Layout = Layout {
+    name: "Scientific",
+    rows: &[
+        [FUNCTIONS, Key::new("INV", KeyAction::Modifier(Modifiers::INVERSE)), Key::new("HYP", KeyAction::Modifier(Modifiers::HYPERBOLIC)), CLEAR, CLEAR_ENTRY, BACKSPACE],
+        [trig("sin", true), trig("cos", true), trig("tan", true), insert("("), insert(")"), DIV],
+        [trig("csc", false), trig("sec", false), trig("cot", false), insert_as("π", "pi"), insert("e"), MULT],
+        [surround("x^2", "", " ^ 2"), insert_as("x^y", "^"), insert("7"), insert("8"), insert("9"), insert("-")],
+        [surround("√x", "sqrt", ""), insert_as("n!", "!"), insert("4"), insert("5"), insert("6"), insert("+")],
+        [call("log", "log"), call("ln", "ln"), insert("1"), insert("2"), insert("3"), insert(",")],
+        [surround("e^x", "e ^ ", ""), surround("10^x", "10 ^ ", ""), NEG, insert("0"), insert("."), SUBMIT]
+    ]
+};
+
+pub const PROGRAMMER: Layout = Layout {
+    name: "Programmer",
+    rows: &[
+        [insert("0x"), insert("0b"), insert("0o"), Key::new("CLR", KeyAction::Clear(ClearType::all())), CLEAR_ENTRY, BACKSPACE],
+        [insert("A"), insert("B"), insert("C"), insert("and"), insert("or"), insert("xor")],
+        [insert("D"), insert("E"), insert("F"), call("not", "not"), insert("shl"), insert("shr")],
+        [insert("("), insert(")"), insert("7"), insert("8"), insert("9"), MULT],
+        [insert("mod"), insert("%"), insert("4"), insert("5"), insert("6"), insert("-")],
+        [insert("^"), DIV, insert("1"), insert("2"), insert("3"), insert("+")],
+        [insert("$"), insert("$m"), NEG, insert("0"), insert(","), SUBMIT]
+    ]
+};
+
+pub const STATISTICS: Layout = Layout {
+    name: "Statistics",
+    rows: &[
+        [FUNCTIONS, Key::new("Σ+", KeyAction::AddData), Key::new("CD", KeyAction::ClearData), CLEAR, CLEAR_ENTRY, BACKSPACE],
+        [data_call("x̄", "mean"), data_call("Σx", "sum"), data_call("min", "min"), data_call("max", "max"), insert("("), insert(")")],
+        [call("normpdf", "normpdf"), call("normcdf", "normcdf"), insert("7"), insert("8"), insert("9"), DIV],
+        [call("binompdf", "binompdf"), call("binomcdf", "binomcdf"), insert("4"), insert("5"), insert("6"), MULT],
+        [call("poissonpdf", "poissonpdf"), call("tcdf", "tcdf"), insert("1"), insert("2"), insert("3"), insert("-")],
+        [insert("["), insert("]"), insert(","), insert("0"), insert("."), insert("+")],
+        [call("norminv", "norminv"), call("chisqcdf", "chisqcdf"), insert("!"), insert("^"), NEG, SUBMIT]
+    ]
+};
+
+/**
+ * The layouts which can be switched between, the first being the default
+ */
+pub const LAYOUTS: [Layout; 4] = [BASIC, SCIENTIFIC, PROGRAMMER, STATISTICS];
@@ -2,7 +2,10 @@
 
 pub use calculator::calculator;
 
+mod buffer;
 mod calculator_interface;
+mod keypad;
+mod plot;
 
 fn main() {
     //Initialize WASM logging
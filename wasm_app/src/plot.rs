@@ -0,0 +1,507 @@
+use yew::prelude::*;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use super::calculator::{Calculator, calculator_err::CalculatorErr, calculator_plot::{self, Curve, PlotFunction, Point, Viewport}};
+
+/**
+ * The colors the functions are drawn in, repeating once they run out
+ */
+const COLORS: [&str; 6] = ["#1f77b4", "#d62728", "#2ca02c", "#9467bd", "#ff7f0e", "#17becf"];
+
+/**
+ * The size the canvas is drawn at, before being scaled to fit the page
+ */
+const CANVAS_WIDTH: u32 = 600;
+const CANVAS_HEIGHT: u32 = 400;
+
+/**
+ * How many samples are taken across the canvas, before adding more where the curve bends
+ */
+const RESOLUTION: usize = 300;
+
+/**
+ * How close to a root or extremum, in pixels, the trace snaps to it
+ */
+const SNAP_DISTANCE: f64 = 6_f64;
+
+/**
+ * How much one step of the mouse wheel zooms by
+ */
+const ZOOM_FACTOR: f64 = 1.2_f64;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PlotAction {
+    SetFunction(usize, String),
+    AddFunction,
+    RemoveFunction(usize),
+    /**
+     * Zoom around a point, given as its position across and down the canvas from 0 to 1, where a factor greater than 1 zooms out
+     */
+    Zoom { factor: f64, x: f64, y: f64 },
+    /**
+     * Start dragging the plot, from a position across and down the canvas
+     */
+    DragStart(f64, f64),
+    /**
+     * Hover over a position across and down the canvas, panning if it's being dragged
+     */
+    Move(f64, f64),
+    DragEnd,
+    Leave,
+    /**
+     * Fit the y range to the functions
+     */
+    Fit,
+    Reset
+}
+
+#[derive(Properties, Debug, PartialEq, Clone)]
+pub struct PlotProperties {
+    /**
+     * The calculator whose variables and functions the expressions are evaluated with
+     */
+    pub calculator: Calculator,
+    /**
+     * The entry of the calculator, which is the first function plotted if it isn't empty
+     */
+    pub entry: AttrValue
+}
+
+/**
+ * An expression entered to plot, which has been parsed if it could be, and sampled across the viewport
+ */
+#[derive(Debug, PartialEq, Clone)]
+struct PlotEntry {
+    expression: String,
+    function: Option<Result<PlotFunction, CalculatorErr>>,
+    curve: Curve
+}
+
+/**
+ * A zoomable and pannable plot of functions of a single variable, with their roots and extrema marked, and their values traced under the mouse
+ */
+#[derive(Debug)]
+pub struct PlotView {
+    entries: Vec<PlotEntry>,
+    viewport: Viewport,
+    /**
+     * The position across and down the canvas that the mouse is over
+     */
+    hover: Option<(f64, f64)>,
+    /**
+     * The position across and down the canvas that the plot was last dragged to, while it's being dragged
+     */
+    drag: Option<(f64, f64)>,
+    canvas_ref: NodeRef
+}
+
+impl PlotView {
+    fn new_entry(calculator: &Calculator, expression: String) -> PlotEntry {
+        let function = (!expression.trim().is_empty()).then(|| PlotFunction::parse(calculator, &expression));
+
+        PlotEntry {
+            expression,
+            function,
+            curve: Curve::default()
+        }
+    }
+
+    fn functions(&self) -> Vec<PlotFunction> {
+        self.entries.iter()
+            .filter_map(|entry| match &entry.function {
+                Some(Ok(function)) => Some(function.clone()),
+                _ => None
+            })
+            .collect()
+    }
+
+    /**
+     * Sample the functions across the viewport, after it or the functions have changed
+     */
+    fn resample(&mut self, calculator: &Calculator) {
+        for entry in self.entries.iter_mut() {
+            entry.curve = match &entry.function {
+                Some(Ok(function)) => function.sample(calculator.interpreter(), &self.viewport, RESOLUTION),
+                _ => Curve::default()
+            };
+        }
+    }
+
+    /**
+     * The viewport across the default x range, fitted to the functions
+     */
+    fn fitted_viewport(&self, calculator: &Calculator, x_min: f64, x_max: f64) -> Viewport {
+        let functions = self.functions();
+
+        if functions.is_empty() {
+            Viewport::new(x_min, x_max, Viewport::default().y_min, Viewport::default().y_max)
+        }
+        else {
+            PlotFunction::fit(&functions, calculator.interpreter(), x_min, x_max, RESOLUTION)
+        }
+    }
+
+    /**
+     * The point on the plane at a position across and down the canvas
+     */
+    fn point_at(&self, (x, y): (f64, f64)) -> Point {
+        Point::new(self.viewport.x_min + x * self.viewport.width(), self.viewport.y_max - y * self.viewport.height())
+    }
+
+    /**
+     * The value of x being traced, which snaps to a root or extremum close to the mouse
+     */
+    fn trace_x(&self) -> Option<f64> {
+        let hover = self.point_at(self.hover?);
+        let snap = SNAP_DISTANCE / CANVAS_WIDTH as f64 * self.viewport.width();
+
+        let markers = self.entries.iter().flat_map(|entry| entry.curve.roots.iter()
+            .copied()
+            .chain(entry.curve.extrema.iter().map(|extremum| extremum.point)));
+
+        let nearest = markers
+            .filter(|point| (point.x - hover.x).abs() <= snap)
+            .min_by(|a, b| (a.x - hover.x).abs().total_cmp(&(b.x - hover.x).abs()));
+
+        Some(nearest.map_or(hover.x, |point| point.x))
+    }
+
+    /**
+     * Draw the grid, the curves and their markers, and the trace on the canvas
+     */
+    fn draw(&self, calculator: &Calculator) {
+        let canvas = match self.canvas_ref.cast::<HtmlCanvasElement>() {
+            Some(canvas) => canvas,
+            None => return
+        };
+
+        let context = match canvas.get_context("2d") {
+            Ok(Some(context)) => match context.dyn_into::<CanvasRenderingContext2d>() {
+                Ok(context) => context,
+                Err(_) => return
+            },
+            _ => {
+                log::error!("The canvas can't be drawn on.");
+                return;
+            }
+        };
+
+        let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+        let viewport = self.viewport;
+
+        //Points far off the canvas are drawn at its edge, so that steep curves are still drawn towards it
+        let to_canvas = |point: Point| (
+            (point.x - viewport.x_min) / viewport.width() * width,
+            ((viewport.y_max - point.y) / viewport.height() * height).clamp(-height, height * 2_f64)
+        );
+
+        context.clear_rect(0_f64, 0_f64, width, height);
+        context.set_font("11px sans-serif");
+        context.set_line_width(1_f64);
+
+        //Grid lines and labels at round values, with the axes through zero where they can be seen
+        let (origin_x, origin_y) = to_canvas(Point::new(0_f64, 0_f64));
+        let label_x = origin_x.clamp(2_f64, width - 30_f64);
+        let label_y = origin_y.clamp(12_f64, height - 4_f64);
+
+        context.set_fill_style(&JsValue::from_str("#555555"));
+
+        for tick in calculator_plot::ticks(viewport.x_min, viewport.x_max, 10) {
+            let (x, _) = to_canvas(Point::new(tick.value, 0_f64));
+            stroke_line(&context, "#dddddd", (x, 0_f64), (x, height));
+
+            if tick.value != 0_f64 {
+                _ = context.fill_text(&tick.label, x + 2_f64, label_y + if label_y > height - 14_f64 { -2_f64 } else { 12_f64 });
+            }
+        }
+
+        for tick in calculator_plot::ticks(viewport.y_min, viewport.y_max, 8) {
+            let (_, y) = to_canvas(Point::new(0_f64, tick.value));
+            stroke_line(&context, "#dddddd", (0_f64, y), (width, y));
+
+            if tick.value != 0_f64 {
+                _ = context.fill_text(&tick.label, label_x + 3_f64, y - 2_f64);
+            }
+        }
+
+        stroke_line(&context, "#333333", (origin_x, 0_f64), (origin_x, height));
+        stroke_line(&context, "#333333", (0_f64, origin_y), (width, origin_y));
+
+        //The curves, with a circle at each root and a square at each extremum
+        context.set_line_width(2_f64);
+
+        for (entry, color) in self.entries.iter().zip(COLORS.iter().cycle()) {
+            context.set_stroke_style(&JsValue::from_str(color));
+            context.set_fill_style(&JsValue::from_str(color));
+
+            for segment in &entry.curve.segments {
+                context.begin_path();
+
+                for (i, point) in segment.iter().enumerate() {
+                    let (x, y) = to_canvas(*point);
+
+                    if i == 0 {
+                        context.move_to(x, y);
+                    }
+                    else {
+                        context.line_to(x, y);
+                    }
+                }
+
+                context.stroke();
+            }
+
+            for root in &entry.curve.roots {
+                let (x, y) = to_canvas(*root);
+
+                context.begin_path();
+                _ = context.arc(x, y, 4_f64, 0_f64, std::f64::consts::TAU);
+                context.fill();
+            }
+
+            for extremum in &entry.curve.extrema {
+                let (x, y) = to_canvas(extremum.point);
+                context.stroke_rect(x - 4_f64, y - 4_f64, 8_f64, 8_f64);
+            }
+        }
+
+        //The value of each function where the mouse is
+        if let Some(trace) = self.trace_x() {
+            let (x, _) = to_canvas(Point::new(trace, 0_f64));
+
+            context.set_line_width(1_f64);
+            _ = context.set_line_dash(&js_sys::Array::of2(&JsValue::from(4), &JsValue::from(4)));
+            stroke_line(&context, "#888888", (x, 0_f64), (x, height));
+            _ = context.set_line_dash(&js_sys::Array::new());
+
+            for (entry, color) in self.entries.iter().zip(COLORS.iter().cycle()) {
+                let value = match &entry.function {
+                    Some(Ok(function)) => function.evaluate(calculator.interpreter(), trace),
+                    _ => None
+                };
+
+                if let Some(value) = value {
+                    let (x, y) = to_canvas(Point::new(trace, value));
+
+                    context.set_fill_style(&JsValue::from_str(color));
+                    context.begin_path();
+                    _ = context.arc(x, y, 3_f64, 0_f64, std::f64::consts::TAU);
+                    context.fill();
+
+                    //Keep the label on the canvas
+                    let label = format!("({}, {})", round_label(trace), round_label(value));
+                    let label_x = if x > width - 120_f64 { x - 120_f64 } else { x + 6_f64 };
+                    _ = context.fill_text(&label, label_x, y.clamp(12_f64, height - 4_f64) - 6_f64);
+                }
+            }
+        }
+    }
+}
+
+impl Component for PlotView {
+    type Message = PlotAction;
+    type Properties = PlotProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let calculator = &ctx.props().calculator;
+
+        let mut plot = Self {
+            entries: vec![Self::new_entry(calculator, ctx.props().entry.to_string())],
+            viewport: Viewport::default(),
+            hover: None,
+            drag: None,
+            canvas_ref: NodeRef::default()
+        };
+
+        let default = Viewport::default();
+        plot.viewport = plot.fitted_viewport(calculator, default.x_min, default.x_max);
+        plot.resample(calculator);
+        plot
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        //Variables and functions may have been defined since the expressions were parsed
+        if ctx.props().calculator != old_props.calculator {
+            let calculator = &ctx.props().calculator;
+
+            for entry in self.entries.iter_mut() {
+                *entry = Self::new_entry(calculator, std::mem::take(&mut entry.expression));
+            }
+
+            self.resample(calculator);
+        }
+
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let calculator = &ctx.props().calculator;
+
+        match msg {
+            PlotAction::SetFunction(position, expression) => {
+                match self.entries.get_mut(position) {
+                    Some(entry) => *entry = Self::new_entry(calculator, expression),
+                    None => return false
+                }
+            },
+            PlotAction::AddFunction => self.entries.push(Self::new_entry(calculator, String::new())),
+            PlotAction::RemoveFunction(position) => {
+                if position >= self.entries.len() {
+                    return false;
+                }
+
+                self.entries.remove(position);
+            },
+            PlotAction::Zoom { factor, x, y } => self.viewport = self.viewport.zoom(factor, self.point_at((x, y))),
+            PlotAction::DragStart(x, y) => {
+                self.drag = Some((x, y));
+                return false;
+            },
+            PlotAction::Move(x, y) => {
+                self.hover = Some((x, y));
+
+                //Only the trace moves unless the plot is being dragged
+                let (from_x, from_y) = match self.drag {
+                    Some(from) => from,
+                    None => return true
+                };
+
+                self.drag = Some((x, y));
+                self.viewport = self.viewport.pan((from_x - x) * self.viewport.width(), (y - from_y) * self.viewport.height());
+            },
+            PlotAction::DragEnd => {
+                self.drag = None;
+                return false;
+            },
+            PlotAction::Leave => {
+                self.hover = None;
+                self.drag = None;
+                return true;
+            },
+            PlotAction::Fit => self.viewport = self.fitted_viewport(calculator, self.viewport.x_min, self.viewport.x_max),
+            PlotAction::Reset => {
+                let default = Viewport::default();
+                self.viewport = self.fitted_viewport(calculator, default.x_min, default.x_max);
+            }
+        }
+
+        self.resample(calculator);
+        true
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        self.draw(&ctx.props().calculator);
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let rows = self.entries.iter().zip(COLORS.iter().cycle()).enumerate().map(|(position, (entry, color))| {
+            let oninput = ctx.link().callback(move |e: InputEvent| {
+                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                PlotAction::SetFunction(position, input.value())
+            });
+
+            let error = match &entry.function {
+                Some(Err(err)) => Some(html! {
+                    <div class="plot-error">{ err.to_string() }</div>
+                }),
+                _ => None
+            };
+
+            let label = match &entry.function {
+                Some(Ok(function)) => format!("f{}({})", position + 1, function.variable()),
+                _ => format!("f{}", position + 1)
+            };
+
+            html! {
+                <>
+                    <div class="plot-function">
+                        <span class="plot-label" style={format!("color: {color}")}>{ label }</span>
+                        <input type="text" placeholder="sin(x) * x" value={entry.expression.clone()} {oninput} />
+                        <button title="Remove" onclick={ctx.link().callback(move |_: MouseEvent| PlotAction::RemoveFunction(position))}>{ "×" }</button>
+                    </div>
+                    { for error }
+                </>
+            }
+        });
+
+        let onwheel = ctx.link().callback(|e: WheelEvent| {
+            e.prevent_default();
+            let (x, y) = canvas_position(&e);
+            PlotAction::Zoom { factor: if e.delta_y() > 0_f64 { ZOOM_FACTOR } else { 1_f64 / ZOOM_FACTOR }, x, y }
+        });
+
+        let onmousedown = ctx.link().callback(|e: MouseEvent| {
+            let (x, y) = canvas_position(&e);
+            PlotAction::DragStart(x, y)
+        });
+
+        let onmousemove = ctx.link().callback(|e: MouseEvent| {
+            let (x, y) = canvas_position(&e);
+            PlotAction::Move(x, y)
+        });
+
+        let onmouseup = ctx.link().callback(|_: MouseEvent| PlotAction::DragEnd);
+        let onmouseleave = ctx.link().callback(|_: MouseEvent| PlotAction::Leave);
+
+        let viewport = format!("x: {} to {}, y: {} to {}",
+            round_label(self.viewport.x_min), round_label(self.viewport.x_max), round_label(self.viewport.y_min), round_label(self.viewport.y_max));
+
+        html! {
+            <div class="plot">
+                <canvas width={CANVAS_WIDTH.to_string()} height={CANVAS_HEIGHT.to_string()} ref={self.canvas_ref.clone()} {onwheel} {onmousedown} {onmousemove} {onmouseup} {onmouseleave}></canvas>
+                <div class="plot-controls">
+                    <span class="plot-viewport">{ viewport }</span>
+                    <button onclick={ctx.link().callback(|_: MouseEvent| PlotAction::Zoom { factor: 1_f64 / ZOOM_FACTOR, x: 0.5_f64, y: 0.5_f64 })}>{ "+" }</button>
+                    <button onclick={ctx.link().callback(|_: MouseEvent| PlotAction::Zoom { factor: ZOOM_FACTOR, x: 0.5_f64, y: 0.5_f64 })}>{ "−" }</button>
+                    <button title="Fit the y range to the functions" onclick={ctx.link().callback(|_: MouseEvent| PlotAction::Fit)}>{ "Fit" }</button>
+                    <button onclick={ctx.link().callback(|_: MouseEvent| PlotAction::Reset)}>{ "Reset" }</button>
+                    <button onclick={ctx.link().callback(|_: MouseEvent| PlotAction::AddFunction)}>{ "Add function" }</button>
+                </div>
+                <div class="plot-functions">{ for rows }</div>
+            </div>
+        }
+    }
+}
+
+/**
+ * The position of the mouse across and down the element it's over, from 0 to 1
+ */
+fn canvas_position(e: &MouseEvent) -> (f64, f64) {
+    match e.target_dyn_into::<web_sys::Element>() {
+        Some(element) if element.client_width() > 0 && element.client_height() > 0 =>
+            (e.offset_x() as f64 / element.client_width() as f64, e.offset_y() as f64 / element.client_height() as f64),
+        _ => (0.5_f64, 0.5_f64)
+    }
+}
+
+fn stroke_line(context: &CanvasRenderingContext2d, color: &str, from: (f64, f64), to: (f64, f64)) {
+    context.set_stroke_style(&JsValue::from_str(color));
+    context.begin_path();
+    context.move_to(from.0, from.1);
+    context.line_to(to.0, to.1);
+    context.stroke();
+}
+
+/**
+ * A value rounded to 4 significant figures, to label the plot with
+ */
+fn round_label(value: f64) -> String {
+    if value == 0_f64 {
+        return String::from("0");
+    }
+    else if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let decimals = (3 - value.abs().log10().floor() as i32).max(0) as usize;
+    let rounded = format!("{value:.decimals$}");
+
+    //Trailing zeros after the decimal point aren't needed
+    if rounded.contains('.') {
+        rounded.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+    else {
+        rounded
+    }
+}